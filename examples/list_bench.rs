@@ -0,0 +1,127 @@
+// Compares `VecList` with the Rc<RefCell<..>> lists. There's no
+// benchmark crate here, and `cargo bench` would build the chapter
+// binaries, most of which only have tests. So this is an example that
+// times each operation with `Instant`, and prints the best of a few
+// runs per element:
+// cargo run --release --example list_bench
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use cracking::linked_list::{brandon, list::List, refcell, refcell_singly};
+use cracking::VecList;
+
+const ELEMS: u64 = 100_000;
+const RUNS: usize = 5;
+
+// Times `run` on a fresh value from `setup` each time, so the setup
+// isn't counted.
+fn bench<S, R>(group: &str, list: &str, mut setup: impl FnMut() -> S, mut run: impl FnMut(S) -> R) {
+    let mut best = Duration::MAX;
+    for _ in 0..RUNS {
+        let input = setup();
+        let start = Instant::now();
+        let output = run(input);
+        best = best.min(start.elapsed());
+        // Dropping the list isn't part of the benchmark either:
+        drop(black_box(output));
+    }
+    let per_elem = best.as_nanos() as f64 / ELEMS as f64;
+    println!("{:<18} {:<16} {:>8.1} ns/elem", group, list, per_elem);
+}
+
+fn filled<L: List<Elem = u64>>() -> L {
+    L::from_values(0..ELEMS)
+}
+
+// Pushing, visiting and popping through the `List` trait, which every
+// list does in O(1) per element.
+fn deque_ops<L: List<Elem = u64>>(name: &str) {
+    bench(
+        "push_front",
+        name,
+        || (),
+        |_| {
+            let mut list = L::default();
+            for i in 0..ELEMS {
+                list.push_front(black_box(i));
+            }
+            list
+        },
+    );
+    bench("visit", name, filled::<L>, |list| {
+        let mut sum = 0;
+        list.visit(|&elem| {
+            sum += elem;
+            true
+        });
+        black_box(sum);
+        list
+    });
+    bench("pop_front", name, filled::<L>, |mut list| {
+        while let Some(elem) = list.pop_front() {
+            black_box(elem);
+        }
+        list
+    });
+}
+
+// Removing every other element by handle, each in O(1).
+fn remove_by_handle() {
+    bench(
+        "remove_by_handle",
+        "vec_list",
+        || {
+            let mut list = VecList::new();
+            let indices: Vec<_> = (0..ELEMS).map(|i| list.push_back(i)).collect();
+            (list, indices)
+        },
+        |(mut list, indices)| {
+            for index in indices.into_iter().step_by(2) {
+                black_box(list.remove(index));
+            }
+            list
+        },
+    );
+    bench(
+        "remove_by_handle",
+        "refcell",
+        || {
+            let list: refcell::LinkedList<u64> = filled();
+            let nodes: Vec<_> = list.iter().collect();
+            (list, nodes)
+        },
+        |(mut list, nodes)| {
+            for node in nodes.into_iter().step_by(2) {
+                list.unlink_node(Some(node));
+            }
+            list
+        },
+    );
+    bench(
+        "remove_by_handle",
+        "refcell_singly",
+        || {
+            let mut list = refcell_singly::LinkedList::new();
+            // Prepending, since appending walks to the tail:
+            let handles: Vec<_> = (0..ELEMS).map(|i| list.prepend(i)).collect();
+            (list, handles)
+        },
+        |(mut list, handles)| {
+            // Removing a handle's element makes the next one stale, so
+            // every other one is still valid. The first handle is the
+            // tail, which is O(n) to remove, so it's skipped:
+            for handle in handles.into_iter().skip(1).step_by(2) {
+                black_box(list.remove(handle).unwrap());
+            }
+            list
+        },
+    );
+}
+
+fn main() {
+    deque_ops::<VecList<u64>>("vec_list");
+    deque_ops::<refcell::LinkedList<u64>>("refcell");
+    deque_ops::<refcell_singly::LinkedList<u64>>("refcell_singly");
+    deque_ops::<brandon::LinkedList<u64>>("brandon");
+    remove_by_handle();
+}
@@ -1,46 +1,35 @@
-use std::collections::hash_map::DefaultHasher;
-use std::collections::HashSet;
-use std::hash::Hasher;
-
 // Inspired by:
 // https://raw.githubusercontent.com/brndnmtthws/cracking-the-coding-interview-rust/master/src/bin/c02p01.rs
 // but with some improvements:
 
 //  * implements Eq and PartialEq traits, instead of a
 //  "has_duplicates" function.
-//  * uses an _unlink_node method on LinkedList, instead of on the
-//  Node struct, which is more consistent with the official library
+//  * works on any list through the `List` trait, comparing the values
+//  themselves rather than their hashes, which can collide
 
 extern crate cracking;
-use cracking::LinkedList;
+use cracking::linked_list::list::{unique, unique_in_place};
+use cracking::{LinkedList, List};
 
 pub trait RemoveDups {
     fn remove_duplicates(&mut self);
+    // FOLLOW UP: How would you solve this problem if a temporary buffer
+    // is not allowed?
+    fn remove_duplicates_without_buffer(&mut self);
 }
 
-impl<T> RemoveDups for LinkedList<T>
+// Works for every list, not just `cracking::LinkedList`:
+impl<L> RemoveDups for L
 where
-    T: std::cmp::Eq
-        + std::hash::Hash
-        + std::clone::Clone
-        + std::cmp::PartialOrd
-        + std::cmp::PartialOrd
-        + std::fmt::Debug,
+    L: List,
+    L::Elem: std::cmp::Eq + std::hash::Hash,
 {
     fn remove_duplicates(&mut self) {
-        let mut set: HashSet<u64> = HashSet::new();
-        for node in self.iter() {
-            let mut s = DefaultHasher::new();
-            {
-                let data = &node.borrow().data;
-                data.hash(&mut s);
-            }
-            let hash = s.finish();
-            if set.contains(&hash) {
-                self.unlink_node(Some(node));
-            }
-            set.insert(hash);
-        }
+        unique(self)
+    }
+
+    fn remove_duplicates_without_buffer(&mut self) {
+        unique_in_place(self)
     }
 }
 
@@ -64,6 +53,30 @@ mod tests {
         list1.remove_duplicates();
 
         assert_eq!(list1, list2);
+
+        list1.append(String::from("item2"));
+        list1.append(String::from("item1"));
+        list1.remove_duplicates_without_buffer();
+        assert_eq!(list1, list2);
+    }
+
+    #[test]
+    fn remove_duplicates_from_any_list() {
+        use cracking::linked_list::list::remove_dups;
+        use cracking::linked_list::{fifth, second};
+        use cracking::List;
+
+        let mut list = second::List::from_values(vec!["a", "b", "a", "c", "b"]);
+        remove_dups(&mut list);
+        assert_eq!(list.to_vec(), vec!["a", "b", "c"]);
+
+        let mut list = fifth::List::from_values(vec![1, 1, 1]);
+        remove_dups(&mut list);
+        assert_eq!(list.to_vec(), vec![1]);
+
+        let mut list = LinkedList::from_values(vec![3, 1, 3]);
+        remove_dups(&mut list);
+        assert!(list.list_eq(&second::List::from_values(vec![3, 1])));
     }
 }
 
@@ -2,7 +2,7 @@ extern crate cracking;
 use cracking::LinkedList;
 
 pub trait KthToLast<T> {
-    fn kth_to_last(&self, k: usize) -> T;
+    fn kth_to_last(&self, k: usize) -> Option<T>;
 }
 
 impl<T> KthToLast<T> for LinkedList<T>
@@ -14,7 +14,8 @@ where
         + std::cmp::PartialOrd
         + std::fmt::Debug,
 {
-    fn kth_to_last(&self, k: usize) -> T {
+    // None if there are k or fewer elements.
+    fn kth_to_last(&self, k: usize) -> Option<T> {
         // use "tortoise/hare" method:
         let mut kth_last_node = None; // our 'tortoise'
         for (n, _) in self.iter().enumerate() {
@@ -25,7 +26,7 @@ where
                 kth_last_node = kth_last_node.unwrap().borrow().next.clone();
             }
         }
-        kth_last_node.unwrap().borrow().data.clone()
+        kth_last_node.map(|node| node.borrow().data.clone())
     }
 }
 
@@ -41,9 +42,41 @@ mod tests {
         list.append(3);
         list.append(4);
 
-        assert_eq!(list.kth_to_last(1), 3);
-        assert_eq!(list.kth_to_last(2), 2);
-        assert_eq!(list.kth_to_last(0), 4);
+        assert_eq!(list.kth_to_last(1), Some(3));
+        assert_eq!(list.kth_to_last(2), Some(2));
+        assert_eq!(list.kth_to_last(0), Some(4));
+        assert_eq!(list.kth_to_last(4), None);
+        assert_eq!(LinkedList::<usize>::new().kth_to_last(0), None);
+    }
+
+    #[test]
+    fn nth_from_back() {
+        use cracking::linked_list::second;
+        use cracking::{List, SinglyLinkedList};
+
+        let list = second::List::from_values(vec![1, 2, 3, 4]);
+        assert_eq!(list.nth_from_back(1), Some(&3));
+        assert_eq!(list.nth_from_back(4), None);
+
+        let list = SinglyLinkedList::from_values(vec![1, 2, 3, 4]);
+        assert_eq!(list.nth_from_back(3).map(|node| node.borrow().data), Some(1));
+        assert!(list.nth_from_back(4).is_none());
+    }
+
+    #[test]
+    fn kth_to_last_of_any_list() {
+        use cracking::linked_list::list::kth_to_last;
+        use cracking::linked_list::{fourth, third};
+        use cracking::List;
+
+        let list = third::List::from_values(vec![1, 2, 3, 4]);
+        assert_eq!(kth_to_last(&list, 0), Some(4));
+        assert_eq!(kth_to_last(&list, 3), Some(1));
+        assert_eq!(kth_to_last(&list, 4), None);
+
+        let list = fourth::List::from_values(vec![1, 2, 3, 4]);
+        assert_eq!(kth_to_last(&list, 1), Some(3));
+        assert_eq!(kth_to_last(&LinkedList::<u32>::default(), 0), None);
     }
 }
 
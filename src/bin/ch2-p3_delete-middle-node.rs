@@ -7,224 +7,64 @@
 // Result: nothing is returned, but the new linked list looks like
 // a->b->d->e->f
 
-use std::cell::RefCell;
-use std::fmt;
-use std::fmt::Display;
-use std::rc::Rc;
+extern crate cracking;
+use cracking::{NodeHandle, SinglyLinkedList as LinkedList, StaleHandle};
 
-type NodeRef<T> = Rc<RefCell<Node<T>>>;
-
-#[derive(Debug)]
-struct LinkedList<T> {
-    head: Option<NodeRef<T>>,
-}
-
-struct Node<T> {
-    data: T,
-    next: Option<NodeRef<T>>,
+pub trait DeleteMiddleNode<T> {
+    fn delete_middle_node(&mut self, node: NodeHandle<T>) -> Result<T, StaleHandle>;
 }
 
-impl<T: fmt::Debug> fmt::Debug for Node<T> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Node {{ data: {:?}, next: {:?} }}", self.data, self.next)
-    }
-}
-
-impl<T> LinkedList<T>
+impl<T> DeleteMiddleNode<T> for LinkedList<T>
 where
-    T: std::cmp::Eq,
-    T: std::hash::Hash,
-    T: std::clone::Clone,
+    T: std::cmp::Eq + std::hash::Hash + std::clone::Clone + std::cmp::PartialOrd + std::fmt::Debug,
 {
-    fn new() -> Self {
-        Self { head: None }
-    }
-
-    fn append(&mut self, new_value: T) {
-        if let Some(tail) = self.tail() {
-            tail.borrow_mut().next = Some(Rc::new(RefCell::new(Node {
-                data: new_value,
-                next: None,
-            })));
-        } else {
-            self.head = Some(Rc::new(RefCell::new(Node {
-                data: new_value,
-                next: None,
-            })));
-        }
-    }
-
-    fn tail(&self) -> Option<NodeRef<T>> {
-        for node in self.iter() {
-            if let None = node.borrow().next {
-                return Some(node.clone());
-            }
-        }
-        None
-    }
-
-    /// Warning: this will not check that the provided node belongs to the current list.
-    fn _unlink_node(&mut self, node_to_remove: Option<NodeRef<T>>) {
-        let node_to_remove = node_to_remove.unwrap();
-
-        for n in self.iter() {
-            let mut borrowed_node = n.borrow_mut();
-            if let Some(next) = borrowed_node.next.clone() {
-                if Rc::ptr_eq(&next, &node_to_remove) {
-                    borrowed_node.next = node_to_remove.borrow_mut().next.take();
-                    break;
-                }
-            } else if Rc::ptr_eq(&n, &node_to_remove) {
-                // handle case when node_to_remove is the only element
-                // in the list
-                self.head = None;
-            }
-        }
+    // The handle is all the access we get to the node. There's no way
+    // back to the node before it, so `remove` copies the next node's
+    // data into this one, and unlinks the next node instead: O(1).
+    fn delete_middle_node(&mut self, node: NodeHandle<T>) -> Result<T, StaleHandle> {
+        self.remove(node)
     }
-
-    fn iter(&self) -> Iter<T> {
-        Iter {
-            next: self.head.as_ref().cloned(),
-        }
-    }
-
-    fn delete_middle_node(&mut self) {
-        let mut middle_node: Option<NodeRef<T>> = None;
-        for (n, _) in self.iter().enumerate() {
-            // using "tortoise/hare" method:
-            // middle_node is the tortoise
-            // _ is the hare
-            if n % 2 == 0 {
-                if let Some(node) = middle_node {
-                    middle_node = node.borrow().next.clone();
-                } else {
-                    middle_node = self.head.clone();
-                }
-            }
-        }
-        // delete the middle node:
-        if middle_node.is_some() {
-            self._unlink_node(middle_node);
-        }
-    }
-}
-
-struct Iter<T> {
-    next: Option<NodeRef<T>>,
 }
 
-impl<'a, T> Iterator for Iter<T> {
-    type Item = NodeRef<T>;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        if let Some(cur) = self.next.as_ref().cloned() {
-            self.next = cur.borrow().next.clone();
-            return Some(cur.clone());
-        }
-        None
-    }
-}
-
-impl<T: Display> Display for LinkedList<T> {
-    fn fmt(&self, w: &mut std::fmt::Formatter) -> std::result::Result<(), std::fmt::Error> {
-        write!(w, "[")?;
-        let mut node = self.head.clone();
-        while let Some(n) = node {
-            write!(w, "{}", n.borrow().data)?;
-            node = n.borrow().next.clone();
-            if node.is_some() {
-                write!(w, ", ")?;
-            }
-        }
-        write!(w, "]")
-    }
-}
-
-impl<T: PartialEq + fmt::Debug> PartialEq for Node<T> {
-    fn eq(&self, other: &Self) -> bool {
-        println!(
-            "comparing two nodes: self.head: {:?}, other.head: {:?}",
-            self, other
-        );
-        self.data == other.data && self.next == other.next
-    }
-
-    fn ne(&self, other: &Self) -> bool {
-        println!(
-            "comparing two nodes: self.head: {:?}, other.head: {:?}",
-            self, other
-        );
-        self.data != other.data && self.next == other.next
-    }
-}
-
-impl<T: PartialEq + std::fmt::Debug> PartialEq for LinkedList<T> {
-    fn eq(&self, other: &Self) -> bool {
-        println!(
-            "comparing two lists: self.head: {:?}, other.head: {:?}",
-            self.head, other.head
-        );
-        self.head == other.head
-    }
-
-    fn ne(&self, other: &Self) -> bool {
-        println!(
-            "comparing two lists: self.head: {:?}, other.head: {:?}",
-            self.head, other.head
-        );
-        self.head != other.head
-    }
-}
-
-impl<T: Eq + std::fmt::Debug> Eq for LinkedList<T> {}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn delete_middle_node() {
-        let mut list1 = LinkedList::<usize>::new();
-        list1.append(1);
-        list1.append(2);
-        list1.append(3);
-        list1.append(4);
-
-        let mut list2 = LinkedList::<usize>::new();
-        list2.append(1);
-        list2.append(3);
-        list2.append(4);
-
-        list1.delete_middle_node();
-
-        assert_eq!(list1, list2);
-
-        let mut list1 = LinkedList::<usize>::new();
-        list1.append(1);
-
-        let list2 = LinkedList::<usize>::new();
-
-        list1.delete_middle_node();
-
-        assert_eq!(list1, list2);
-
-        let mut list1 = LinkedList::<usize>::new();
-        list1.append(1);
-        list1.append(2);
-        list1.append(3);
-
-        let mut list2 = LinkedList::<usize>::new();
-        list2.append(1);
-        list2.append(3);
-
-        list1.delete_middle_node();
+        let mut list = LinkedList::new();
+        let mut handles: Vec<_> = "abcdef".chars().map(|c| list.append(c).unwrap()).collect();
+        let c = handles.remove(2);
+        assert_eq!(list.delete_middle_node(c), Ok('c'));
+        assert_eq!(list.to_string(), "[a, b, d, e, f]");
+
+        // d's data is now in c's old node, so its handle is stale:
+        let d = handles.remove(2);
+        assert_eq!(list.delete_middle_node(d), Err(StaleHandle));
+        assert_eq!(list.to_string(), "[a, b, d, e, f]");
+
+        let b = handles.remove(1);
+        assert_eq!(list.delete_middle_node(b), Ok('b'));
+        assert_eq!(list.to_string(), "[a, d, e, f]");
+    }
 
-        assert_eq!(list1, list2);
+    #[test]
+    fn delete_first_and_last_node() {
+        let mut list = LinkedList::new();
+        let one = list.append(1).unwrap();
+        list.append(2).unwrap();
+        let three = list.append(3).unwrap();
+        assert_eq!(list.delete_middle_node(three), Ok(3));
+        assert_eq!(list.delete_middle_node(one), Ok(1));
+        assert_eq!(list.to_string(), "[2]");
     }
 }
 
 fn main() {
     let mut list = LinkedList::<String>::new();
-    list.append(String::from("item1"));
-    list.append(String::from("item2"));
+    list.append(String::from("item1")).unwrap();
+    let middle = list.append(String::from("item2")).unwrap();
+    list.append(String::from("item3")).unwrap();
+    list.delete_middle_node(middle).unwrap();
+    println!("{}", list);
 }
@@ -21,52 +21,35 @@
 // Output: (0 -) 0 -) 0 -) 1). That is, 1000.
 
 extern crate cracking;
-use cracking::LinkedList;
+use cracking::{DigitList, DigitOrder, LinkedList};
 
 pub trait SumLists<T> {
     fn sum_lists(&self, other: &LinkedList<T>) -> LinkedList<T>;
     fn sum_lists_reversed(&self, other: &LinkedList<T>) -> LinkedList<T>;
 }
 
-// Assumes that both lists are of the same size.
+// The lists can be of different lengths. Panics if a list is empty, or
+// holds something that isn't a digit.
+fn sum_digit_lists(a: &LinkedList<i32>, b: &LinkedList<i32>, order: DigitOrder) -> LinkedList<i32> {
+    let to_digit_list = |list: &LinkedList<i32>| {
+        let digits = list.iter().map(|node| node.borrow().data);
+        DigitList::from_digits(digits, order).expect("not a list of digits")
+    };
+    let sum = &to_digit_list(a) + &to_digit_list(b);
+    let mut new_list = LinkedList::new();
+    for digit in sum.digits() {
+        new_list.append(digit);
+    }
+    new_list
+}
+
 impl SumLists<i32> for LinkedList<i32> {
     fn sum_lists(&self, other: &LinkedList<i32>) -> LinkedList<i32> {
-        let mut new_list = LinkedList::new();
-        let mut overflow = 0;
-        for (node, other_node) in self.iter().zip(other.iter()) {
-            let this_data = node.borrow().data;
-            let other_data = other_node.borrow().data;
-            println!("node: {:?}, other_node: {:?}", this_data, other_data);
-            let digits = (this_data + other_data + overflow) % 10;
-            overflow = (this_data + other_data + overflow) / 10;
-            println!("digits: {}, overflow: {}", digits, overflow);
-            new_list.append(digits);
-        }
-        // Add on an extra node, if there is still an overflow:
-        if overflow > 0 {
-            new_list.append(overflow);
-        }
-        new_list
+        sum_digit_lists(self, other, DigitOrder::LeastSignificantFirst)
     }
 
     fn sum_lists_reversed(&self, other: &LinkedList<i32>) -> LinkedList<i32> {
-        let mut new_list = LinkedList::new();
-        let mut overflow = 0;
-
-        let self_digits = self.iter().map(|node| node.borrow().data).collect::<Vec<i32>>();
-        let other_digits = other.iter().map(|node| node.borrow().data).collect::<Vec<i32>>();
-        for (this_data, other_data) in self_digits.iter().rev().zip(other_digits.iter().rev()) {
-            println!("node: {:?}, other_node: {:?}", this_data, other_data);
-            let digits = (this_data + other_data + overflow) % 10;
-            overflow = (this_data + other_data + overflow) / 10;
-            println!("digits: {}, overflow: {}", digits, overflow);
-            new_list.prepend(digits);
-        }
-        // Add on an extra node, if there is still an overflow:
-        if overflow > 0 {
-            new_list.prepend(overflow);
-        }
-        new_list
+        sum_digit_lists(self, other, DigitOrder::MostSignificantFirst)
     }
 }
 
@@ -160,6 +143,27 @@ mod test {
 
         assert_eq!(list.sum_lists_reversed(&list2), sum_list);
     }
+
+    #[test]
+    fn add_lists_of_different_lengths() {
+        let list_of = |digits: &[i32]| {
+            let mut list = LinkedList::new();
+            for digit in digits.iter() {
+                list.append(*digit);
+            }
+            list
+        };
+        // 9999 + 1 = 10000
+        assert_eq!(
+            list_of(&[9, 9, 9, 9]).sum_lists(&list_of(&[1])),
+            list_of(&[0, 0, 0, 0, 1])
+        );
+        // 7 + 995 = 1002
+        assert_eq!(
+            list_of(&[7]).sum_lists_reversed(&list_of(&[9, 9, 5])),
+            list_of(&[1, 0, 0, 2])
+        );
+    }
 }
 
 fn main() {
@@ -114,6 +114,35 @@ mod test {
         assert!(!list.is_palindrome_iterative());
     }
 
+    #[test]
+    fn check_palindrome_any_list() {
+        use cracking::linked_list::list;
+        use cracking::linked_list::{fifth, second};
+        use cracking::List;
+
+        assert!(list::is_palindrome(&second::List::from_values("abcba".chars())));
+        assert!(!list::is_palindrome(&second::List::from_values("abca".chars())));
+        assert!(list::is_palindrome(&fifth::List::from_values("abba".chars())));
+        assert!(list::is_palindrome(&LinkedList::from_values("a".chars())));
+    }
+
+    #[test]
+    fn check_palindrome_in_place() {
+        // Without a Vec or a stack: these reverse the back half of the
+        // list to compare it, and then reverse it back.
+        use cracking::linked_list::second;
+        use cracking::{List, SinglyLinkedList};
+
+        let mut list = SinglyLinkedList::from_values("abcba".chars());
+        assert!(list.is_palindrome().unwrap());
+        assert_eq!(list.to_string(), "[a, b, c, b, a]");
+        let mut list = SinglyLinkedList::from_values("abca".chars());
+        assert!(!list.is_palindrome().unwrap());
+
+        let mut list = second::List::from_values("abba".chars());
+        assert!(list.is_palindrome());
+        assert_eq!(list.to_vec(), vec!['a', 'b', 'b', 'a']);
+    }
 }
 
 fn main() {
@@ -8,7 +8,7 @@
 
 
 extern crate cracking;
-use cracking::{SinglyLinkedList as LinkedList, Node};
+use cracking::{SinglyLinkedList as LinkedList, Node, NodeRef};
 
 use std::rc::Rc;
 use std::cell::RefCell;
@@ -16,6 +16,7 @@ use std::cell::RefCell;
 pub trait CheckIntersection<T> {
     fn is_intersection(&self, other: &LinkedList<T>) -> bool;
     fn is_intersection_iterative(&self, other: &LinkedList<T>) -> bool;
+    fn intersecting_node(&self, other: &LinkedList<T>) -> Option<NodeRef<T>>;
 }
 
 impl<T> CheckIntersection<T> for LinkedList<T>
@@ -31,7 +32,7 @@ where
     // then the tails will be the same node.
     // Takes O(A+B) time, where A and B are lengths of the lists.
     fn is_intersection(&self, other: &LinkedList<T>) -> bool {
-        if let (Some(self_tail), Some(other_tail)) = (self.tail(), other.tail()) {
+        if let (Ok(Some(self_tail)), Ok(Some(other_tail))) = (self.tail(), other.tail()) {
             Rc::ptr_eq(&self_tail, &other_tail)
         } else {
             false
@@ -49,6 +50,11 @@ where
         }
         false
     }
+    // The node itself, which the library finds by lining up the two
+    // lists from their ends, in O(A+B) time and O(1) space.
+    fn intersecting_node(&self, other: &LinkedList<T>) -> Option<NodeRef<T>> {
+        LinkedList::intersection_node(self, other).map(|found| found.node)
+    }
 }
 
 
@@ -58,22 +64,22 @@ mod test {
     #[test]
     fn check_intersection_node() {
         let mut list1 = LinkedList::new();
-        list1.append(3);
-        list1.append(2);
-        list1.append(1);
+        list1.append(3).unwrap();
+        list1.append(2).unwrap();
+        list1.append(1).unwrap();
         let mut list2 = LinkedList::new();
-        list2.append(3);
-        list2.append(2);
-        list2.append(1);
+        list2.append(3).unwrap();
+        list2.append(2).unwrap();
+        list2.append(1).unwrap();
         assert_ne!(list1.is_intersection(&list2), true);
     }
 
     #[test]
     fn check_no_intersection() {
         let mut list1 = LinkedList::new();
-        list1.append(3);
+        list1.append(3).unwrap();
         let mut list2 = LinkedList::new();
-        list2.append(3);
+        list2.append(3).unwrap();
         assert_eq!(list1.is_intersection(&list2), false);
     }
 
@@ -81,9 +87,9 @@ mod test {
     fn check_single_intersection() {
         let common_node = Rc::new(RefCell::new(Node{ data: 9, next: None}));
         let mut list1 = LinkedList::new();
-        list1.append_node(common_node.clone());
+        list1.append_node(common_node.clone()).unwrap();
         let mut list2 = LinkedList::new();
-        list2.append_node(common_node);
+        list2.append_node(common_node).unwrap();
         assert_eq!(list1.is_intersection(&list2), true);
     }
 
@@ -91,27 +97,43 @@ mod test {
     fn check_intersection() {
         let common_node = Rc::new(RefCell::new(Node{ data: 9, next: None}));
         let mut list1 = LinkedList::new();
-        list1.append(3);
-        list1.append(2);
-        list1.append_node(common_node.clone());
-        list1.append(1);
+        list1.append(3).unwrap();
+        list1.append(2).unwrap();
+        list1.append_node(common_node.clone()).unwrap();
+        list1.append(1).unwrap();
         let mut list2 = LinkedList::new();
-        list1.append(3);
-        list1.append(2);
+        list1.append(3).unwrap();
+        list1.append(2).unwrap();
         assert_eq!(list1.is_intersection(&list2), false);
-        list2.append_node(common_node);
+        list2.append_node(common_node).unwrap();
         assert_eq!(list1.is_intersection(&list2), true);
-        list1.append(2);
-        list2.append(2);
+        list1.append(2).unwrap();
+        list2.append(2).unwrap();
         assert_eq!(list1.is_intersection(&list2), true);
     }
 
+    #[test]
+    fn check_intersecting_node() {
+        let common_node = Rc::new(RefCell::new(Node{ data: 9, next: None}));
+        let mut list1 = LinkedList::new();
+        list1.append(3).unwrap();
+        list1.append(2).unwrap();
+        list1.append_node(common_node.clone()).unwrap();
+        list1.append(1).unwrap();
+        let mut list2 = LinkedList::new();
+        list2.append(9).unwrap();
+        assert!(list1.intersecting_node(&list2).is_none());
+        list2.append_node(common_node.clone()).unwrap();
+        let found = list1.intersecting_node(&list2).unwrap();
+        assert!(Rc::ptr_eq(&found, &common_node));
+    }
+
     #[test]
     fn check_no_intersection_iterative() {
         let mut list1 = LinkedList::new();
-        list1.append(3);
+        list1.append(3).unwrap();
         let mut list2 = LinkedList::new();
-        list2.append(3);
+        list2.append(3).unwrap();
         assert_eq!(list1.is_intersection_iterative(&list2), false);
     }
 
@@ -119,9 +141,9 @@ mod test {
     fn check_single_intersection_iterative() {
         let common_node = Rc::new(RefCell::new(Node{ data: 9, next: None}));
         let mut list1 = LinkedList::new();
-        list1.append_node(common_node.clone());
+        list1.append_node(common_node.clone()).unwrap();
         let mut list2 = LinkedList::new();
-        list2.append_node(common_node);
+        list2.append_node(common_node).unwrap();
         assert_eq!(list1.is_intersection_iterative(&list2), true);
     }
 
@@ -129,18 +151,18 @@ mod test {
     fn check_intersection_iterative() {
         let common_node = Rc::new(RefCell::new(Node{ data: 9, next: None}));
         let mut list1 = LinkedList::new();
-        list1.append(3);
-        list1.append(2);
-        list1.append_node(common_node.clone());
-        list1.append(1);
+        list1.append(3).unwrap();
+        list1.append(2).unwrap();
+        list1.append_node(common_node.clone()).unwrap();
+        list1.append(1).unwrap();
         let mut list2 = LinkedList::new();
-        list1.append(3);
-        list1.append(2);
+        list1.append(3).unwrap();
+        list1.append(2).unwrap();
         assert_eq!(list1.is_intersection_iterative(&list2), false);
-        list2.append_node(common_node);
+        list2.append_node(common_node).unwrap();
         assert_eq!(list1.is_intersection_iterative(&list2), true);
-        list1.append(2);
-        list2.append(2);
+        list1.append(2).unwrap();
+        list2.append(2).unwrap();
         assert_eq!(list1.is_intersection_iterative(&list2), true);
     }
 }
@@ -19,6 +19,12 @@ pub trait LoopDetection<T> {
     fn detect_loop_with_set(&self) -> Option<NodeRef<T>>;
 }
 
+// Follows the links by hand, since `iter` already stops at a cycle's
+// entry, which would give the answer away.
+fn links<T>(list: &LinkedList<T>) -> impl Iterator<Item = NodeRef<T>> {
+    std::iter::successors(list.head.clone(), |node| node.borrow().next.clone())
+}
+
 impl<T> LoopDetection<T> for LinkedList<T>
 where
     T: std::cmp::Eq
@@ -29,8 +35,8 @@ where
         + std::fmt::Debug,
 {
     fn detect_loop(&self) -> Option<NodeRef<T>> {
-        let mut slow_iter = self.iter();
-        let mut fast_iter = self.iter();
+        let mut slow_iter = links(self);
+        let mut fast_iter = links(self);
         while let (Some(slow), Some(_)) = (slow_iter.next(), fast_iter.next()) {
             // fast_iter moves 2 steps per iteration:
             match fast_iter.next() {
@@ -59,7 +65,7 @@ where
         // of nodes away from the loop start as the head, so let's set
         // a new pointer to the head, and move along both iterators
         // until they collide again at the start of the loop.
-        slow_iter = self.iter();
+        slow_iter = links(self);
         while let (Some(slow), Some(fast)) = (slow_iter.next(), fast_iter.next()) {
             if Rc::ptr_eq(&slow, &fast) {
                 // This is the starting node of our loop.
@@ -71,7 +77,7 @@ where
 
     fn detect_loop_with_set(&self) -> Option<NodeRef<T>> {
         let mut visited_nodes: HashSet<HashedNode<T>> = HashSet::new();
-        for node in self.iter() {
+        for node in links(self) {
             if visited_nodes.contains(&HashedNode::from_node(node.clone())) {
                 return Some(node);
             } else {
@@ -95,9 +101,9 @@ mod test {
     #[test]
     fn detect_loop_none() {
         let mut list = LinkedList::new();
-        list.append(1);
-        list.append(2);
-        list.append(3);
+        list.append(1).unwrap();
+        list.append(2).unwrap();
+        list.append(3).unwrap();
         assert_eq!(list.detect_loop(), None);
         assert_eq!(list.detect_loop_with_set(), None);
     }
@@ -105,7 +111,7 @@ mod test {
     #[test]
     fn detect_loop_none_short() {
         let mut list: LinkedList<i32> = LinkedList::new();
-        list.append(1);
+        list.append(1).unwrap();
         assert_eq!(list.detect_loop(), None);
         assert_eq!(list.detect_loop_with_set(), None);
     }
@@ -117,11 +123,11 @@ mod test {
             next: None,
         }));
         let mut list: LinkedList<i32> = LinkedList::new();
-        list.append_node(node.clone());
-        list.append(1);
-        list.append(2);
-        list.append(3);
-        list.append_node(node.clone());
+        list.append_node(node.clone()).unwrap();
+        list.append(1).unwrap();
+        list.append(2).unwrap();
+        list.append(3).unwrap();
+        list.append_node(node.clone()).unwrap();
         assert!(Rc::ptr_eq(&list.detect_loop().unwrap(), &node));
         assert!(Rc::ptr_eq(&list.detect_loop_with_set().unwrap(), &node));
     }
@@ -133,12 +139,12 @@ mod test {
             next: None,
         }));
         let mut list: LinkedList<i32> = LinkedList::new();
-        list.append_node(node.clone());
-        list.append(1);
-        list.append(2);
-        list.append(3);
-        list.append(4);
-        list.append_node(node.clone());
+        list.append_node(node.clone()).unwrap();
+        list.append(1).unwrap();
+        list.append(2).unwrap();
+        list.append(3).unwrap();
+        list.append(4).unwrap();
+        list.append_node(node.clone()).unwrap();
         assert!(Rc::ptr_eq(&list.detect_loop().unwrap(), &node));
         assert!(Rc::ptr_eq(&list.detect_loop_with_set().unwrap(), &node));
     }
@@ -150,17 +156,21 @@ mod test {
             next: None,
         }));
         let mut list: LinkedList<i32> = LinkedList::new();
-        list.append(1);
-        list.append(2);
-        list.append(3);
-        list.append_node(node.clone());
-        list.append(4);
-        list.append(5);
-        list.append(6);
-        list.append(7);
-        list.append(8);
-        list.append_node(node.clone());
+        list.append(1).unwrap();
+        list.append(2).unwrap();
+        list.append(3).unwrap();
+        list.append_node(node.clone()).unwrap();
+        list.append(4).unwrap();
+        list.append(5).unwrap();
+        list.append(6).unwrap();
+        list.append(7).unwrap();
+        list.append(8).unwrap();
+        list.append_node(node.clone()).unwrap();
         assert!(Rc::ptr_eq(&list.detect_loop().unwrap(), &node));
         assert!(Rc::ptr_eq(&list.detect_loop_with_set().unwrap(), &node));
+        // The library finds the same entry without a set:
+        let info = list.loop_info().unwrap();
+        assert!(Rc::ptr_eq(&info.entry, &node));
+        assert_eq!((info.tail_len, info.loop_len), (3, 6));
     }
 }
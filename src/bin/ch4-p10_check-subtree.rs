@@ -104,6 +104,30 @@ mod tests {
         assert_eq!(is_subtree_string(&bt.root, &bt_sub2.root), false);
     }
 
+    #[test]
+    fn test_tree_in_subtree_from_fixtures() {
+        use cracking::{SerializeTree, TreeFormat};
+        let bt = BinaryTree::<u32>::deserialize("[1,2,1,null,null,1]", TreeFormat::LevelOrder).unwrap();
+        let bt_sub = BinaryTree::<u32>::deserialize("1,1,#,#,#", TreeFormat::Preorder).unwrap();
+        let bt_sub2 = BinaryTree::<u32>::deserialize("[1,1,1]", TreeFormat::LevelOrder).unwrap();
+        assert!(is_subtree(&bt.root, &bt_sub.root));
+        assert!(is_subtree_string(&bt.root, &bt_sub.root));
+        assert!(!is_subtree(&bt.root, &bt_sub2.root));
+        assert!(!is_subtree_string(&bt.root, &bt_sub2.root));
+    }
+
+    #[test]
+    fn test_tree_in_subtree_linear() {
+        // The library version runs in O(n + m), by giving each
+        // distinct subtree an id:
+        use cracking::{find_subtrees, SerializeTree, TreeFormat, TreeRoot};
+        let bt = BinaryTree::<u32>::deserialize("[1,2,1,1,null,1]", TreeFormat::LevelOrder).unwrap();
+        let bt_sub = BinaryTree::<u32>::deserialize("[1,1]", TreeFormat::LevelOrder).unwrap();
+        assert!(is_subtree(&bt.root, &bt_sub.root));
+        assert!(cracking::is_subtree(bt.root_node(), bt_sub.root_node()));
+        assert_eq!(find_subtrees(bt.root_node(), bt_sub.root_node()).len(), 1);
+    }
+
     #[test]
     fn test_tree_in_subtree_alt() {
         let root = Node::new(
@@ -152,3 +152,16 @@ fn test_count_paths_complex() {
     assert_eq!(count_paths(&node_fixture, 8), 3);
     assert_eq!(count_paths_optimized(&node_fixture, 8), 3);
 }
+
+#[test]
+fn test_count_paths_without_overflow() {
+    use cracking::{count_paths_with_sum, BinaryTree, TreeRoot};
+    // The running sums here don't fit in an i8:
+    let bt = BinaryTree::new(Node::<i8>::new(
+        100,
+        Node::new(100, Node::new(-100, None, None), None),
+        Node::new(27, None, None),
+    ));
+    assert_eq!(count_paths_with_sum(bt.root_node(), 127), 1);
+    assert_eq!(count_paths_with_sum(bt.root_node(), 100), 3);
+}
@@ -5,12 +5,13 @@
 // - how to handle duplicate values?
 // - does the tree only contain numbers?
 
-use cracking::{BinarySearchTree, Tree, TreeNode};
+use cracking::{BinarySearchTree, Duplicates, TreeNode, ValidateBst};
 
+// `BinarySearchTree::add` puts duplicate values on the left, so that's
+// the policy to validate against. The library version reports which
+// node is out of order, and which ancestor's bound it violates.
 trait ValidateBST<T> {
     fn validate_bst(&self) -> bool;
-
-    fn _is_tree_node_valid(&self, node: &Tree<T>, min: Option<T>, max: Option<T>) -> bool;
 }
 
 impl<T> ValidateBST<T> for BinarySearchTree<T>
@@ -18,69 +19,7 @@ where
     T: std::cmp::PartialOrd + std::clone::Clone + std::fmt::Debug,
 {
     fn validate_bst(&self) -> bool {
-        self._is_tree_node_valid(&self.root, None, None)
-    }
-
-    fn _is_tree_node_valid(&self, node: &Tree<T>, min: Option<T>, max: Option<T>) -> bool {
-        // A BST is valid if its left and right subtrees are valid,
-        // meaning that if the max value of the left subtree is less
-        // than or equal to the current value, and the min value of
-        // the left subtree is greater than the current
-
-        // Returns the min/max values of this node's subtree, or None
-        // if this node's subtree is invalid.
-
-        if let Some(n) = node {
-            // check whether the left subtree is valid:
-            if let Some(minimum) = min.clone() {
-                if n.data <= minimum {
-                    return false;
-                }
-            }
-            if !self._is_tree_node_valid(&n.left, min, Some(n.data.clone())) {
-                return false;
-            }
-
-            // check whether the right subtree is valid:
-            if let Some(maximum) = max.clone() {
-                if n.data > maximum {
-                    return false;
-                }
-            }
-            if !self._is_tree_node_valid(&n.right, Some(n.data.clone()), max) {
-                return false;
-            }
-
-            true
-        } else {
-            // We are at a leaf node:
-            true
-        }
-    }
-}
-
-// A simpler version, but limited to a u32:
-trait ValidateBSTU32 {
-    fn validate_bst_u32(&self) -> bool;
-
-    fn _is_tree_node_valid_u32(&self, node: &Tree<u32>, min: u32, max: u32) -> bool;
-}
-
-impl ValidateBSTU32 for BinarySearchTree<u32> {
-    fn validate_bst_u32(&self) -> bool {
-        self._is_tree_node_valid_u32(&self.root, u32::min_value(), u32::max_value())
-    }
-
-    fn _is_tree_node_valid_u32(&self, node: &Tree<u32>, min: u32, max: u32) -> bool {
-        if let Some(n) = node {
-            if n.data < min || max <= n.data {
-                return false;
-            }
-
-            return self._is_tree_node_valid_u32(&n.left, min, n.data + 1)
-                && self._is_tree_node_valid_u32(&n.right, n.data, max);
-        }
-        true
+        self.validate(Duplicates::Left).is_ok()
     }
 }
 
@@ -94,7 +33,6 @@ mod tests {
         root.set_right(TreeNode::new_node(3));
         let t = BinarySearchTree::<u32>::new().with_root(root).create();
         assert_eq!(t.validate_bst(), true);
-        assert_eq!(t.validate_bst_u32(), true);
     }
 
     #[test]
@@ -104,7 +42,6 @@ mod tests {
         root.set_right(TreeNode::new_node(3));
         let t = BinarySearchTree::<u32>::new().with_root(root).create();
         assert_eq!(t.validate_bst(), false);
-        assert_eq!(t.validate_bst_u32(), false);
     }
 
     #[test]
@@ -118,7 +55,6 @@ mod tests {
         t.add(9);
         t.add(7);
         assert_eq!(t.validate_bst(), true);
-        assert_eq!(t.validate_bst_u32(), true);
     }
 
     #[test]
@@ -132,7 +68,6 @@ mod tests {
         t.add(6);
         t.add(7);
         assert_eq!(t.validate_bst(), true);
-        assert_eq!(t.validate_bst_u32(), true);
     }
 
     #[test]
@@ -158,7 +93,6 @@ mod tests {
         t.add(16);
         t.add(14);
         assert_eq!(t.validate_bst(), true);
-        assert_eq!(t.validate_bst_u32(), true);
     }
 
     #[test]
@@ -173,7 +107,6 @@ mod tests {
         root.set_right(root_right);
         let t = BinarySearchTree::<u32>::new().with_root(root).create();
         assert_eq!(t.validate_bst(), false);
-        assert_eq!(t.validate_bst_u32(), false);
     }
 
     #[test]
@@ -188,6 +121,18 @@ mod tests {
         root.set_right(root_right);
         let t = BinarySearchTree::<u32>::new().with_root(root).create();
         assert_eq!(t.validate_bst(), false);
-        assert_eq!(t.validate_bst_u32(), false);
+    }
+
+    #[test]
+    fn invalid_tree_report() {
+        let mut root = TreeNode::<u32>::new_node(3);
+        let mut root_left = TreeNode::<u32>::new_node(2);
+        root_left.set_right(TreeNode::new_node(4)); // this is greater than root!
+        root.set_left(root_left);
+        let t = BinarySearchTree::<u32>::new().with_root(root).create();
+        assert_eq!(
+            t.validate(Duplicates::Left).unwrap_err().to_string(),
+            "node 4 at root.left.right is above its upper bound: must be <= 3"
+        );
     }
 }
@@ -32,7 +32,7 @@ where
         if lowest_right.is_some() {
             lowest_right
         } else {
-            if let Some(p) = &n.borrow().parent {
+            if let Some(p) = &n.borrow().parent() {
                 // Check whether this node is the left or right child of the parent.
                 let parent = p.borrow().clone();
                 if let Some(parent_left_child) = parent.left {
@@ -25,15 +25,15 @@ impl<T> FirstCommonAncestor<T> for BinaryTree<T> {
         for _ in 0..abs_diff {
             // Move the lower node up until it's at the same level
             if let Some(node) = lower_node {
-                lower_node = node.borrow().parent.clone();
+                lower_node = node.borrow().parent();
             }
         }
         while let (Some(lower), Some(higher)) = (lower_node, higher_node) {
             if Rc::ptr_eq(&lower, &higher) {
                 return Some(lower);
             } else {
-                lower_node = lower.borrow().parent.clone();
-                higher_node = higher.borrow().parent.clone();
+                lower_node = lower.borrow().parent();
+                higher_node = higher.borrow().parent();
             }
         }
         panic!("the nodes don't have a common ancestor!");
@@ -42,7 +42,7 @@ impl<T> FirstCommonAncestor<T> for BinaryTree<T> {
     fn _get_depth(&self, mut node: Tree<T>) -> i32 {
         let mut depth = 0;
         while let Some(n) = node {
-            node = n.borrow().parent.clone();
+            node = n.borrow().parent();
             depth += 1;
         }
         depth
@@ -211,6 +211,29 @@ mod tests {
             &parent.as_ref().unwrap(),
         ));
     }
+    #[test]
+    fn test_complex_bt_index() {
+        // For many queries against the same tree, the library's
+        // LcaIndex answers each one in O(1) after preprocessing:
+        use cracking::LcaIndex;
+        let n1 = Node::new(1, Node::new(1, None, None), None);
+        let n2 = Node::new(2, None, Node::new(1, None, None));
+        let parent = Node::new(
+            2,
+            Node::new(1, Node::new(1, n2.clone(), None), None),
+            Node::new(1, None, Node::new(1, None, n1.clone())),
+        );
+        let node = Node::new(1, parent.clone(), None);
+        let bt = BinaryTree::<u32>::new(node.clone());
+        let index = LcaIndex::new(&bt);
+        let (n1, n2) = (n1.unwrap(), n2.unwrap());
+        assert!(Rc::ptr_eq(
+            bt.find_common(Some(n1.clone()), Some(n2.clone())).as_ref().unwrap(),
+            &index.lca(&n1, &n2).unwrap(),
+        ));
+        assert_eq!(index.distance(&n1, &n2), Some(6));
+    }
+
     #[test]
     #[should_panic(expected = "the nodes don't have a common ancestor!")]
     fn test_fail_bt() {
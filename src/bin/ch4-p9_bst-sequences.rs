@@ -250,4 +250,17 @@ mod tests {
             .collect()
         );
     }
+
+    #[test]
+    fn test_get_all_arrays_streaming() {
+        use cracking::BstSequences;
+        let mut bst = BinarySearchTree::<u32>::new();
+        for i in [3, 1, 0, 2, 4, 5].iter() {
+            bst.add(*i);
+        }
+        let sequences = BstSequences::new(&bst);
+        assert_eq!(sequences.count_sequences(), Some(20));
+        let streamed: HashSet<Vec<u32>> = sequences.collect();
+        assert_eq!(streamed, get_all_arrays(bst));
+    }
 }
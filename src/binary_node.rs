@@ -0,0 +1,316 @@
+use std::cell::RefCell;
+use std::ptr;
+use std::rc::Rc;
+
+use crate::binary_search_tree::{BinarySearchTree, Node as BSTNode, Tree as BSTTree};
+use crate::binary_search_tree_refcell::{
+    BinarySearchTree as RCBinarySearchTree, Node as RCBSTNode, Tree as RCBSTTree,
+};
+use crate::binary_tree::{BinaryTree, Node as BTNode, Tree as BTTree};
+use crate::binary_tree_arena::{ArenaTree, NodeId};
+use crate::binary_tree_refcell::{BinaryTree as RCBinaryTree, Node as RCBTNode, Tree as RCBTTree};
+
+// Read-only access to the nodes of any of the binary trees in this
+// crate, so that tree algorithms can be written once for both the
+// boxed trees (`&Node<T>`) and the refcell trees (`Rc<RefCell<Node<T>>>`).
+pub trait BinaryNode: Clone {
+    type Data;
+
+    fn left(&self) -> Option<Self>;
+    fn right(&self) -> Option<Self>;
+    // The refcell trees can't hand out a plain reference to their
+    // data, so it's lent out to a closure instead:
+    fn with_data<R>(&self, f: impl FnOnce(&Self::Data) -> R) -> R;
+
+    // Node identity, rather than data equality:
+    fn same_node(&self, other: &Self) -> bool;
+
+    // A key that's unique to this node within its tree, for looking
+    // nodes up in a HashMap. It's the node's address for the boxed and
+    // refcell trees.
+    fn node_key(&self) -> usize;
+
+    // Only the refcell and arena trees keep links to their parents:
+    const HAS_PARENT_LINKS: bool = false;
+
+    fn parent(&self) -> Option<Self> {
+        None
+    }
+}
+
+// Implemented by the tree structs, to hand out their root node.
+pub trait TreeRoot<'a> {
+    type Node: BinaryNode;
+
+    fn root_node(&'a self) -> Option<Self::Node>;
+}
+
+// The other direction: building a tree bottom-up from its data. This
+// is implemented on the `Tree<T>` aliases, where `None` is the empty
+// tree.
+pub trait BuildBinaryNode: Sized {
+    type Data;
+
+    fn empty() -> Self;
+    fn build(data: Self::Data, left: Self, right: Self) -> Self;
+}
+
+impl<T> BinaryNode for &BTNode<T> {
+    type Data = T;
+
+    fn left(&self) -> Option<Self> {
+        self.left.as_deref()
+    }
+
+    fn right(&self) -> Option<Self> {
+        self.right.as_deref()
+    }
+
+    fn with_data<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.data)
+    }
+
+    fn same_node(&self, other: &Self) -> bool {
+        ptr::eq(*self, *other)
+    }
+
+    fn node_key(&self) -> usize {
+        *self as *const _ as usize
+    }
+}
+
+impl<T> BinaryNode for &BSTNode<T> {
+    type Data = T;
+
+    fn left(&self) -> Option<Self> {
+        self.left.as_deref()
+    }
+
+    fn right(&self) -> Option<Self> {
+        self.right.as_deref()
+    }
+
+    fn with_data<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.data)
+    }
+
+    fn same_node(&self, other: &Self) -> bool {
+        ptr::eq(*self, *other)
+    }
+
+    fn node_key(&self) -> usize {
+        *self as *const _ as usize
+    }
+}
+
+impl<T> BinaryNode for Rc<RefCell<RCBTNode<T>>> {
+    type Data = T;
+
+    fn left(&self) -> Option<Self> {
+        self.borrow().left.clone()
+    }
+
+    fn right(&self) -> Option<Self> {
+        self.borrow().right.clone()
+    }
+
+    fn with_data<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.borrow().data)
+    }
+
+    fn same_node(&self, other: &Self) -> bool {
+        Rc::ptr_eq(self, other)
+    }
+
+    fn node_key(&self) -> usize {
+        Rc::as_ptr(self) as usize
+    }
+
+    const HAS_PARENT_LINKS: bool = true;
+
+    fn parent(&self) -> Option<Self> {
+        self.borrow().parent()
+    }
+}
+
+impl<T> BinaryNode for Rc<RefCell<RCBSTNode<T>>> {
+    type Data = T;
+
+    fn left(&self) -> Option<Self> {
+        self.borrow().left.clone()
+    }
+
+    fn right(&self) -> Option<Self> {
+        self.borrow().right.clone()
+    }
+
+    fn with_data<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.borrow().data)
+    }
+
+    fn same_node(&self, other: &Self) -> bool {
+        Rc::ptr_eq(self, other)
+    }
+
+    fn node_key(&self) -> usize {
+        Rc::as_ptr(self) as usize
+    }
+
+    const HAS_PARENT_LINKS: bool = true;
+
+    fn parent(&self) -> Option<Self> {
+        self.borrow().parent()
+    }
+}
+
+// A node of an `ArenaTree`, which needs the arena to look up its
+// children.
+pub struct ArenaNodeRef<'a, T> {
+    pub tree: &'a ArenaTree<T>,
+    pub id: NodeId,
+}
+
+impl<'a, T> Clone for ArenaNodeRef<'a, T> {
+    fn clone(&self) -> Self {
+        ArenaNodeRef {
+            tree: self.tree,
+            id: self.id,
+        }
+    }
+}
+
+impl<'a, T> ArenaNodeRef<'a, T> {
+    fn at(&self, id: Option<NodeId>) -> Option<Self> {
+        id.map(|id| ArenaNodeRef {
+            tree: self.tree,
+            id,
+        })
+    }
+}
+
+impl<'a, T> BinaryNode for ArenaNodeRef<'a, T> {
+    type Data = T;
+
+    fn left(&self) -> Option<Self> {
+        self.at(self.tree[self.id].left)
+    }
+
+    fn right(&self) -> Option<Self> {
+        self.at(self.tree[self.id].right)
+    }
+
+    fn with_data<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.tree[self.id].data)
+    }
+
+    fn same_node(&self, other: &Self) -> bool {
+        ptr::eq(self.tree, other.tree) && self.id == other.id
+    }
+
+    fn node_key(&self) -> usize {
+        self.id
+    }
+
+    const HAS_PARENT_LINKS: bool = true;
+
+    fn parent(&self) -> Option<Self> {
+        self.at(self.tree[self.id].parent)
+    }
+}
+
+impl<'a, T: 'a> TreeRoot<'a> for BinaryTree<T> {
+    type Node = &'a BTNode<T>;
+
+    fn root_node(&'a self) -> Option<Self::Node> {
+        self.root.as_deref()
+    }
+}
+
+impl<'a, T: 'a> TreeRoot<'a> for BinarySearchTree<T> {
+    type Node = &'a BSTNode<T>;
+
+    fn root_node(&'a self) -> Option<Self::Node> {
+        self.root.as_deref()
+    }
+}
+
+impl<'a, T> TreeRoot<'a> for RCBinaryTree<T> {
+    type Node = Rc<RefCell<RCBTNode<T>>>;
+
+    fn root_node(&'a self) -> Option<Self::Node> {
+        self.root.clone()
+    }
+}
+
+impl<'a, T> TreeRoot<'a> for RCBinarySearchTree<T> {
+    type Node = Rc<RefCell<RCBSTNode<T>>>;
+
+    fn root_node(&'a self) -> Option<Self::Node> {
+        self.root.clone()
+    }
+}
+
+impl<'a, T: 'a> TreeRoot<'a> for ArenaTree<T> {
+    type Node = ArenaNodeRef<'a, T>;
+
+    fn root_node(&'a self) -> Option<Self::Node> {
+        self.root.map(|id| ArenaNodeRef { tree: self, id })
+    }
+}
+
+impl<T> BuildBinaryNode for BTTree<T> {
+    type Data = T;
+
+    fn empty() -> Self {
+        None
+    }
+
+    fn build(data: T, left: Self, right: Self) -> Self {
+        BTNode::new(data, left, right)
+    }
+}
+
+impl<T> BuildBinaryNode for BSTTree<T> {
+    type Data = T;
+
+    fn empty() -> Self {
+        None
+    }
+
+    fn build(data: T, left: Self, right: Self) -> Self {
+        Some(Box::new(BSTNode { data, left, right }))
+    }
+}
+
+impl<T> BuildBinaryNode for RCBTTree<T> {
+    type Data = T;
+
+    fn empty() -> Self {
+        None
+    }
+
+    fn build(data: T, left: Self, right: Self) -> Self {
+        RCBTNode::new(data, left, right)
+    }
+}
+
+impl<T> BuildBinaryNode for RCBSTTree<T> {
+    type Data = T;
+
+    fn empty() -> Self {
+        None
+    }
+
+    fn build(data: T, left: Self, right: Self) -> Self {
+        let node = RCBSTNode::new(data);
+        if let Some(n) = &node {
+            for child in left.iter().chain(right.iter()) {
+                child.borrow_mut().set_parent(n);
+            }
+            let mut n_mut = n.borrow_mut();
+            n_mut.left = left;
+            n_mut.right = right;
+        }
+        node
+    }
+}
@@ -1,3 +1,5 @@
+use std::cmp;
+use std::iter::FromIterator;
 use std::mem;
 
 // This implementation is largely inspired by:
@@ -64,6 +66,41 @@ where
         self
     }
 
+    // Builds a tree of minimal height from items that are already in
+    // increasing order, in O(n). Each subtree takes the middle item as
+    // its root, so no re-sorting or repeated `add`s are needed.
+    //
+    // Like `add`, equal items always go on the left: the root is moved
+    // to the last of the items equal to the middle one. A run of k
+    // equal items can't be any shorter than k levels that way, so the
+    // build uses a stack of its own rather than recursing.
+    //
+    // Panics if the items aren't sorted.
+    pub fn from_sorted_iter<I>(sorted: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let items: Vec<T> = sorted.into_iter().collect();
+        assert!(
+            items.windows(2).all(|pair| pair[0] <= pair[1]),
+            "from_sorted_iter: items are not sorted"
+        );
+        let run_ends = run_ends(&items);
+        let length = items.len() as u64;
+        BinarySearchTree {
+            root: build_balanced(items, &run_ends),
+            length,
+        }
+    }
+
+    // Flattens the tree and rebuilds it with minimal height, which
+    // fixes up trees that degenerated into a list from sorted `add`s.
+    // time: O(n)
+    pub fn rebalance(&mut self) {
+        let items = into_sorted_vec(self.root.take());
+        *self = Self::from_sorted_iter(items);
+    }
+
     pub fn add(&mut self, data: T) {
         self.length += 1;
         let root = mem::replace(&mut self.root, None);
@@ -120,6 +157,141 @@ where
     }
 }
 
+// For each item, the index of the last item in its run of equal ones.
+// time: O(n)
+fn run_ends<T: PartialEq>(items: &[T]) -> Vec<usize> {
+    let mut ends = vec![0; items.len()];
+    for i in (0..items.len()).rev() {
+        ends[i] = if i + 1 < items.len() && items[i] == items[i + 1] {
+            ends[i + 1]
+        } else {
+            i
+        };
+    }
+    ends
+}
+
+// The steps of `build_balanced`, which would otherwise recurse once
+// per level.
+enum Build {
+    // Build the subtree for the items in `start..end`:
+    Range(usize, usize),
+    // Join the two subtrees built last under the item at this index:
+    Join(usize),
+}
+
+// Builds a balanced tree from the items, in O(n). Each subtree's root
+// is its middle item, or the last one equal to it in the subtree, so
+// that nothing equal to the root is on its right.
+fn build_balanced<T>(items: Vec<T>, run_ends: &[usize]) -> Tree<T> {
+    let mut items: Vec<Option<T>> = items.into_iter().map(Some).collect();
+    let mut steps = vec![Build::Range(0, items.len())];
+    let mut built: Vec<Tree<T>> = Vec::new();
+    while let Some(step) = steps.pop() {
+        match step {
+            Build::Range(start, end) if start == end => built.push(None),
+            Build::Range(start, end) => {
+                let middle = cmp::min(run_ends[start + (end - start) / 2], end - 1);
+                // The left subtree is built first, so it's pushed last:
+                steps.push(Build::Join(middle));
+                steps.push(Build::Range(middle + 1, end));
+                steps.push(Build::Range(start, middle));
+            }
+            Build::Join(middle) => {
+                let right = built.pop().expect("build_balanced: no right subtree");
+                let left = built.pop().expect("build_balanced: no left subtree");
+                let data = items[middle]
+                    .take()
+                    .expect("build_balanced: invariant violated, item already taken");
+                built.push(Some(Box::new(Node { data, left, right })));
+            }
+        }
+    }
+    built.pop().unwrap_or(None)
+}
+
+// An iterative in-order walk that moves the data out of the tree, so
+// that degenerate trees don't overflow the stack.
+fn into_sorted_vec<T>(root: Tree<T>) -> Vec<T> {
+    let mut items = Vec::new();
+    let mut stack: Vec<Box<Node<T>>> = Vec::new();
+    let mut node = root;
+    loop {
+        while let Some(mut n) = node {
+            node = n.left.take();
+            stack.push(n);
+        }
+        match stack.pop() {
+            Some(n) => {
+                let Node { data, right, .. } = *n;
+                items.push(data);
+                node = right;
+            }
+            None => return items,
+        }
+    }
+}
+
+fn sort_items<T: PartialOrd>(items: &mut [T]) {
+    items.sort_by(|a, b| {
+        a.partial_cmp(b)
+            .expect("BinarySearchTree: items must be comparable")
+    });
+}
+
+// Frees the nodes one at a time, since dropping a tall tree, like one
+// with a long run of equal items, would otherwise recurse once per
+// level.
+impl<T> Drop for BinarySearchTree<T> {
+    fn drop(&mut self) {
+        let mut nodes: Vec<Box<Node<T>>> = self.root.take().into_iter().collect();
+        while let Some(mut node) = nodes.pop() {
+            nodes.extend(node.left.take());
+            nodes.extend(node.right.take());
+        }
+    }
+}
+
+// Collecting from an unsorted source sorts the items first:
+impl<T> FromIterator<T> for BinarySearchTree<T>
+where
+    T: std::cmp::PartialOrd + std::clone::Clone + std::fmt::Debug,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut items: Vec<T> = iter.into_iter().collect();
+        sort_items(&mut items);
+        Self::from_sorted_iter(items)
+    }
+}
+
+// Merges the new items with the existing ones, and rebuilds a balanced
+// tree, in O(n + m log(m)). Adding the items one at a time could
+// degenerate the tree instead.
+impl<T> Extend<T> for BinarySearchTree<T>
+where
+    T: std::cmp::PartialOrd + std::clone::Clone + std::fmt::Debug,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let mut added: Vec<T> = iter.into_iter().collect();
+        sort_items(&mut added);
+        let existing = into_sorted_vec(self.root.take());
+        let mut added = added.into_iter().peekable();
+        let mut merged = Vec::with_capacity(existing.len() + added.len());
+        for item in existing {
+            while let Some(next) = added.peek() {
+                if *next < item {
+                    merged.push(added.next().unwrap());
+                } else {
+                    break;
+                }
+            }
+            merged.push(item);
+        }
+        merged.extend(added);
+        *self = Self::from_sorted_iter(merged);
+    }
+}
+
 mod tests {
     use super::*;
 
@@ -239,4 +411,106 @@ mod tests {
         bst.add(6);
         assert_eq!(bst.get_height(), 4);
     }
+
+    #[cfg(test)]
+    fn in_order(node: &Tree<u32>, items: &mut Vec<u32>) {
+        if let Some(n) = node {
+            in_order(&n.left, items);
+            items.push(n.data);
+            in_order(&n.right, items);
+        }
+    }
+
+    #[test]
+    fn from_sorted_iter_minimal_height() {
+        let bst = BinarySearchTree::from_sorted_iter(0..11);
+        assert_eq!(bst.length, 11);
+        assert_eq!(bst.get_height(), 4);
+        let mut items = Vec::new();
+        in_order(&bst.root, &mut items);
+        assert_eq!(items, (0..11).collect::<Vec<u32>>());
+        assert_eq!(bst.find(7), Some(7));
+
+        let bst = BinarySearchTree::from_sorted_iter(0..(1 << 10) - 1);
+        assert_eq!(bst.get_height(), 10);
+        let bst = BinarySearchTree::<u32>::from_sorted_iter(vec![]);
+        assert!(bst.root.is_none());
+        assert_eq!(bst.length, 0);
+    }
+
+    #[test]
+    fn from_sorted_iter_keeps_duplicates_on_the_left() {
+        use crate::bst_validate::{Duplicates, ValidateBst};
+
+        let bst = BinarySearchTree::from_sorted_iter(vec![3, 3, 3]);
+        assert_eq!(bst.validate(Duplicates::Left), Ok(()));
+        let bst: BinarySearchTree<u32> = vec![1, 1, 1, 1, 1].into_iter().collect();
+        assert_eq!(bst.validate(Duplicates::Left), Ok(()));
+        assert_eq!(bst.find(1), Some(1));
+
+        let mut bst = BinarySearchTree::from_sorted_iter(vec![0, 1, 2, 2, 2, 2, 3, 4, 4, 5, 6]);
+        assert_eq!(bst.validate(Duplicates::Left), Ok(()));
+        let mut items = Vec::new();
+        in_order(&bst.root, &mut items);
+        assert_eq!(items, vec![0, 1, 2, 2, 2, 2, 3, 4, 4, 5, 6]);
+        // `add` agrees with where the built tree put the duplicates:
+        bst.add(2);
+        bst.add(4);
+        assert_eq!(bst.validate(Duplicates::Left), Ok(()));
+        bst.rebalance();
+        assert_eq!(bst.validate(Duplicates::Left), Ok(()));
+        assert_eq!(bst.length, 13);
+    }
+
+    #[test]
+    fn from_sorted_iter_long_run_of_equal_items() {
+        // A million levels, built and dropped without recursing:
+        let bst = BinarySearchTree::from_sorted_iter(std::iter::repeat_n(7u32, 1_000_000));
+        assert_eq!(bst.length, 1_000_000);
+        let root = bst.root.as_ref().unwrap();
+        assert_eq!(root.data, 7);
+        assert!(root.right.is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "from_sorted_iter: items are not sorted")]
+    fn from_sorted_iter_unsorted() {
+        BinarySearchTree::from_sorted_iter(vec![1, 3, 2]);
+    }
+
+    #[test]
+    fn collect_unsorted() {
+        let bst: BinarySearchTree<u32> = vec![5, 3, 9, 1, 6, 0, 7, 2, 8, 4].into_iter().collect();
+        assert_eq!(bst.length, 10);
+        assert_eq!(bst.get_height(), 4);
+        let mut items = Vec::new();
+        in_order(&bst.root, &mut items);
+        assert_eq!(items, (0..10).collect::<Vec<u32>>());
+    }
+
+    #[test]
+    fn extend_merges_and_balances() {
+        let mut bst = BinarySearchTree::from_sorted_iter(vec![1, 3, 5]);
+        bst.extend(vec![6, 0, 4, 3]);
+        assert_eq!(bst.length, 7);
+        assert_eq!(bst.get_height(), 3);
+        let mut items = Vec::new();
+        in_order(&bst.root, &mut items);
+        assert_eq!(items, vec![0, 1, 3, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn rebalance_degenerate_tree() {
+        let mut bst = BinarySearchTree::<u32>::new();
+        for i in 0..1000 {
+            bst.add(i);
+        }
+        assert_eq!(bst.get_height(), 1000);
+        bst.rebalance();
+        assert_eq!(bst.length, 1000);
+        assert_eq!(bst.get_height(), 10);
+        let mut items = Vec::new();
+        in_order(&bst.root, &mut items);
+        assert_eq!(items, (0..1000).collect::<Vec<u32>>());
+    }
 }
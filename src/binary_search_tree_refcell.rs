@@ -3,17 +3,20 @@ use std::mem;
 // This implementation is largely inspired by:
 // https://github.com/PacktPublishing/Hands-On-Data-Structures-and-Algorithms-with-Rust/blob/e79494a07c8d771e0d357ed05eb6d7ddb58a3bf8/Chapter05/src/binary_search_tree.rs
 use std::cell::RefCell;
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
 
 type BareTree<T> = Rc<RefCell<Node<T>>>;
 pub type Tree<T> = Option<BareTree<T>>;
+// Parent links are weak, so that a parent and its children don't form
+// a reference cycle, and the tree is freed once the root is dropped:
+pub type WeakTree<T> = Option<Weak<RefCell<Node<T>>>>;
 
 #[derive(Debug, Clone)]
 pub struct Node<T> {
     pub data: T,
     pub left: Tree<T>,
     pub right: Tree<T>,
-    pub parent: Tree<T>,
+    pub parent: WeakTree<T>,
 }
 
 impl<T> Node<T> {
@@ -26,8 +29,14 @@ impl<T> Node<T> {
         })))
     }
 
-    fn set_parent(&mut self, parent: Tree<T>) {
-        self.parent = parent;
+    pub(crate) fn set_parent(&mut self, parent: &BareTree<T>) {
+        self.parent = Some(Rc::downgrade(parent));
+    }
+
+    // Returns the parent node, or None if this is the root, or if the
+    // parent has already been dropped.
+    pub fn parent(&self) -> Tree<T> {
+        self.parent.as_ref().and_then(Weak::upgrade)
     }
 
     pub fn new_node(data: T) -> Node<T> {
@@ -70,13 +79,13 @@ where
                     if data <= n_mut.data {
                         let new_child = self.add_rec(n_mut.left.clone(), data);
                         if let Some(child) = new_child.clone() {
-                            child.borrow_mut().set_parent(Some(n.clone()));
+                            child.borrow_mut().set_parent(&n);
                         }
                         n_mut.left = new_child;
                     } else {
                         let new_child = self.add_rec(n_mut.right.clone(), data);
                         if let Some(child) = new_child.clone() {
-                            child.borrow_mut().set_parent(Some(n.clone()));
+                            child.borrow_mut().set_parent(&n);
                         }
                         n_mut.right = new_child;
                     }
@@ -243,4 +252,28 @@ mod tests {
         bst.add(6);
         assert_eq!(bst.get_height(), 4);
     }
+
+    #[test]
+    fn tree_is_dropped() {
+        let mut bst = BinarySearchTree::<u32>::new();
+        bst.add(5);
+        bst.add(3);
+        bst.add(9);
+        bst.add(1);
+        let root = bst.root.clone().unwrap();
+        let leaf = root.borrow().left.clone().unwrap().borrow().left.clone().unwrap();
+        assert_eq!(leaf.borrow().data, 1);
+        assert!(Rc::ptr_eq(
+            &leaf.borrow().parent().unwrap().borrow().parent().unwrap(),
+            &root
+        ));
+        let weak_root = Rc::downgrade(&root);
+        let weak_leaf = Rc::downgrade(&leaf);
+        drop(root);
+        drop(leaf);
+
+        drop(bst);
+        assert!(weak_root.upgrade().is_none());
+        assert!(weak_leaf.upgrade().is_none());
+    }
 }
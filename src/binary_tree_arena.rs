@@ -0,0 +1,229 @@
+use std::ops::{Index, IndexMut};
+
+// An alternative to the refcell trees: all nodes live in a single
+// Vec, and refer to their children and parents by index. Parent links
+// don't create reference cycles, and there are no RefCell borrows that
+// can panic at runtime.
+
+pub type NodeId = usize;
+
+#[derive(Debug, Clone)]
+pub struct Node<T> {
+    pub data: T,
+    pub left: Option<NodeId>,
+    pub right: Option<NodeId>,
+    pub parent: Option<NodeId>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ArenaTree<T> {
+    nodes: Vec<Node<T>>,
+    pub root: Option<NodeId>,
+}
+
+impl<T> Default for ArenaTree<T> {
+    fn default() -> Self {
+        ArenaTree {
+            nodes: Vec::new(),
+            root: None,
+        }
+    }
+}
+
+impl<T> ArenaTree<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Similar to `Node::new` on the other binary trees: creates a
+    // node with the given children, and links the children back to
+    // it. The new node is not attached to the tree until it's set as
+    // the root, or as the child of another node.
+    pub fn new_node(&mut self, data: T, left: Option<NodeId>, right: Option<NodeId>) -> NodeId {
+        let id = self.nodes.len();
+        for child in left.iter().chain(right.iter()) {
+            self.nodes[*child].parent = Some(id);
+        }
+        self.nodes.push(Node {
+            data,
+            left,
+            right,
+            parent: None,
+        });
+        id
+    }
+
+    pub fn get(&self, id: NodeId) -> Option<&Node<T>> {
+        self.nodes.get(id)
+    }
+
+    pub fn get_mut(&mut self, id: NodeId) -> Option<&mut Node<T>> {
+        self.nodes.get_mut(id)
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.nodes[id].parent
+    }
+
+    // Number of nodes in the arena, including nodes that were created
+    // but never attached to the root.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    // The root has a depth of 1, to match `get_height`.
+    pub fn depth(&self, id: NodeId) -> usize {
+        let mut depth = 1;
+        let mut node = id;
+        while let Some(parent) = self.nodes[node].parent {
+            node = parent;
+            depth += 1;
+        }
+        depth
+    }
+
+    pub fn get_height(&self) -> usize {
+        self.get_height_r(self.root)
+    }
+
+    fn get_height_r(&self, node: Option<NodeId>) -> usize {
+        if let Some(id) = node {
+            let n = &self.nodes[id];
+            1 + std::cmp::max(self.get_height_r(n.left), self.get_height_r(n.right))
+        } else {
+            0
+        }
+    }
+}
+
+impl<T> ArenaTree<T>
+where
+    T: std::cmp::PartialOrd,
+{
+    // Inserts the data with binary search tree ordering, like
+    // `BinarySearchTree::add`, and returns the id of the new node.
+    pub fn add(&mut self, data: T) -> NodeId {
+        let mut parent = match self.root {
+            Some(root) => root,
+            None => {
+                let id = self.new_node(data, None, None);
+                self.root = Some(id);
+                return id;
+            }
+        };
+        loop {
+            let go_left = data <= self.nodes[parent].data;
+            let next = if go_left {
+                self.nodes[parent].left
+            } else {
+                self.nodes[parent].right
+            };
+            match next {
+                Some(child) => parent = child,
+                None => {
+                    let id = self.new_node(data, None, None);
+                    self.nodes[id].parent = Some(parent);
+                    if go_left {
+                        self.nodes[parent].left = Some(id);
+                    } else {
+                        self.nodes[parent].right = Some(id);
+                    }
+                    return id;
+                }
+            }
+        }
+    }
+
+    pub fn find(&self, data: &T) -> Option<NodeId> {
+        let mut node = self.root;
+        while let Some(id) = node {
+            let n = &self.nodes[id];
+            if n.data == *data {
+                return Some(id);
+            } else if *data < n.data {
+                node = n.left;
+            } else {
+                node = n.right;
+            }
+        }
+        None
+    }
+}
+
+impl<T> Index<NodeId> for ArenaTree<T> {
+    type Output = Node<T>;
+
+    fn index(&self, id: NodeId) -> &Self::Output {
+        &self.nodes[id]
+    }
+}
+
+impl<T> IndexMut<NodeId> for ArenaTree<T> {
+    fn index_mut(&mut self, id: NodeId) -> &mut Self::Output {
+        &mut self.nodes[id]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn create_complex_bt() {
+        let mut t = ArenaTree::<u32>::new();
+        let four = t.new_node(4, None, None);
+        let five = t.new_node(5, None, None);
+        let three = t.new_node(3, Some(four), Some(five));
+        let two = t.new_node(2, None, None);
+        let root = t.new_node(1, Some(two), Some(three));
+        t.root = Some(root);
+
+        assert_eq!(t.len(), 5);
+        assert_eq!(t.parent(four), Some(three));
+        assert_eq!(t.parent(three), Some(root));
+        assert_eq!(t.parent(root), None);
+        assert_eq!(t[t.parent(five).unwrap()].data, 3);
+        assert_eq!(t.depth(five), 3);
+        assert_eq!(t.get_height(), 3);
+    }
+
+    #[test]
+    fn mutate_through_parent_links() {
+        let mut t = ArenaTree::<u32>::new();
+        let leaf = t.new_node(2, None, None);
+        let root = t.new_node(1, Some(leaf), None);
+        t.root = Some(root);
+
+        // With Rc<RefCell<Node>> this would need a borrow of the child
+        // and a mutable borrow of the parent at the same time.
+        let parent = t.parent(leaf).unwrap();
+        t[parent].data += t[leaf].data;
+        assert_eq!(t[root].data, 3);
+    }
+
+    #[test]
+    fn add_and_find() {
+        let mut t = ArenaTree::<u32>::new();
+        t.add(5);
+        t.add(3);
+        t.add(9);
+        t.add(1);
+        let six = t.add(6);
+        assert_eq!(t.find(&6), Some(six));
+        assert_eq!(t.find(&10), None);
+        assert_eq!(t[t.parent(six).unwrap()].data, 9);
+        assert_eq!(t.get_height(), 3);
+    }
+
+    #[test]
+    fn get_height_empty() {
+        let t = ArenaTree::<u32>::new();
+        assert!(t.is_empty());
+        assert_eq!(t.get_height(), 0);
+        assert!(t.get(0).is_none());
+    }
+}
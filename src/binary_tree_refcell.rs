@@ -1,15 +1,18 @@
 use std::cell::RefCell;
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
 
 type BareTree<T> = Rc<RefCell<Node<T>>>;
 pub type Tree<T> = Option<BareTree<T>>;
+// Parent links are weak, so that a parent and its children don't form
+// a reference cycle, and the tree is freed once the root is dropped:
+pub type WeakTree<T> = Option<Weak<RefCell<Node<T>>>>;
 
 #[derive(Debug, Clone)]
 pub struct Node<T> {
     pub data: T,
     pub left: Tree<T>,
     pub right: Tree<T>,
-    pub parent: Tree<T>,
+    pub parent: WeakTree<T>,
 }
 
 impl<T> Node<T> {
@@ -21,13 +24,19 @@ impl<T> Node<T> {
             parent: None,
         })));
         if let Some(l) = left {
-            l.borrow_mut().parent = new_node.clone();
+            l.borrow_mut().parent = new_node.as_ref().map(Rc::downgrade);
         }
         if let Some(r) = right {
-            r.borrow_mut().parent = new_node.clone();
+            r.borrow_mut().parent = new_node.as_ref().map(Rc::downgrade);
         }
         new_node
     }
+
+    // Returns the parent node, or None if this is the root, or if the
+    // parent has already been dropped.
+    pub fn parent(&self) -> Tree<T> {
+        self.parent.as_ref().and_then(Weak::upgrade)
+    }
 }
 
 pub struct BinaryTree<T> {
@@ -73,11 +82,30 @@ mod tests {
         let grandchild = Node::new(4, None, None);
         let child = Node::new(3, grandchild.clone(), Node::new(5, None, None));
         assert!(Rc::ptr_eq(
-            &grandchild.as_ref().unwrap().borrow().parent.as_ref().unwrap(),
+            &grandchild.as_ref().unwrap().borrow().parent().unwrap(),
             &child.clone().unwrap()
         ));
         let node = Node::new(1, Node::new(2, None, None), child);
         BinaryTree::<u32>::new(node);
         assert!(true);
     }
+
+    #[test]
+    fn tree_is_dropped() {
+        let leaf = Node::new(4, None, None);
+        let child = Node::new(3, leaf.clone(), Node::new(5, None, None));
+        let root = Node::new(1, Node::new(2, None, None), child);
+        let weak_root = Rc::downgrade(root.as_ref().unwrap());
+        let weak_leaf = Rc::downgrade(leaf.as_ref().unwrap());
+        drop(leaf);
+
+        // Only the tree itself holds strong references to its nodes:
+        assert_eq!(Rc::strong_count(root.as_ref().unwrap()), 1);
+        let bt = BinaryTree::<u32>::new(root);
+        assert!(weak_leaf.upgrade().is_some());
+
+        drop(bt);
+        assert!(weak_root.upgrade().is_none());
+        assert!(weak_leaf.upgrade().is_none());
+    }
 }
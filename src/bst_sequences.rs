@@ -0,0 +1,307 @@
+use rand::Rng;
+
+use crate::binary_node::{BinaryNode, TreeRoot};
+
+// The arrays that build a given BST when their elements are inserted
+// from left to right. These are exactly the orderings of the nodes
+// where every node comes after its parent, so the same applies to any
+// binary tree.
+//
+// The number of sequences grows factorially with the size of the tree,
+// so rather than collecting them up front, they're produced one at a
+// time by a depth-first walk over the choices. Each step picks the next
+// node from the "frontier" of nodes whose parents are already placed,
+// and only the current path of choices is kept in memory: O(n^2) for
+// the frontiers, regardless of how many sequences there are.
+pub struct BstSequences<T> {
+    // The tree is flattened into preorder, with node 0 as the root:
+    data: Vec<T>,
+    children: Vec<Vec<usize>>,
+    sizes: Vec<usize>,
+    frames: Vec<Frame>,
+    sequence: Vec<usize>,
+    // The empty tree has a single, empty sequence:
+    empty_pending: bool,
+}
+
+struct Frame {
+    frontier: Vec<usize>,
+    next: usize,
+}
+
+impl<T> BstSequences<T>
+where
+    T: Clone,
+{
+    pub fn new<'a, B, N>(tree: &'a B) -> Self
+    where
+        B: TreeRoot<'a, Node = N>,
+        N: BinaryNode<Data = T>,
+    {
+        Self::from_node(tree.root_node())
+    }
+
+    pub fn from_node<N>(root: Option<N>) -> Self
+    where
+        N: BinaryNode<Data = T>,
+    {
+        let mut sequences = BstSequences {
+            data: Vec::new(),
+            children: Vec::new(),
+            sizes: Vec::new(),
+            frames: Vec::new(),
+            sequence: Vec::new(),
+            empty_pending: root.is_none(),
+        };
+        if let Some(root) = root {
+            sequences.flatten_tree(root);
+            sequences.frames.push(Frame {
+                frontier: vec![0],
+                next: 0,
+            });
+        }
+        sequences
+    }
+
+    // Returns the index of the node, and records its subtree size.
+    fn flatten_tree<N>(&mut self, node: N) -> usize
+    where
+        N: BinaryNode<Data = T>,
+    {
+        let i = self.data.len();
+        self.data.push(node.with_data(T::clone));
+        self.children.push(Vec::new());
+        self.sizes.push(1);
+        for child in node.left().into_iter().chain(node.right()) {
+            let c = self.flatten_tree(child);
+            self.children[i].push(c);
+            self.sizes[i] += self.sizes[c];
+        }
+        i
+    }
+
+    // The number of nodes in the tree, which is the length of every
+    // sequence.
+    pub fn tree_len(&self) -> usize {
+        self.data.len()
+    }
+
+    // The total number of sequences, without enumerating them. A node
+    // with subtrees of sizes l and r can interleave their sequences in
+    // C(l + r, l) ways, so the count is the product of that over every
+    // node. Returns None if the count doesn't fit in a u128.
+    // time: O(n^2) at worst, for computing the binomials
+    pub fn count_sequences(&self) -> Option<u128> {
+        let mut count: u128 = 1;
+        for (i, children) in self.children.iter().enumerate() {
+            let left = children.first().map_or(0, |c| self.sizes[*c]);
+            count = count.checked_mul(binomial(self.sizes[i] - 1, left)?)?;
+        }
+        Some(count)
+    }
+
+    // Picks one of the sequences, with every sequence equally likely.
+    //
+    // Choosing the next node from the frontier with probability
+    // proportional to its subtree size makes a sequence's probability
+    // the product of size/remaining over its nodes, which is
+    // (product of the sizes) / n! for every sequence.
+    // time: O(n * width of the tree)
+    pub fn sample<R>(&self, rng: &mut R) -> Vec<T>
+    where
+        R: Rng + ?Sized,
+    {
+        let mut sample = Vec::with_capacity(self.data.len());
+        let mut frontier = if self.data.is_empty() {
+            Vec::new()
+        } else {
+            vec![0]
+        };
+        let mut remaining = self.data.len();
+        while remaining > 0 {
+            let mut pick = rng.gen_range(0, remaining);
+            let position = frontier
+                .iter()
+                .position(|node| {
+                    if pick < self.sizes[*node] {
+                        true
+                    } else {
+                        pick -= self.sizes[*node];
+                        false
+                    }
+                })
+                .expect("BstSequences: invariant violated, frontier sizes don't add up");
+            let node = frontier.swap_remove(position);
+            frontier.extend(self.children[node].iter().cloned());
+            sample.push(self.data[node].clone());
+            remaining -= 1;
+        }
+        sample
+    }
+}
+
+impl<T> Iterator for BstSequences<T>
+where
+    T: Clone,
+{
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        if self.empty_pending {
+            self.empty_pending = false;
+            return Some(Vec::new());
+        }
+        loop {
+            let frame = self.frames.last_mut()?;
+            if frame.next == frame.frontier.len() {
+                // Every choice at this depth is exhausted, so back up
+                // and undo the choice that led here:
+                self.frames.pop();
+                self.sequence.pop();
+                continue;
+            }
+            let mut frontier = frame.frontier.clone();
+            let chosen = frontier.remove(frame.next);
+            frame.next += 1;
+            frontier.extend(self.children[chosen].iter().cloned());
+            self.sequence.push(chosen);
+
+            if self.sequence.len() == self.data.len() {
+                let sequence = self
+                    .sequence
+                    .iter()
+                    .map(|i| self.data[*i].clone())
+                    .collect();
+                self.sequence.pop();
+                return Some(sequence);
+            }
+            self.frames.push(Frame { frontier, next: 0 });
+        }
+    }
+}
+
+// C(n, k), or None if it overflows. Each partial product is itself a
+// binomial, so the division is always exact.
+fn binomial(n: usize, k: usize) -> Option<u128> {
+    let k = std::cmp::min(k, n - k);
+    let mut result: u128 = 1;
+    for i in 0..k {
+        result = result.checked_mul((n - i) as u128)? / (i as u128 + 1);
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binary_search_tree::BinarySearchTree;
+    use rand::thread_rng;
+    use std::collections::{HashMap, HashSet};
+
+    fn bst(data: &[u32]) -> BinarySearchTree<u32> {
+        let mut bst = BinarySearchTree::new();
+        for d in data {
+            bst.add(*d);
+        }
+        bst
+    }
+
+    // Checks that inserting the sequence builds the same tree shape.
+    fn builds_same_tree(sequence: &[u32], tree: &BinarySearchTree<u32>) -> bool {
+        let rebuilt = bst(sequence);
+        format!("{:?}", rebuilt.root) == format!("{:?}", tree.root)
+    }
+
+    #[test]
+    fn sequences_simple() {
+        let tree = bst(&[2, 1, 3]);
+        let sequences: Vec<Vec<u32>> = BstSequences::new(&tree).collect();
+        assert_eq!(sequences, vec![vec![2, 1, 3], vec![2, 3, 1]]);
+    }
+
+    #[test]
+    fn sequences_empty_and_single() {
+        let empty = bst(&[]);
+        let sequences: Vec<Vec<u32>> = BstSequences::new(&empty).collect();
+        assert_eq!(sequences, vec![Vec::<u32>::new()]);
+        assert_eq!(BstSequences::new(&empty).count_sequences(), Some(1));
+        assert!(BstSequences::new(&empty)
+            .sample(&mut thread_rng())
+            .is_empty());
+
+        let single = bst(&[7]);
+        let sequences: Vec<Vec<u32>> = BstSequences::new(&single).collect();
+        assert_eq!(sequences, vec![vec![7]]);
+    }
+
+    #[test]
+    fn sequences_are_valid_and_distinct() {
+        let tree = bst(&[5, 3, 8, 1, 4, 7, 9, 2, 6]);
+        let sequences = BstSequences::new(&tree);
+        let count = sequences.count_sequences().unwrap();
+        let mut seen = HashSet::new();
+        for sequence in sequences {
+            assert!(builds_same_tree(&sequence, &tree));
+            assert!(seen.insert(sequence));
+        }
+        assert_eq!(seen.len() as u128, count);
+    }
+
+    #[test]
+    fn count_without_enumerating() {
+        // A path has a single sequence:
+        let path: Vec<u32> = (0..200).collect();
+        assert_eq!(BstSequences::new(&bst(&path)).count_sequences(), Some(1));
+
+        // 3 with subtrees {1, 0, 2} and {4, 5}: C(5, 2) * 2 = 20
+        let tree = bst(&[3, 1, 0, 2, 4, 5]);
+        assert_eq!(BstSequences::new(&tree).count_sequences(), Some(20));
+
+        // Large balanced trees overflow a u128:
+        let mut data = Vec::new();
+        balanced_order(0, 1023, &mut data);
+        let tree = bst(&data);
+        let sequences = BstSequences::new(&tree);
+        assert_eq!(sequences.tree_len(), 1023);
+        assert_eq!(sequences.count_sequences(), None);
+        // ... but can still be streamed and sampled:
+        let first = BstSequences::new(&tree).next().unwrap();
+        assert!(builds_same_tree(&first, &tree));
+        assert!(builds_same_tree(
+            &sequences.sample(&mut thread_rng()),
+            &tree
+        ));
+    }
+
+    fn balanced_order(lo: u32, hi: u32, data: &mut Vec<u32>) {
+        if lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            data.push(mid);
+            balanced_order(lo, mid, data);
+            balanced_order(mid + 1, hi, data);
+        }
+    }
+
+    #[test]
+    fn sample_is_uniform() {
+        // 3 with subtrees {1, 0, 2} and {4, 5} has 20 sequences. A
+        // biased sampler would favor the sequences that start with the
+        // larger subtree.
+        let tree = bst(&[3, 1, 0, 2, 4, 5]);
+        let sequences = BstSequences::new(&tree);
+        let mut counts = HashMap::new();
+        let trials = 20_000;
+        let mut rng = thread_rng();
+        for _ in 0..trials {
+            let sample = sequences.sample(&mut rng);
+            assert!(builds_same_tree(&sample, &tree));
+            *counts.entry(sample).or_insert(0) += 1;
+        }
+        assert_eq!(counts.len(), 20);
+        // Each sequence is expected 1000 times, with a standard
+        // deviation of about 31:
+        for count in counts.values() {
+            assert!(*count > 800 && *count < 1200, "count: {}", count);
+        }
+    }
+}
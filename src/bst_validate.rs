@@ -0,0 +1,369 @@
+use std::fmt;
+use std::fmt::Display;
+
+use crate::binary_node::{BinaryNode, TreeRoot};
+use crate::binary_search_tree::BinarySearchTree;
+use crate::binary_search_tree_refcell::BinarySearchTree as RCBinarySearchTree;
+use crate::binary_tree::BinaryTree;
+use crate::binary_tree_refcell::BinaryTree as RCBinaryTree;
+
+// Checks whether a binary tree is a valid BST, and reports the first
+// node (in preorder) that breaks the ordering. Every node is checked
+// against the tightest bounds from all of its ancestors, not just its
+// parent, so a node deep in a left subtree that's larger than the root
+// is caught too.
+//
+// For the trees with parent links, every parent link is also checked
+// against the node that actually has it as a child.
+
+// Which side equal values are allowed on. `BinarySearchTree::add`
+// puts duplicates on the left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Duplicates {
+    Left,
+    Right,
+    Forbidden,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation<T> {
+    // The node is in the right subtree of an ancestor holding `bound`,
+    // so it must be greater than it (or equal, if `inclusive`):
+    BelowLowerBound {
+        bound: T,
+        inclusive: bool,
+    },
+    // The node is in the left subtree of an ancestor holding `bound`:
+    AboveUpperBound {
+        bound: T,
+        inclusive: bool,
+    },
+    // The node's parent link doesn't point to its actual parent. Holds
+    // the data of the actual parent and of the linked node, where None
+    // is no parent:
+    ParentLink {
+        expected: Option<T>,
+        found: Option<T>,
+    },
+}
+
+// The offending node is named by its data, and by its path from the
+// root, since the data alone might not be unique.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BstViolation<T> {
+    pub path: Vec<Side>,
+    pub data: T,
+    pub violation: Violation<T>,
+}
+
+impl<T: Display> Display for BstViolation<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "node {} at root", self.data)?;
+        for side in self.path.iter() {
+            match side {
+                Side::Left => write!(f, ".left")?,
+                Side::Right => write!(f, ".right")?,
+            }
+        }
+        match &self.violation {
+            Violation::BelowLowerBound { bound, inclusive } => {
+                let op = if *inclusive { ">=" } else { ">" };
+                write!(f, " is below its lower bound: must be {} {}", op, bound)
+            }
+            Violation::AboveUpperBound { bound, inclusive } => {
+                let op = if *inclusive { "<=" } else { "<" };
+                write!(f, " is above its upper bound: must be {} {}", op, bound)
+            }
+            Violation::ParentLink { expected, found } => {
+                write!(f, " has a parent link to ")?;
+                write_optional(f, found)?;
+                write!(f, ", but its parent is ")?;
+                write_optional(f, expected)
+            }
+        }
+    }
+}
+
+fn write_optional<T: Display>(f: &mut fmt::Formatter, data: &Option<T>) -> fmt::Result {
+    match data {
+        Some(data) => write!(f, "{}", data),
+        None => write!(f, "∅"),
+    }
+}
+
+impl<T: Display + fmt::Debug> std::error::Error for BstViolation<T> {}
+
+pub fn validate_bst<N>(root: Option<N>, duplicates: Duplicates) -> Result<(), BstViolation<N::Data>>
+where
+    N: BinaryNode,
+    N::Data: PartialOrd + Clone,
+{
+    match root {
+        Some(root) => walk(&root, None, None, None, &mut Vec::new(), Some(duplicates)),
+        None => Ok(()),
+    }
+}
+
+// Only checks the parent links, for trees that aren't BSTs. Always
+// passes for trees without parent links.
+pub fn check_parent_links<N>(root: Option<N>) -> Result<(), BstViolation<N::Data>>
+where
+    N: BinaryNode,
+    N::Data: PartialOrd + Clone,
+{
+    match root {
+        Some(root) if N::HAS_PARENT_LINKS => walk(&root, None, None, None, &mut Vec::new(), None),
+        _ => Ok(()),
+    }
+}
+
+// Bounds are (data, inclusive) pairs. Without a duplicate policy, no
+// bounds are set, and only the parent links are checked.
+fn walk<N>(
+    node: &N,
+    actual_parent: Option<&N>,
+    lower: Option<(N::Data, bool)>,
+    upper: Option<(N::Data, bool)>,
+    path: &mut Vec<Side>,
+    duplicates: Option<Duplicates>,
+) -> Result<(), BstViolation<N::Data>>
+where
+    N: BinaryNode,
+    N::Data: PartialOrd + Clone,
+{
+    let data = node.with_data(N::Data::clone);
+    let report = |violation| BstViolation {
+        path: path.clone(),
+        data: data.clone(),
+        violation,
+    };
+
+    if N::HAS_PARENT_LINKS {
+        let parent = node.parent();
+        let linked_correctly = match (&parent, actual_parent) {
+            (Some(p), Some(actual)) => p.same_node(actual),
+            (None, None) => true,
+            _ => false,
+        };
+        if !linked_correctly {
+            return Err(report(Violation::ParentLink {
+                expected: actual_parent.map(|p| p.with_data(N::Data::clone)),
+                found: parent.map(|p| p.with_data(N::Data::clone)),
+            }));
+        }
+    }
+
+    if let Some((bound, inclusive)) = lower.clone() {
+        let in_bounds = if inclusive {
+            data >= bound
+        } else {
+            data > bound
+        };
+        if !in_bounds {
+            return Err(report(Violation::BelowLowerBound { bound, inclusive }));
+        }
+    }
+    if let Some((bound, inclusive)) = upper.clone() {
+        let in_bounds = if inclusive {
+            data <= bound
+        } else {
+            data < bound
+        };
+        if !in_bounds {
+            return Err(report(Violation::AboveUpperBound { bound, inclusive }));
+        }
+    }
+
+    if let Some(l) = node.left() {
+        let left_upper = duplicates.map(|d| (data.clone(), d == Duplicates::Left));
+        path.push(Side::Left);
+        walk(&l, Some(node), lower, left_upper, path, duplicates)?;
+        path.pop();
+    }
+    if let Some(r) = node.right() {
+        let right_lower = duplicates.map(|d| (data, d == Duplicates::Right));
+        path.push(Side::Right);
+        walk(&r, Some(node), right_lower, upper, path, duplicates)?;
+        path.pop();
+    }
+    Ok(())
+}
+
+pub trait ValidateBst {
+    type Data;
+
+    fn validate(&self, duplicates: Duplicates) -> Result<(), BstViolation<Self::Data>>;
+}
+
+impl<T> ValidateBst for BinaryTree<T>
+where
+    T: PartialOrd + Clone,
+{
+    type Data = T;
+
+    fn validate(&self, duplicates: Duplicates) -> Result<(), BstViolation<T>> {
+        validate_bst(self.root_node(), duplicates)
+    }
+}
+
+impl<T> ValidateBst for BinarySearchTree<T>
+where
+    T: PartialOrd + Clone,
+{
+    type Data = T;
+
+    fn validate(&self, duplicates: Duplicates) -> Result<(), BstViolation<T>> {
+        validate_bst(self.root_node(), duplicates)
+    }
+}
+
+impl<T> ValidateBst for RCBinaryTree<T>
+where
+    T: PartialOrd + Clone,
+{
+    type Data = T;
+
+    fn validate(&self, duplicates: Duplicates) -> Result<(), BstViolation<T>> {
+        validate_bst(self.root_node(), duplicates)
+    }
+}
+
+impl<T> ValidateBst for RCBinarySearchTree<T>
+where
+    T: PartialOrd + Clone,
+{
+    type Data = T;
+
+    fn validate(&self, duplicates: Duplicates) -> Result<(), BstViolation<T>> {
+        validate_bst(self.root_node(), duplicates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binary_tree_refcell::Node as RCNode;
+    use crate::tree_serialize::{Format, SerializeTree};
+    use std::rc::Rc;
+
+    fn level_order(s: &str) -> BinaryTree<i32> {
+        BinaryTree::deserialize(s, Format::LevelOrder).unwrap()
+    }
+
+    #[test]
+    fn valid_trees() {
+        assert_eq!(level_order("[]").validate(Duplicates::Forbidden), Ok(()));
+        assert_eq!(
+            level_order("[2,1,3]").validate(Duplicates::Forbidden),
+            Ok(())
+        );
+        let bt = level_order("[5,2,9,0,3,7,13,null,null,null,4]");
+        assert_eq!(bt.validate(Duplicates::Forbidden), Ok(()));
+    }
+
+    #[test]
+    fn grandparent_bound() {
+        // 4 is in the left subtree of 3:
+        let bt = level_order("[3,2,9,1,4]");
+        let err = bt.validate(Duplicates::Left).unwrap_err();
+        assert_eq!(
+            err,
+            BstViolation {
+                path: vec![Side::Left, Side::Right],
+                data: 4,
+                violation: Violation::AboveUpperBound {
+                    bound: 3,
+                    inclusive: true
+                },
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "node 4 at root.left.right is above its upper bound: must be <= 3"
+        );
+    }
+
+    #[test]
+    fn duplicate_policies() {
+        let on_left = level_order("[2,2,3]");
+        let on_right = level_order("[2,1,2]");
+        assert_eq!(on_left.validate(Duplicates::Left), Ok(()));
+        assert_eq!(on_right.validate(Duplicates::Right), Ok(()));
+
+        let err = on_left.validate(Duplicates::Right).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "node 2 at root.left is above its upper bound: must be < 2"
+        );
+        let err = on_right.validate(Duplicates::Left).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "node 2 at root.right is below its lower bound: must be > 2"
+        );
+        assert!(on_left.validate(Duplicates::Forbidden).is_err());
+        assert!(on_right.validate(Duplicates::Forbidden).is_err());
+    }
+
+    #[test]
+    fn bst_add_uses_left_duplicates() {
+        let mut bst = BinarySearchTree::new();
+        for i in [5, 2, 2, 0, 3, 9, 7, 9].iter() {
+            bst.add(*i);
+        }
+        assert_eq!(bst.validate(Duplicates::Left), Ok(()));
+        assert!(bst.validate(Duplicates::Right).is_err());
+    }
+
+    #[test]
+    fn refcell_tree_and_parent_links() {
+        let leaf = RCNode::new(1, None, None);
+        let bt = RCBinaryTree::new(RCNode::new(3, RCNode::new(2, leaf.clone(), None), None));
+        assert_eq!(bt.validate(Duplicates::Forbidden), Ok(()));
+        assert_eq!(check_parent_links(bt.root_node()), Ok(()));
+
+        // Point the leaf's parent link at the root, which is wrong:
+        let root = bt.root.clone().unwrap();
+        leaf.as_ref().unwrap().borrow_mut().parent = Some(Rc::downgrade(&root));
+        let err = bt.validate(Duplicates::Forbidden).unwrap_err();
+        assert_eq!(
+            err.violation,
+            Violation::ParentLink {
+                expected: Some(2),
+                found: Some(3)
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "node 1 at root.left.left has a parent link to 3, but its parent is 2"
+        );
+        assert_eq!(check_parent_links(bt.root_node()), Err(err));
+
+        // The root must not have a parent:
+        root.borrow_mut().parent = Some(Rc::downgrade(&root));
+        let err = check_parent_links(bt.root_node()).unwrap_err();
+        assert!(err.path.is_empty());
+        assert_eq!(
+            err.to_string(),
+            "node 3 at root has a parent link to 3, but its parent is ∅"
+        );
+        root.borrow_mut().parent = None;
+    }
+
+    #[test]
+    fn parent_links_on_non_bst() {
+        let bt = RCBinaryTree::<i32>::deserialize("[1,2,3,4]", Format::LevelOrder).unwrap();
+        assert!(bt.validate(Duplicates::Left).is_err());
+        assert_eq!(check_parent_links(bt.root_node()), Ok(()));
+        // Boxed trees have no parent links to check:
+        assert_eq!(
+            check_parent_links(level_order("[1,2,3]").root_node()),
+            Ok(())
+        );
+    }
+}
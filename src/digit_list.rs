@@ -0,0 +1,665 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Mul, Sub};
+use std::str::FromStr;
+
+use crate::linked_list::fifth;
+use crate::linked_list::list::List;
+use crate::path_sum::Widen;
+
+// Arbitrary-precision unsigned integers, as lists of decimal digits.
+//
+// `BigUint` keeps its digits in a Vec, least significant first, for
+// the arithmetic. `DigitList` is the linked list form from chapter 2,
+// with the digits in either order, and converts to and from BigUint.
+
+// Below this many digits, Karatsuba's extra additions cost more than
+// the multiplications they save:
+const KARATSUBA_THRESHOLD: usize = 32;
+
+// Least significant digit first, without leading zeros, so zero has no
+// digits at all. That makes the derived equality and hashing right.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigUint {
+    digits: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseBigUintError {
+    Empty,
+    InvalidDigit { index: usize, found: char },
+    // For digit lists, which hold integers:
+    DigitOutOfRange { index: usize, digit: i32 },
+}
+
+impl fmt::Display for ParseBigUintError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseBigUintError::Empty => write!(f, "cannot parse a number from an empty string"),
+            ParseBigUintError::InvalidDigit { index, found } => {
+                write!(f, "invalid digit {:?} at index {}", found, index)
+            }
+            ParseBigUintError::DigitOutOfRange { index, digit } => {
+                write!(f, "digit {} at index {} is out of range", digit, index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseBigUintError {}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    // Zero has one digit, like it's written.
+    pub fn num_digits(&self) -> usize {
+        self.digits.len().max(1)
+    }
+
+    // From digits that are least significant first, which may have
+    // leading zeros. Panics if any isn't a decimal digit.
+    fn from_le_digits(mut digits: Vec<u8>) -> Self {
+        assert!(digits.iter().all(|d| *d < 10), "not a decimal digit");
+        trim(&mut digits);
+        BigUint { digits }
+    }
+
+    // Least significant first, with a single 0 for zero.
+    pub fn to_le_digits(&self) -> Vec<u8> {
+        if self.is_zero() {
+            vec![0]
+        } else {
+            self.digits.clone()
+        }
+    }
+
+    pub fn checked_sub(&self, other: &BigUint) -> Option<BigUint> {
+        if *self < *other {
+            return None;
+        }
+        let mut digits = self.digits.clone();
+        sub_in_place(&mut digits, &other.digits);
+        trim(&mut digits);
+        Some(BigUint { digits })
+    }
+
+    // time: O(n * m)
+    pub fn mul_schoolbook(&self, other: &BigUint) -> BigUint {
+        BigUint::from_le_digits(mul_schoolbook(&self.digits, &other.digits))
+    }
+
+    // time: O(n^log2(3)), about O(n^1.585)
+    pub fn mul_karatsuba(&self, other: &BigUint) -> BigUint {
+        BigUint::from_le_digits(mul_karatsuba(&self.digits, &other.digits))
+    }
+}
+
+// Removes leading zeros, which are at the end.
+fn trim(digits: &mut Vec<u8>) {
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+}
+
+fn add_digits(a: &[u8], b: &[u8]) -> Vec<u8> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut sum = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0;
+    for (i, digit) in long.iter().enumerate() {
+        let total = digit + short.get(i).unwrap_or(&0) + carry;
+        sum.push(total % 10);
+        carry = total / 10;
+    }
+    if carry > 0 {
+        sum.push(carry);
+    }
+    sum
+}
+
+// Requires a >= b.
+fn sub_in_place(a: &mut [u8], b: &[u8]) {
+    let mut borrow = 0;
+    for (i, digit) in a.iter_mut().enumerate() {
+        if i >= b.len() && borrow == 0 {
+            break;
+        }
+        let subtrahend = b.get(i).unwrap_or(&0) + borrow;
+        if *digit >= subtrahend {
+            *digit -= subtrahend;
+            borrow = 0;
+        } else {
+            *digit = *digit + 10 - subtrahend;
+            borrow = 1;
+        }
+    }
+    debug_assert_eq!(borrow, 0, "subtraction underflowed");
+}
+
+fn mul_schoolbook(a: &[u8], b: &[u8]) -> Vec<u8> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    // Each column is at most 81 * min(n, m), so carry once at the end:
+    let mut columns = vec![0u64; a.len() + b.len()];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            columns[i + j] += u64::from(x * y);
+        }
+    }
+    let mut digits = Vec::with_capacity(columns.len());
+    let mut carry = 0;
+    for column in columns {
+        let total = column + carry;
+        digits.push((total % 10) as u8);
+        carry = total / 10;
+    }
+    while carry > 0 {
+        digits.push((carry % 10) as u8);
+        carry /= 10;
+    }
+    trim(&mut digits);
+    digits
+}
+
+// With a = a1 * 10^m + a0 and b = b1 * 10^m + b0:
+// a * b = z2 * 10^2m + z1 * 10^m + z0, where z2 = a1 * b1, z0 = a0 * b0,
+// and z1 = (a0 + a1)(b0 + b1) - z2 - z0, for three multiplications
+// instead of four.
+fn mul_karatsuba(a: &[u8], b: &[u8]) -> Vec<u8> {
+    if a.len().min(b.len()) < KARATSUBA_THRESHOLD {
+        return mul_schoolbook(a, b);
+    }
+    let m = a.len().max(b.len()) / 2;
+    let (a0, a1) = split(a, m);
+    let (b0, b1) = split(b, m);
+
+    let z0 = mul_karatsuba(a0, b0);
+    let z2 = mul_karatsuba(a1, b1);
+    let mut z1 = mul_karatsuba(&add_digits(a0, a1), &add_digits(b0, b1));
+    sub_in_place(&mut z1, &z0);
+    sub_in_place(&mut z1, &z2);
+    trim(&mut z1);
+
+    let mut product = vec![0; a.len() + b.len() + 1];
+    add_shifted(&mut product, &z0, 0);
+    add_shifted(&mut product, &z1, m);
+    add_shifted(&mut product, &z2, 2 * m);
+    trim(&mut product);
+    product
+}
+
+// The low m digits, and the rest. Either half can be empty.
+fn split(digits: &[u8], m: usize) -> (&[u8], &[u8]) {
+    let (low, high) = digits.split_at(m.min(digits.len()));
+    // The low half can have leading zeros of its own:
+    let low_len = low.iter().rposition(|d| *d != 0).map_or(0, |i| i + 1);
+    (&low[..low_len], high)
+}
+
+// Adds `digits * 10^shift` into `into`, which must be long enough.
+fn add_shifted(into: &mut [u8], digits: &[u8], shift: usize) {
+    let mut carry = 0;
+    let mut i = shift;
+    for digit in digits {
+        let total = into[i] + digit + carry;
+        into[i] = total % 10;
+        carry = total / 10;
+        i += 1;
+    }
+    while carry > 0 {
+        let total = into[i] + carry;
+        into[i] = total % 10;
+        carry = total / 10;
+        i += 1;
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(mut n: u64) -> Self {
+        let mut digits = Vec::new();
+        while n > 0 {
+            digits.push((n % 10) as u8);
+            n /= 10;
+        }
+        BigUint { digits }
+    }
+}
+
+// Most significant digit first, as it's written. Leading zeros are
+// allowed.
+impl FromStr for BigUint {
+    type Err = ParseBigUintError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseBigUintError::Empty);
+        }
+        let mut digits = Vec::with_capacity(s.len());
+        for (index, c) in s.char_indices() {
+            match c.to_digit(10) {
+                Some(d) => digits.push(d as u8),
+                None => return Err(ParseBigUintError::InvalidDigit { index, found: c }),
+            }
+        }
+        digits.reverse();
+        Ok(BigUint::from_le_digits(digits))
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s: String = self
+            .to_le_digits()
+            .iter()
+            .rev()
+            .map(|d| char::from(b'0' + d))
+            .collect();
+        // Respects width and alignment:
+        f.pad_integral(true, "", &s)
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.digits
+            .len()
+            .cmp(&other.digits.len())
+            .then_with(|| self.digits.iter().rev().cmp(other.digits.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a> Add<&'a BigUint> for &'a BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        BigUint {
+            digits: add_digits(&self.digits, &other.digits),
+        }
+    }
+}
+
+// Panics if `other` is larger, like the primitive integers do. Use
+// `checked_sub` otherwise.
+impl<'a> Sub<&'a BigUint> for &'a BigUint {
+    type Output = BigUint;
+
+    fn sub(self, other: &BigUint) -> BigUint {
+        self.checked_sub(other)
+            .expect("attempt to subtract with overflow")
+    }
+}
+
+impl<'a> Mul<&'a BigUint> for &'a BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        self.mul_karatsuba(other)
+    }
+}
+
+// The same operators on owned values:
+macro_rules! forward_owned_op {
+    ($($op:ident, $method:ident;)*) => {
+        $(
+            impl $op for BigUint {
+                type Output = BigUint;
+
+                fn $method(self, other: BigUint) -> BigUint {
+                    (&self).$method(&other)
+                }
+            }
+        )*
+    };
+}
+
+forward_owned_op! {
+    Add, add;
+    Sub, sub;
+    Mul, mul;
+}
+
+// Path sums over trees of BigUints are summed as they are, since a
+// BigUint can't overflow:
+impl Widen for BigUint {
+    type Wide = BigUint;
+
+    fn widen(self) -> BigUint {
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigitOrder {
+    // The 1's digit is at the head: 617 is 7 -> 1 -> 6.
+    LeastSignificantFirst,
+    // As it's written: 617 is 6 -> 1 -> 7.
+    MostSignificantFirst,
+}
+
+// A number as a linked list of digits, in either order. Arithmetic
+// results come back in the order of the left-hand side.
+pub struct DigitList {
+    digits: fifth::List<u8>,
+    order: DigitOrder,
+}
+
+impl DigitList {
+    pub fn new(value: &BigUint, order: DigitOrder) -> Self {
+        let mut digits = fifth::List::new();
+        let le_digits = value.to_le_digits();
+        match order {
+            DigitOrder::LeastSignificantFirst => digits.extend(le_digits),
+            DigitOrder::MostSignificantFirst => digits.extend(le_digits.into_iter().rev()),
+        }
+        DigitList { digits, order }
+    }
+
+    // The digits in list order. Leading zeros are kept.
+    pub fn from_digits<I>(digits: I, order: DigitOrder) -> Result<Self, ParseBigUintError>
+    where
+        I: IntoIterator<Item = i32>,
+    {
+        let mut list = fifth::List::new();
+        for (index, digit) in digits.into_iter().enumerate() {
+            if !(0..10).contains(&digit) {
+                return Err(ParseBigUintError::DigitOutOfRange { index, digit });
+            }
+            list.push(digit as u8);
+        }
+        if list.is_empty() {
+            return Err(ParseBigUintError::Empty);
+        }
+        Ok(DigitList {
+            digits: list,
+            order,
+        })
+    }
+
+    pub fn order(&self) -> DigitOrder {
+        self.order
+    }
+
+    // The digits in list order.
+    pub fn digits(&self) -> Vec<i32> {
+        self.digits.iter().map(|d| i32::from(*d)).collect()
+    }
+
+    pub fn to_biguint(&self) -> BigUint {
+        let mut digits = self.digits.to_vec();
+        if self.order == DigitOrder::MostSignificantFirst {
+            digits.reverse();
+        }
+        BigUint::from_le_digits(digits)
+    }
+
+    pub fn with_order(&self, order: DigitOrder) -> DigitList {
+        DigitList::new(&self.to_biguint(), order)
+    }
+}
+
+impl Clone for DigitList {
+    fn clone(&self) -> Self {
+        let mut digits = fifth::List::new();
+        digits.extend(self.digits.iter().cloned());
+        DigitList {
+            digits,
+            order: self.order,
+        }
+    }
+}
+
+impl fmt::Debug for DigitList {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DigitList")
+            .field("digits", &self.digits)
+            .field("order", &self.order)
+            .finish()
+    }
+}
+
+// Displays the number, not the list.
+impl fmt::Display for DigitList {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.to_biguint(), f)
+    }
+}
+
+// Equal if they're the same number, whatever the order or leading
+// zeros.
+impl PartialEq for DigitList {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_biguint() == other.to_biguint()
+    }
+}
+
+impl Eq for DigitList {}
+
+impl PartialOrd for DigitList {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DigitList {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.to_biguint().cmp(&other.to_biguint())
+    }
+}
+
+impl FromStr for DigitList {
+    type Err = ParseBigUintError;
+
+    // Written most significant first, and stored that way.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value: BigUint = s.parse()?;
+        Ok(DigitList::new(&value, DigitOrder::MostSignificantFirst))
+    }
+}
+
+macro_rules! digit_list_op {
+    ($($op:ident, $method:ident;)*) => {
+        $(
+            impl<'a> $op<&'a DigitList> for &'a DigitList {
+                type Output = DigitList;
+
+                fn $method(self, other: &DigitList) -> DigitList {
+                    let value = (&self.to_biguint()).$method(&other.to_biguint());
+                    DigitList::new(&value, self.order)
+                }
+            }
+        )*
+    };
+}
+
+digit_list_op! {
+    Add, add;
+    Sub, sub;
+    Mul, mul;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn big(s: &str) -> BigUint {
+        s.parse().unwrap()
+    }
+
+    fn random_digits(rng: &mut StdRng, len: usize) -> String {
+        (0..len)
+            .map(|_| char::from(b'0' + rng.gen_range(0, 10) as u8))
+            .collect()
+    }
+
+    #[test]
+    fn parse_and_display() {
+        assert_eq!(big("0").to_string(), "0");
+        assert_eq!(big("000").to_string(), "0");
+        assert_eq!(big("00617").to_string(), "617");
+        assert_eq!(big("0"), BigUint::zero());
+        assert_eq!(big("18446744073709551615"), BigUint::from(u64::MAX));
+        assert_eq!(format!("{:>5}", big("42")), "   42");
+        assert_eq!("".parse::<BigUint>(), Err(ParseBigUintError::Empty));
+        assert_eq!(
+            "12a4".parse::<BigUint>(),
+            Err(ParseBigUintError::InvalidDigit {
+                index: 2,
+                found: 'a'
+            })
+        );
+        assert!("-1".parse::<BigUint>().is_err());
+        assert_eq!(big("617").num_digits(), 3);
+        assert_eq!(BigUint::zero().num_digits(), 1);
+    }
+
+    #[test]
+    fn compare() {
+        assert!(big("999") < big("1000"));
+        assert!(big("1000") > big("0999"));
+        assert!(big("617") > big("295"));
+        assert_eq!(big("0012").cmp(&big("12")), Ordering::Equal);
+        assert!(BigUint::zero() < big("1"));
+    }
+
+    #[test]
+    fn add_and_sub_unequal_lengths() {
+        assert_eq!(&big("999") + &big("1"), big("1000"));
+        assert_eq!(&big("617") + &big("295"), big("912"));
+        assert_eq!(big("5") + big("99995"), big("100000"));
+        assert_eq!(&big("1000") - &big("1"), big("999"));
+        assert_eq!(&big("100000") - &big("100000"), BigUint::zero());
+        assert_eq!(big("1").checked_sub(&big("2")), None);
+        assert_eq!(&big("12") + &BigUint::zero(), big("12"));
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to subtract with overflow")]
+    fn sub_underflow() {
+        let _ = big("99") - big("100");
+    }
+
+    #[test]
+    fn matches_u128() {
+        let mut rng = StdRng::seed_from_u64(41);
+        for _ in 0..500 {
+            let a: u64 = rng.gen_range(0, u64::MAX);
+            // So that b has any number of digits:
+            let bits = rng.gen_range(1, 64);
+            let b: u64 = rng.gen_range(0, 1u64 << bits);
+            let (x, y) = (BigUint::from(a), BigUint::from(b));
+            let (a, b) = (u128::from(a), u128::from(b));
+            assert_eq!((&x + &y).to_string(), (a + b).to_string());
+            assert_eq!((&x * &y).to_string(), (a * b).to_string());
+            assert_eq!(x.mul_schoolbook(&y).to_string(), (a * b).to_string());
+            assert_eq!(x.cmp(&y), a.cmp(&b));
+            match x.checked_sub(&y) {
+                Some(diff) => assert_eq!(diff.to_string(), (a - b).to_string()),
+                None => assert!(a < b),
+            }
+        }
+    }
+
+    #[test]
+    fn karatsuba_matches_schoolbook() {
+        let mut rng = StdRng::seed_from_u64(1729);
+        for &(n, m) in [(40, 40), (100, 33), (257, 256), (600, 31), (500, 1000)].iter() {
+            let a = big(&random_digits(&mut rng, n));
+            let b = big(&random_digits(&mut rng, m));
+            assert_eq!(a.mul_karatsuba(&b), a.mul_schoolbook(&b));
+        }
+        // Halves with leading zeros, and zero:
+        let a = big(&format!("1{}1", "0".repeat(100)));
+        assert_eq!(
+            a.mul_karatsuba(&a),
+            big(&format!("1{}2{}1", "0".repeat(100), "0".repeat(100)))
+        );
+        assert!(a.mul_karatsuba(&BigUint::zero()).is_zero());
+    }
+
+    #[test]
+    fn factorial() {
+        let mut product = BigUint::from(1);
+        for i in 1..=30 {
+            product = &product * &BigUint::from(i);
+        }
+        assert_eq!(product.to_string(), "265252859812191058636308480000000");
+        // (10^50 - 1)^2 = 10^100 - 2 * 10^50 + 1
+        let nines = big(&"9".repeat(50));
+        assert_eq!(
+            (&nines * &nines).to_string(),
+            format!("{}8{}1", "9".repeat(49), "0".repeat(49))
+        );
+    }
+
+    #[test]
+    fn digit_lists() {
+        use DigitOrder::*;
+        // 617 + 295 = 912, in both orders:
+        let a = DigitList::from_digits(vec![7, 1, 6], LeastSignificantFirst).unwrap();
+        let b = DigitList::from_digits(vec![5, 9, 2], LeastSignificantFirst).unwrap();
+        assert_eq!((&a + &b).digits(), vec![2, 1, 9]);
+        let a = a.with_order(MostSignificantFirst);
+        assert_eq!(a.digits(), vec![6, 1, 7]);
+        assert_eq!((&a + &b).digits(), vec![9, 1, 2]);
+        assert_eq!((&a + &b).order(), MostSignificantFirst);
+
+        // Unequal lengths, and leading zeros:
+        let c = DigitList::from_digits(vec![0, 0, 1], MostSignificantFirst).unwrap();
+        let d: DigitList = "999".parse().unwrap();
+        assert_eq!((&d + &c).digits(), vec![1, 0, 0, 0]);
+        assert_eq!((&d - &c).digits(), vec![9, 9, 8]);
+        assert_eq!((&d * &c).to_string(), "999");
+        assert_eq!(c, DigitList::new(&BigUint::from(1), LeastSignificantFirst));
+        assert!(c < d);
+        assert_eq!((&c - &c).digits(), vec![0]);
+
+        assert_eq!(
+            DigitList::from_digits(vec![1, 12], LeastSignificantFirst).unwrap_err(),
+            ParseBigUintError::DigitOutOfRange {
+                index: 1,
+                digit: 12
+            }
+        );
+        assert_eq!(
+            DigitList::from_digits(vec![], LeastSignificantFirst).unwrap_err(),
+            ParseBigUintError::Empty
+        );
+    }
+
+    #[test]
+    fn path_sums() {
+        use crate::binary_node::TreeRoot;
+        use crate::binary_tree::{BinaryTree, Node};
+        use crate::path_sum::{count_paths_with_sum, max_path_sum, paths_with_sum};
+
+        let big: BigUint = "99999999999999999999".parse().unwrap();
+        let bt = BinaryTree::new(Node::new(
+            big.clone(),
+            Node::new(BigUint::from(1), None, None),
+            Node::new(big.clone(), None, None),
+        ));
+        let sum: BigUint = "100000000000000000000".parse().unwrap();
+        assert_eq!(count_paths_with_sum(bt.root_node(), sum.clone()), 1);
+        assert_eq!(paths_with_sum(bt.root_node(), sum).len(), 1);
+        // Unsigned sums never need to go below zero:
+        assert_eq!(count_paths_with_sum(bt.root_node(), BigUint::from(1)), 1);
+        assert_eq!(
+            max_path_sum(bt.root_node()),
+            Some(&(&big + &big) + &BigUint::from(1))
+        );
+    }
+}
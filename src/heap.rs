@@ -0,0 +1,671 @@
+use std::fmt;
+use std::fmt::Display;
+use std::iter::FromIterator;
+use std::mem;
+
+// Binary heaps, stored as a complete binary tree in a Vec: the
+// children of index i are at 2i + 1 and 2i + 2. Unlike
+// `std::collections::BinaryHeap`, the ordering is chosen at runtime,
+// so a min-heap doesn't need its items wrapped in `Reverse`.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeapOrder {
+    // The largest item comes out first, as with std's BinaryHeap:
+    Max,
+    Min,
+}
+
+impl HeapOrder {
+    // Whether `a` belongs above `b` in the heap.
+    fn before<T: Ord>(self, a: &T, b: &T) -> bool {
+        match self {
+            HeapOrder::Max => a > b,
+            HeapOrder::Min => a < b,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Heap<T> {
+    data: Vec<T>,
+    order: HeapOrder,
+}
+
+impl<T: Ord> Default for Heap<T> {
+    fn default() -> Self {
+        Heap {
+            data: Vec::new(),
+            order: HeapOrder::Max,
+        }
+    }
+}
+
+impl<T> Heap<T>
+where
+    T: Ord,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Using the builder pattern to allow for optional arguments.
+    pub fn with_order(mut self, order: HeapOrder) -> Self {
+        self.order = order;
+        self.heapify();
+        self
+    }
+
+    // Using the builder pattern to allow for optional arguments.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.data.reserve(capacity);
+        self
+    }
+
+    pub fn create(self) -> Self {
+        self
+    }
+
+    // Builds a heap from unordered items in O(n), which is faster than
+    // pushing them one at a time.
+    pub fn from_vec(data: Vec<T>, order: HeapOrder) -> Self {
+        let mut heap = Heap { data, order };
+        heap.heapify();
+        heap
+    }
+
+    pub fn order(&self) -> HeapOrder {
+        self.order
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    // time: O(log(n))
+    pub fn push(&mut self, item: T) {
+        self.data.push(item);
+        let (last, order) = (self.data.len() - 1, self.order);
+        sift_up(&mut self.data, last, |a, b| order.before(a, b));
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    // time: O(log(n))
+    pub fn pop(&mut self) -> Option<T> {
+        let last = self.data.pop()?;
+        if self.data.is_empty() {
+            return Some(last);
+        }
+        let top = mem::replace(&mut self.data[0], last);
+        let order = self.order;
+        sift_down(&mut self.data, 0, |a, b| order.before(a, b));
+        Some(top)
+    }
+
+    // Replaces the top item, which is cheaper than a pop and a push.
+    pub fn replace_top(&mut self, item: T) -> Option<T> {
+        if self.data.is_empty() {
+            self.data.push(item);
+            return None;
+        }
+        let top = mem::replace(&mut self.data[0], item);
+        let order = self.order;
+        sift_down(&mut self.data, 0, |a, b| order.before(a, b));
+        Some(top)
+    }
+
+    // The items in no particular order.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.data.iter()
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+
+    // The items in the order they would be popped.
+    // time: O(n log(n))
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut sorted = Vec::with_capacity(self.len());
+        while let Some(item) = self.pop() {
+            sorted.push(item);
+        }
+        sorted
+    }
+
+    fn heapify(&mut self) {
+        let order = self.order;
+        for i in (0..self.data.len() / 2).rev() {
+            sift_down(&mut self.data, i, |a, b| order.before(a, b));
+        }
+    }
+}
+
+impl<T: Ord> FromIterator<T> for Heap<T> {
+    // Collects into a max-heap, like std's BinaryHeap. Use `from_vec`
+    // for a min-heap.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Heap::from_vec(iter.into_iter().collect(), HeapOrder::Max)
+    }
+}
+
+impl<T: Ord> Extend<T> for Heap<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+// Moves the item at `i` up until its parent belongs above it. Returns
+// its final index.
+fn sift_up<T, F>(data: &mut [T], mut i: usize, before: F) -> usize
+where
+    F: Fn(&T, &T) -> bool,
+{
+    while i > 0 {
+        let parent = (i - 1) / 2;
+        if !before(&data[i], &data[parent]) {
+            break;
+        }
+        data.swap(i, parent);
+        i = parent;
+    }
+    i
+}
+
+// Moves the item at `i` down until it belongs above both of its
+// children. Returns its final index.
+fn sift_down<T, F>(data: &mut [T], mut i: usize, before: F) -> usize
+where
+    F: Fn(&T, &T) -> bool,
+{
+    loop {
+        let (left, right) = (2 * i + 1, 2 * i + 2);
+        let mut first = i;
+        if left < data.len() && before(&data[left], &data[first]) {
+            first = left;
+        }
+        if right < data.len() && before(&data[right], &data[first]) {
+            first = right;
+        }
+        if first == i {
+            return i;
+        }
+        data.swap(i, first);
+        i = first;
+    }
+}
+
+// A handle to an item in an `IndexedPriorityQueue`. Handles stay
+// valid while their item is in the queue, and are never confused with
+// a later item that reuses the same slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Handle {
+    index: usize,
+    generation: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriorityQueueError {
+    // The item was already popped or removed:
+    InvalidHandle,
+    // `decrease_key` was given a priority larger than the current one:
+    PriorityIncreased,
+}
+
+impl Display for PriorityQueueError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PriorityQueueError::InvalidHandle => {
+                write!(f, "the handle's item is no longer in the queue")
+            }
+            PriorityQueueError::PriorityIncreased => {
+                write!(f, "decrease_key was given a larger priority")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PriorityQueueError {}
+
+#[derive(Debug, Clone)]
+struct Entry<T, P> {
+    item: T,
+    priority: P,
+    // The entry's index in `heap`:
+    position: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Slot<T, P> {
+    generation: u64,
+    entry: Option<Entry<T, P>>,
+}
+
+// A priority queue whose items can be found again through the handle
+// returned by `push`, to change their priority or remove them in
+// O(log(n)). This is the decrease-key operation that Dijkstra's and
+// Prim's algorithms need.
+//
+// The heap holds slot indices, and every slot records where its index
+// is in the heap, so the two have to be kept in sync on every swap.
+#[derive(Debug, Clone)]
+pub struct IndexedPriorityQueue<T, P> {
+    slots: Vec<Slot<T, P>>,
+    free: Vec<usize>,
+    heap: Vec<usize>,
+    order: HeapOrder,
+}
+
+impl<T, P: Ord> Default for IndexedPriorityQueue<T, P> {
+    fn default() -> Self {
+        IndexedPriorityQueue {
+            slots: Vec::new(),
+            free: Vec::new(),
+            heap: Vec::new(),
+            order: HeapOrder::Max,
+        }
+    }
+}
+
+impl<T, P> IndexedPriorityQueue<T, P>
+where
+    P: Ord,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Using the builder pattern to allow for optional arguments. This
+    // should be set before any items are pushed.
+    pub fn with_order(mut self, order: HeapOrder) -> Self {
+        assert!(
+            self.is_empty(),
+            "IndexedPriorityQueue.with_order: the queue must be empty"
+        );
+        self.order = order;
+        self
+    }
+
+    pub fn create(self) -> Self {
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    // time: O(log(n))
+    pub fn push(&mut self, item: T, priority: P) -> Handle {
+        let entry = Entry {
+            item,
+            priority,
+            position: self.heap.len(),
+        };
+        let index = match self.free.pop() {
+            Some(index) => {
+                self.slots[index].entry = Some(entry);
+                index
+            }
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    entry: Some(entry),
+                });
+                self.slots.len() - 1
+            }
+        };
+        self.heap.push(index);
+        self.sift_up(self.heap.len() - 1);
+        Handle {
+            index,
+            generation: self.slots[index].generation,
+        }
+    }
+
+    pub fn peek(&self) -> Option<(&T, &P)> {
+        let entry = self.entry_at(*self.heap.first()?);
+        Some((&entry.item, &entry.priority))
+    }
+
+    // time: O(log(n))
+    pub fn pop(&mut self) -> Option<(T, P)> {
+        let index = *self.heap.first()?;
+        Some(self.remove_at(index))
+    }
+
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.entry(handle).is_some()
+    }
+
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        self.entry(handle).map(|e| &e.item)
+    }
+
+    pub fn priority(&self, handle: Handle) -> Option<&P> {
+        self.entry(handle).map(|e| &e.priority)
+    }
+
+    // time: O(log(n))
+    pub fn remove(&mut self, handle: Handle) -> Option<(T, P)> {
+        self.entry(handle)?;
+        Some(self.remove_at(handle.index))
+    }
+
+    // Sets a new priority, and returns the old one.
+    // time: O(log(n))
+    pub fn change_priority(
+        &mut self,
+        handle: Handle,
+        priority: P,
+    ) -> Result<P, PriorityQueueError> {
+        self.entry(handle)
+            .ok_or(PriorityQueueError::InvalidHandle)?;
+        let entry = self.slots[handle.index].entry.as_mut().unwrap();
+        let old = mem::replace(&mut entry.priority, priority);
+        let position = entry.position;
+        // The entry only needs to move in one direction:
+        let position = self.sift_up(position);
+        self.sift_down(position);
+        Ok(old)
+    }
+
+    // Like `change_priority`, but only allows the priority to get
+    // smaller, which moves the item towards the top of a min-queue.
+    pub fn decrease_key(&mut self, handle: Handle, priority: P) -> Result<P, PriorityQueueError> {
+        let current = self
+            .priority(handle)
+            .ok_or(PriorityQueueError::InvalidHandle)?;
+        if priority > *current {
+            return Err(PriorityQueueError::PriorityIncreased);
+        }
+        self.change_priority(handle, priority)
+    }
+
+    fn entry(&self, handle: Handle) -> Option<&Entry<T, P>> {
+        let slot = self.slots.get(handle.index)?;
+        if slot.generation == handle.generation {
+            slot.entry.as_ref()
+        } else {
+            None
+        }
+    }
+
+    fn entry_at(&self, index: usize) -> &Entry<T, P> {
+        self.slots[index]
+            .entry
+            .as_ref()
+            .expect("IndexedPriorityQueue: invariant violated, the heap refers to an empty slot")
+    }
+
+    fn remove_at(&mut self, index: usize) -> (T, P) {
+        let position = self.entry_at(index).position;
+        let last = self.heap.len() - 1;
+        self.swap(position, last);
+        self.heap.pop();
+        if position < self.heap.len() {
+            // The item moved into the gap could belong above or below:
+            let position = self.sift_up(position);
+            self.sift_down(position);
+        }
+        let slot = &mut self.slots[index];
+        let entry = slot.entry.take().unwrap();
+        // Invalidate any handles to the removed item:
+        slot.generation += 1;
+        self.free.push(index);
+        (entry.item, entry.priority)
+    }
+
+    fn before(&self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.entry_at(self.heap[a]), self.entry_at(self.heap[b]));
+        self.order.before(&a.priority, &b.priority)
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        let (index_a, index_b) = (self.heap[a], self.heap[b]);
+        self.slots[index_a].entry.as_mut().unwrap().position = a;
+        self.slots[index_b].entry.as_mut().unwrap().position = b;
+    }
+
+    fn sift_up(&mut self, mut i: usize) -> usize {
+        while i > 0 {
+            let parent = (i - 1) / 2;
+            if !self.before(i, parent) {
+                break;
+            }
+            self.swap(i, parent);
+            i = parent;
+        }
+        i
+    }
+
+    fn sift_down(&mut self, mut i: usize) -> usize {
+        loop {
+            let (left, right) = (2 * i + 1, 2 * i + 2);
+            let mut first = i;
+            if left < self.heap.len() && self.before(left, first) {
+                first = left;
+            }
+            if right < self.heap.len() && self.before(right, first) {
+                first = right;
+            }
+            if first == i {
+                return i;
+            }
+            self.swap(i, first);
+            i = first;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{thread_rng, Rng};
+    use std::cmp::Reverse;
+    use std::collections::BinaryHeap;
+
+    #[test]
+    fn max_and_min_heaps() {
+        let mut max = Heap::new();
+        let mut min = Heap::new().with_order(HeapOrder::Min).create();
+        for i in [5, 3, 9, 1, 6, 0, 7].iter() {
+            max.push(*i);
+            min.push(*i);
+        }
+        assert_eq!(max.peek(), Some(&9));
+        assert_eq!(min.peek(), Some(&0));
+        assert_eq!(max.into_sorted_vec(), vec![9, 7, 6, 5, 3, 1, 0]);
+        assert_eq!(min.into_sorted_vec(), vec![0, 1, 3, 5, 6, 7, 9]);
+
+        let mut empty = Heap::<u32>::new();
+        assert_eq!(empty.pop(), None);
+        assert_eq!(empty.peek(), None);
+    }
+
+    #[test]
+    fn heapify_and_replace_top() {
+        let mut heap = Heap::from_vec(vec![4, 8, 1, 9, 2], HeapOrder::Min);
+        assert_eq!(heap.replace_top(5), Some(1));
+        assert_eq!(heap.len(), 5);
+        assert_eq!(heap.into_sorted_vec(), vec![2, 4, 5, 8, 9]);
+
+        // Changing the order of a filled heap re-heapifies it:
+        let heap: Heap<u32> = vec![4, 8, 1].into_iter().collect();
+        assert_eq!(heap.peek(), Some(&8));
+        let heap = heap.with_order(HeapOrder::Min);
+        assert_eq!(heap.peek(), Some(&1));
+    }
+
+    // Random pushes and pops, checked against std's BinaryHeap.
+    #[test]
+    fn heap_matches_std() {
+        let mut rng = thread_rng();
+        for _ in 0..50 {
+            let mut max = Heap::new();
+            let mut min = Heap::new().with_order(HeapOrder::Min).create();
+            let mut std_max = BinaryHeap::new();
+            let mut std_min = BinaryHeap::new();
+            for _ in 0..200 {
+                if rng.gen_range(0, 3) == 0 {
+                    assert_eq!(max.pop(), std_max.pop());
+                    assert_eq!(min.pop(), std_min.pop().map(|Reverse(i)| i));
+                } else {
+                    let i = rng.gen_range(0, 50);
+                    max.push(i);
+                    min.push(i);
+                    std_max.push(i);
+                    std_min.push(Reverse(i));
+                }
+                assert_eq!(max.peek(), std_max.peek());
+                assert_eq!(max.len(), std_max.len());
+            }
+            assert_eq!(
+                max.into_sorted_vec(),
+                std_max
+                    .into_sorted_vec()
+                    .into_iter()
+                    .rev()
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn indexed_queue_basics() {
+        let mut queue = IndexedPriorityQueue::new()
+            .with_order(HeapOrder::Min)
+            .create();
+        let a = queue.push("a", 5);
+        let b = queue.push("b", 3);
+        let c = queue.push("c", 8);
+        assert_eq!(queue.peek(), Some((&"b", &3)));
+
+        assert_eq!(queue.decrease_key(c, 1), Ok(8));
+        assert_eq!(queue.peek(), Some((&"c", &1)));
+        assert_eq!(
+            queue.decrease_key(a, 10),
+            Err(PriorityQueueError::PriorityIncreased)
+        );
+        assert_eq!(queue.change_priority(c, 10), Ok(1));
+        assert_eq!(queue.priority(c), Some(&10));
+
+        assert_eq!(queue.remove(b), Some(("b", 3)));
+        assert!(!queue.contains(b));
+        assert_eq!(queue.remove(b), None);
+        assert_eq!(
+            queue.change_priority(b, 0),
+            Err(PriorityQueueError::InvalidHandle)
+        );
+
+        assert_eq!(queue.pop(), Some(("a", 5)));
+        assert_eq!(queue.pop(), Some(("c", 10)));
+        assert_eq!(queue.pop(), None);
+    }
+
+    #[test]
+    fn stale_handles_are_rejected() {
+        let mut queue = IndexedPriorityQueue::new();
+        let old = queue.push('x', 1);
+        queue.pop();
+        // Reuses the freed slot:
+        let new = queue.push('y', 2);
+        assert_eq!(queue.get(old), None);
+        assert_eq!(queue.get(new), Some(&'y'));
+        assert_eq!(queue.remove(old), None);
+        assert_eq!(queue.len(), 1);
+    }
+
+    // Random operations, checked against std's BinaryHeap. Priority
+    // changes are modeled as a removal and a fresh push. Priorities
+    // are (priority, id) pairs, so that ties pop in the same order.
+    #[test]
+    fn indexed_queue_matches_std() {
+        let mut rng = thread_rng();
+        for _ in 0..30 {
+            let mut queue = IndexedPriorityQueue::new();
+            let mut model = BinaryHeap::new();
+            let mut handles: Vec<(Handle, u32)> = Vec::new();
+            for id in 0..300u32 {
+                match rng.gen_range(0, 4) {
+                    0 => {
+                        let popped = queue.pop().map(|(_, priority)| priority);
+                        assert_eq!(popped, model.pop());
+                    }
+                    1 if !handles.is_empty() => {
+                        let (handle, id) = handles.swap_remove(rng.gen_range(0, handles.len()));
+                        let priority = (rng.gen_range(0, 100), id);
+                        if let Ok(old) = queue.change_priority(handle, priority) {
+                            let mut items = model.into_vec();
+                            items.retain(|p| *p != old);
+                            model = items.into_iter().collect();
+                            model.push(priority);
+                            handles.push((handle, id));
+                        } else {
+                            assert!(model.iter().all(|(_, i)| *i != id));
+                        }
+                    }
+                    _ => {
+                        let priority = (rng.gen_range(0, 100), id);
+                        handles.push((queue.push(id, priority), id));
+                        model.push(priority);
+                    }
+                }
+                assert_eq!(queue.len(), model.len());
+                assert_eq!(queue.peek().map(|(_, priority)| priority), model.peek());
+            }
+        }
+    }
+
+    // Dijkstra's algorithm, where each node's tentative distance is
+    // lowered in place instead of pushing duplicates.
+    #[test]
+    fn dijkstra() {
+        let edges: Vec<Vec<(usize, u32)>> = vec![
+            vec![(1, 4), (2, 1)],
+            vec![(3, 1)],
+            vec![(1, 2), (3, 5)],
+            vec![(4, 3)],
+            vec![],
+        ];
+        let mut distances = vec![u32::MAX; edges.len()];
+        let mut queue = IndexedPriorityQueue::new()
+            .with_order(HeapOrder::Min)
+            .create();
+        let handles: Vec<Handle> = (0..edges.len())
+            .map(|node| queue.push(node, if node == 0 { 0 } else { u32::MAX }))
+            .collect();
+        while let Some((node, distance)) = queue.pop() {
+            distances[node] = distance;
+            if distance == u32::MAX {
+                continue;
+            }
+            for (next, weight) in edges[node].iter() {
+                if let Some(current) = queue.priority(handles[*next]) {
+                    if distance + weight < *current {
+                        queue
+                            .decrease_key(handles[*next], distance + weight)
+                            .unwrap();
+                    }
+                }
+            }
+        }
+        assert_eq!(distances, vec![0, 3, 1, 4, 7]);
+    }
+}
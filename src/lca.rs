@@ -0,0 +1,315 @@
+use std::collections::HashMap;
+
+use crate::binary_node::{BinaryNode, TreeRoot};
+
+// Lowest common ancestor queries, for when there are many queries
+// against the same tree. Building the index takes O(n log(n)), after
+// which every `lca` query takes O(1), and `distance` and `path` take
+// O(1) and O(path length).
+//
+// The index records an Euler tour of the tree: the nodes in the order
+// a depth-first walk visits them, including every time the walk comes
+// back up to a node. The LCA of a and b is the shallowest node on the
+// tour between the first visits of a and b, and a sparse table answers
+// that range-minimum query in O(1).
+//
+// The index is a snapshot: it needs to be rebuilt after the tree is
+// modified.
+pub struct LcaIndex<N> {
+    // Nodes are numbered in preorder:
+    nodes: Vec<N>,
+    parents: Vec<Option<usize>>,
+    depths: Vec<usize>,
+    // The position of each node's first visit on the tour:
+    first_visit: Vec<usize>,
+    tour: Vec<usize>,
+    // sparse[k][i] is the shallowest node on tour[i..i + 2^k]:
+    sparse: Vec<Vec<usize>>,
+    positions: HashMap<usize, usize>,
+}
+
+impl<N> LcaIndex<N>
+where
+    N: BinaryNode,
+{
+    pub fn new<'a, B>(tree: &'a B) -> Self
+    where
+        B: TreeRoot<'a, Node = N>,
+    {
+        Self::from_node(tree.root_node())
+    }
+
+    pub fn from_node(root: Option<N>) -> Self {
+        let mut index = LcaIndex {
+            nodes: Vec::new(),
+            parents: Vec::new(),
+            depths: Vec::new(),
+            first_visit: Vec::new(),
+            tour: Vec::new(),
+            sparse: Vec::new(),
+            positions: HashMap::new(),
+        };
+        if let Some(root) = root {
+            index.walk(root, None, 0);
+        }
+        index.build_sparse_table();
+        index
+    }
+
+    fn walk(&mut self, node: N, parent: Option<usize>, depth: usize) {
+        let i = self.nodes.len();
+        self.positions.insert(node.node_key(), i);
+        self.nodes.push(node.clone());
+        self.parents.push(parent);
+        self.depths.push(depth);
+        self.first_visit.push(self.tour.len());
+        self.tour.push(i);
+        for child in node.left().into_iter().chain(node.right()) {
+            self.walk(child, Some(i), depth + 1);
+            // Coming back up from the child:
+            self.tour.push(i);
+        }
+    }
+
+    fn build_sparse_table(&mut self) {
+        self.sparse.push(self.tour.clone());
+        let mut width = 1;
+        while width * 2 <= self.tour.len() {
+            let prev = self.sparse.last().unwrap();
+            let row = (0..=self.tour.len() - width * 2)
+                .map(|i| self.shallower(prev[i], prev[i + width]))
+                .collect();
+            self.sparse.push(row);
+            width *= 2;
+        }
+    }
+
+    fn shallower(&self, a: usize, b: usize) -> usize {
+        if self.depths[a] <= self.depths[b] {
+            a
+        } else {
+            b
+        }
+    }
+
+    fn position(&self, node: &N) -> Option<usize> {
+        let i = *self.positions.get(&node.node_key())?;
+        // Guard against a node from another tree that happens to have
+        // the same key:
+        if self.nodes[i].same_node(node) {
+            Some(i)
+        } else {
+            None
+        }
+    }
+
+    fn lca_index(&self, a: usize, b: usize) -> usize {
+        let (mut lo, mut hi) = (self.first_visit[a], self.first_visit[b]);
+        if lo > hi {
+            std::mem::swap(&mut lo, &mut hi);
+        }
+        // Two overlapping power-of-two windows cover tour[lo..=hi]:
+        let len = hi - lo + 1;
+        let k = (usize::BITS - 1 - len.leading_zeros()) as usize;
+        self.shallower(self.sparse[k][lo], self.sparse[k][hi + 1 - (1 << k)])
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn contains(&self, node: &N) -> bool {
+        self.position(node).is_some()
+    }
+
+    // Returns None if either node isn't in the tree.
+    pub fn lca(&self, a: &N, b: &N) -> Option<N> {
+        let (a, b) = (self.position(a)?, self.position(b)?);
+        Some(self.nodes[self.lca_index(a, b)].clone())
+    }
+
+    // The number of edges between the two nodes.
+    pub fn distance(&self, a: &N, b: &N) -> Option<usize> {
+        let (a, b) = (self.position(a)?, self.position(b)?);
+        let lca = self.lca_index(a, b);
+        Some(self.depths[a] + self.depths[b] - 2 * self.depths[lca])
+    }
+
+    // The nodes from a up to their LCA, and back down to b, including
+    // both ends.
+    pub fn path(&self, a: &N, b: &N) -> Option<Vec<N>> {
+        let (a, b) = (self.position(a)?, self.position(b)?);
+        let lca = self.lca_index(a, b);
+        let mut path = self.climb(a, lca);
+        let mut down = self.climb(b, lca);
+        down.pop(); // the LCA is already on the way up
+        down.reverse();
+        path.append(&mut down);
+        Some(path.into_iter().map(|i| self.nodes[i].clone()).collect())
+    }
+
+    // Nodes from `from` up to `ancestor`, inclusive.
+    fn climb(&self, from: usize, ancestor: usize) -> Vec<usize> {
+        let mut path = vec![from];
+        let mut node = from;
+        while node != ancestor {
+            node = self.parents[node].expect("LcaIndex: invariant violated, climbed past the root");
+            path.push(node);
+        }
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binary_tree::{BinaryTree, Node};
+    use crate::binary_tree_refcell::{BinaryTree as RCBinaryTree, Node as RCNode, Tree as RCTree};
+    use crate::tree_serialize::{Format, SerializeTree};
+    use rand::{thread_rng, Rng};
+    use std::rc::Rc;
+
+    fn data(path: &[&Node<u32>]) -> Vec<u32> {
+        path.iter().map(|n| n.data).collect()
+    }
+
+    #[test]
+    fn lca_binary_tree() {
+        //         1
+        //      2     3
+        //     4 5   6
+        //      7
+        let bt = BinaryTree::<u32>::deserialize("[1,2,3,4,5,6,null,null,7]", Format::LevelOrder)
+            .unwrap();
+        let root = bt.root.as_deref().unwrap();
+        let two = root.left.as_deref().unwrap();
+        let four = two.left.as_deref().unwrap();
+        let five = two.right.as_deref().unwrap();
+        let seven = four.right.as_deref().unwrap();
+        let six = root.right.as_deref().unwrap().left.as_deref().unwrap();
+
+        let index = LcaIndex::new(&bt);
+        assert_eq!(index.len(), 7);
+        assert_eq!(index.lca(&seven, &five).unwrap().data, 2);
+        assert_eq!(index.lca(&seven, &six).unwrap().data, 1);
+        assert_eq!(index.lca(&four, &seven).unwrap().data, 4);
+        assert_eq!(index.lca(&root, &root).unwrap().data, 1);
+
+        assert_eq!(index.distance(&seven, &five), Some(3));
+        assert_eq!(index.distance(&seven, &six), Some(5));
+        assert_eq!(index.distance(&five, &five), Some(0));
+
+        assert_eq!(
+            data(&index.path(&seven, &six).unwrap()),
+            vec![7, 4, 2, 1, 3, 6]
+        );
+        assert_eq!(data(&index.path(&two, &seven).unwrap()), vec![2, 4, 7]);
+        assert_eq!(data(&index.path(&six, &six).unwrap()), vec![6]);
+    }
+
+    #[test]
+    fn lca_node_not_in_tree() {
+        let bt = BinaryTree::new(Node::new(1, Node::new(2, None, None), None));
+        let other = BinaryTree::new(Node::new(2, None, None));
+        let index = LcaIndex::new(&bt);
+        let root = bt.root.as_deref().unwrap();
+        let stranger = other.root.as_deref().unwrap();
+        assert!(index.contains(&root));
+        assert!(!index.contains(&stranger));
+        assert!(index.lca(&root, &stranger).is_none());
+        assert!(index.distance(&root, &stranger).is_none());
+        assert!(index.path(&stranger, &root).is_none());
+
+        let empty = BinaryTree::<u32>::new(None);
+        let index = LcaIndex::new(&empty);
+        assert!(index.is_empty());
+        assert!(index.lca(&root, &root).is_none());
+    }
+
+    #[test]
+    fn lca_refcell_tree() {
+        // Same fixture as ch4-p8_first-common-ancestor:
+        let n1 = RCNode::new(1, RCNode::new(1, None, None), None);
+        let n2 = RCNode::new(2, None, RCNode::new(1, None, None));
+        let parent = RCNode::new(
+            2,
+            RCNode::new(1, RCNode::new(1, n2.clone(), None), None),
+            RCNode::new(1, None, RCNode::new(1, None, n1.clone())),
+        );
+        let bt = RCBinaryTree::new(RCNode::new(1, parent.clone(), None));
+        let (n1, n2, parent) = (n1.unwrap(), n2.unwrap(), parent.unwrap());
+
+        let index = LcaIndex::new(&bt);
+        assert!(Rc::ptr_eq(&index.lca(&n1, &n2).unwrap(), &parent));
+        assert_eq!(index.distance(&n1, &n2), Some(6));
+        let path = index.path(&n1, &n2).unwrap();
+        assert_eq!(path.len(), 7);
+        assert!(Rc::ptr_eq(&path[3], &parent));
+    }
+
+    // Builds a random tree of `size` nodes, numbered 0..size.
+    fn random_tree(size: u32, next: &mut u32) -> RCTree<u32> {
+        if size == 0 {
+            return None;
+        }
+        let data = *next;
+        *next += 1;
+        let left_size = thread_rng().gen_range(0, size);
+        let left = random_tree(left_size, next);
+        let right = random_tree(size - 1 - left_size, next);
+        RCNode::new(data, left, right)
+    }
+
+    fn all_nodes(node: &RCTree<u32>, nodes: &mut Vec<Rc<std::cell::RefCell<RCNode<u32>>>>) {
+        if let Some(n) = node {
+            nodes.push(n.clone());
+            all_nodes(&n.borrow().left, nodes);
+            all_nodes(&n.borrow().right, nodes);
+        }
+    }
+
+    // The slow way: walk both nodes up through their parent links.
+    fn naive_lca(
+        a: &Rc<std::cell::RefCell<RCNode<u32>>>,
+        b: &Rc<std::cell::RefCell<RCNode<u32>>>,
+    ) -> u32 {
+        let mut ancestors = Vec::new();
+        let mut node = Some(a.clone());
+        while let Some(n) = node {
+            ancestors.push(n.borrow().data);
+            node = n.borrow().parent();
+        }
+        let mut node = Some(b.clone());
+        while let Some(n) = node {
+            if ancestors.contains(&n.borrow().data) {
+                return n.borrow().data;
+            }
+            node = n.borrow().parent();
+        }
+        panic!("the nodes don't have a common ancestor!");
+    }
+
+    #[test]
+    fn lca_matches_naive() {
+        for size in [1, 2, 3, 17, 64, 100].iter() {
+            let bt = RCBinaryTree::new(random_tree(*size, &mut 0));
+            let mut nodes = Vec::new();
+            all_nodes(&bt.root, &mut nodes);
+            let index = LcaIndex::new(&bt);
+            for a in nodes.iter() {
+                for b in nodes.iter().step_by(3) {
+                    let lca = index.lca(a, b).unwrap();
+                    assert_eq!(lca.borrow().data, naive_lca(a, b));
+                    let path = index.path(a, b).unwrap();
+                    assert_eq!(path.len(), index.distance(a, b).unwrap() + 1);
+                    assert!(Rc::ptr_eq(&path[0], a));
+                    assert!(Rc::ptr_eq(path.last().unwrap(), b));
+                }
+            }
+        }
+    }
+}
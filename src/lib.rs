@@ -1,6 +1,14 @@
-mod linked_list;
-pub use linked_list::refcell::LinkedList;
-pub use linked_list::refcell_singly::{LinkedList as SinglyLinkedList, Node, NodeRef, HashedNode};
+pub mod linked_list;
+pub use linked_list::list::List;
+pub use linked_list::refcell::{CursorMut, LinkedList};
+pub use linked_list::refcell_singly::{
+    CycleDetected, HashedNode, Intersection, LinkedList as SinglyLinkedList, LoopInfo, Node,
+    NodeHandle, NodeRef, StaleHandle,
+};
+pub use linked_list::ms_queue::MsQueue;
+pub use linked_list::mutex_list::MutexLinkedList;
+pub use linked_list::treiber::TreiberStack;
+pub use linked_list::vec_list::{Index as VecListIndex, VecList};
 mod stack;
 mod binary_search_tree;
 mod graph;
@@ -14,3 +22,40 @@ mod binary_tree_refcell;
 pub use binary_tree_refcell::{BinaryTree as RCBinaryTree, Tree as RCBTree, Node as RCBTreeNode};
 mod binary_search_tree_refcell;
 pub use binary_search_tree_refcell::{BinarySearchTree as RCBinarySearchTree, Node as RCTreeNode, Tree as RCTree};
+// Nodes are stored in a Vec, and linked by index:
+mod binary_tree_arena;
+pub use binary_tree_arena::{ArenaTree, Node as ArenaTreeNode, NodeId};
+// Shared node access for all of the binary trees above:
+mod binary_node;
+pub use binary_node::{ArenaNodeRef, BinaryNode, BuildBinaryNode, TreeRoot};
+mod tree_serialize;
+pub use tree_serialize::{Format as TreeFormat, ParseTreeError, SerializeTree};
+mod tree_display;
+pub use tree_display::TreeDisplay;
+mod subtree;
+pub use subtree::{find_duplicate_subtrees, find_subtrees, is_subtree};
+mod lca;
+pub use lca::LcaIndex;
+mod path_sum;
+pub use path_sum::{
+    count_paths_with_sum, count_paths_with_sum_by, diameter, max_path_sum, max_path_sum_by,
+    paths_with_sum, paths_with_sum_by, Accumulator, Widen,
+};
+mod bst_sequences;
+pub use bst_sequences::BstSequences;
+mod bst_validate;
+pub use bst_validate::{
+    check_parent_links, validate_bst, BstViolation, Duplicates, Side, ValidateBst, Violation,
+};
+// Trees with more than two children per node:
+mod trie;
+pub use trie::{PrefixIter, Trie};
+mod nary_tree;
+pub use nary_tree::{
+    LevelOrder as NaryLevelOrder, Levels as NaryLevels, NaryTree, Node as NaryTreeNode,
+    Postorder as NaryPostorder, Preorder as NaryPreorder,
+};
+mod heap;
+pub use heap::{Handle, Heap, HeapOrder, IndexedPriorityQueue, PriorityQueueError};
+mod digit_list;
+pub use digit_list::{BigUint, DigitList, DigitOrder, ParseBigUintError};
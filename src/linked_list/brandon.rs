@@ -1,10 +1,10 @@
 use std::cell::RefCell;
-use std::collections::hash_map::DefaultHasher;
 use std::collections::HashSet;
 use std::fmt::Display;
-use std::hash::Hasher;
 use std::rc::Rc;
 use std::fmt;
+
+use super::{list, relink};
 // Inspired by:
 // https://raw.githubusercontent.com/brndnmtthws/cracking-the-coding-interview-rust/master/src/bin/c02p01.rs
 // but with some improvements:
@@ -15,7 +15,7 @@ use std::fmt;
 type NodeRef<T> = Rc<RefCell<Node<T>>>;
 
 #[derive(Debug)]
-struct LinkedList<T> {
+pub struct LinkedList<T> {
     head: Option<NodeRef<T>>,
 }
 
@@ -35,6 +35,22 @@ struct Iter<T> {
     next: Option<NodeRef<T>>,
 }
 
+impl<T> relink::Link for Node<T> {
+    type Data = T;
+
+    fn data(&self) -> &T {
+        &self.data
+    }
+
+    fn next_mut(&mut self) -> &mut Option<NodeRef<T>> {
+        &mut self.next
+    }
+
+    fn into_data(self) -> T {
+        self.data
+    }
+}
+
 impl<T> Node<T> {
     fn tail(node: &NodeRef<T>) -> Option<NodeRef<T>> {
         if let Some(cur) = node.borrow().next.as_ref().cloned() {
@@ -55,12 +71,7 @@ impl<T> Node<T> {
     }
 }
 
-impl<T> LinkedList<T>
-where
-    T: std::cmp::Eq,
-    T: std::hash::Hash,
-    T: std::clone::Clone,
-{
+impl<T> LinkedList<T> {
     fn new() -> Self {
         Self { head: None }
     }
@@ -98,35 +109,94 @@ where
             next: self.head.as_ref().cloned(),
         }
     }
+}
 
+impl<T> LinkedList<T>
+where
+    T: std::cmp::Eq,
+    T: std::hash::Hash,
+    T: std::clone::Clone,
+{
+    // The set holds the values themselves rather than their hashes, so
+    // that two different values with the same hash aren't duplicates.
     fn list_has_duplicates(&self) -> bool {
-        let mut set: HashSet<u64> = HashSet::new();
+        let mut set: HashSet<T> = HashSet::new();
         for node in self.iter() {
-            let data = &node.borrow().data;
-            let mut s = DefaultHasher::new();
-            data.hash(&mut s);
-            let hash = s.finish();
-            if set.contains(&hash) {
+            if !set.insert(node.borrow().data.clone()) {
                 return true;
             }
-            set.insert(hash);
         }
         false
     }
 
     fn remove_duplicates(&mut self) {
-        let mut set: HashSet<u64> = HashSet::new();
+        let mut set: HashSet<T> = HashSet::new();
         for node in self.iter() {
-            let mut s = DefaultHasher::new();
-            {
-                let data = &node.borrow().data;
-                data.hash(&mut s);
-            }
-            let hash = s.finish();
-            if set.contains(&hash) {
+            let is_new = set.insert(node.borrow().data.clone());
+            if !is_new {
                 node.borrow_mut().remove();
             }
-            set.insert(hash);
+        }
+    }
+}
+
+impl<T> Default for LinkedList<T> {
+    fn default() -> Self {
+        Self { head: None }
+    }
+}
+
+impl<T: Clone> list::List for LinkedList<T> {
+    type Elem = T;
+
+    fn push_front(&mut self, elem: T) {
+        let new_head = Rc::new(RefCell::new(Node {
+            data: elem,
+            next: self.head.take(),
+            prev: None,
+        }));
+        if let Some(old_head) = new_head.borrow().next.as_ref() {
+            old_head.borrow_mut().prev = Some(new_head.clone());
+        }
+        self.head = Some(new_head);
+    }
+
+    fn push_back(&mut self, elem: T) {
+        self.append(elem)
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        self.head.take().map(|head| {
+            self.head = head.borrow_mut().next.take();
+            if let Some(new_head) = self.head.as_ref() {
+                new_head.borrow_mut().prev = None;
+            }
+            relink::into_data(head)
+        })
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        let tail = self.tail()?;
+        let prev = tail.borrow_mut().prev.take();
+        match prev {
+            Some(prev) => prev.borrow_mut().next = None,
+            None => self.head = None,
+        }
+        Some(relink::into_data(tail))
+    }
+
+    fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    fn visit<F>(&self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        for node in self.iter() {
+            if !f(&node.borrow().data) {
+                break;
+            }
         }
     }
 }
@@ -1,138 +1,301 @@
 // largely based off of second.rs implementation
 // https://rust-unofficial.github.io/too-many-lists/fifth-final.html
+//
+// A queue: push to the back, pop from the front. Mixing a Box for the
+// head with a raw pointer for the tail is undefined behavior under
+// Stacked Borrows, since every `&mut` to the boxed nodes invalidates
+// the tail pointer. So every link is a raw pointer instead, and nodes
+// are only turned back into a Box when they're freed.
+//
+// Check the unsafe code with Miri:
+// cargo +nightly miri test --lib linked_list::fifth
 use std::collections::HashSet;
+use std::fmt;
+use std::hash::Hash;
+use std::marker::PhantomData;
 use std::ptr;
 
-#[derive(Debug)]
+use super::list;
+
 pub struct List<T> {
     head: Link<T>,
-    tail: *mut Node<T>, // DANGER - raw pointer
+    tail: Link<T>,
+    // The list owns its nodes, for variance and drop checking:
+    _boo: PhantomData<Box<Node<T>>>,
 }
 
-type Link<T> = Option<Box<Node<T>>>;
+type Link<T> = *mut Node<T>;
 
-#[derive(Debug)]
 struct Node<T> {
     elem: T,
     next: Link<T>,
 }
 
-impl<T> List<T>
-where
-    T: std::hash::Hash + std::cmp::Eq + std::marker::Copy,
-{
-    pub fn new() -> Self {
+pub struct IntoIter<T>(List<T>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop()
+    }
+}
+
+pub struct Iter<'a, T> {
+    next: Option<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.map(|node| {
+            // The node is alive for as long as the list is borrowed:
+            self.next = unsafe { node.next.as_ref() };
+            &node.elem
+        })
+    }
+}
+
+pub struct IterMut<'a, T> {
+    next: Option<&'a mut Node<T>>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.take().map(|node| {
+            self.next = unsafe { node.next.as_mut() };
+            &mut node.elem
+        })
+    }
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
         List {
-            head: None,
+            head: ptr::null_mut(),
             tail: ptr::null_mut(),
+            _boo: PhantomData,
         }
     }
+}
+
+impl<T> List<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.is_null()
+    }
 
     pub fn push(&mut self, elem: T) {
-        let mut new_tail = Box::new(Node {
-            elem: elem,
-            next: None,
-        });
-
-        let raw_tail: *mut _ = &mut *new_tail;
-
-        // Put the box in the right place, and then grab a reference to its Node
-        if !self.tail.is_null() {
-            // If the old tail existed, update it to point to the new tail
-            unsafe {
-                (*self.tail).next = Some(new_tail);
+        unsafe {
+            let new_tail = Box::into_raw(Box::new(Node {
+                elem,
+                next: ptr::null_mut(),
+            }));
+
+            if !self.tail.is_null() {
+                // If the old tail existed, update it to point to the new tail
+                (*self.tail).next = new_tail;
+            } else {
+                // Otherwise, update the head to point to it
+                self.head = new_tail;
             }
-        } else {
-            // Otherwise, update the head to point to it
-            self.head = Some(new_tail);
-        }
 
-        self.tail = raw_tail;
+            self.tail = new_tail;
+        }
     }
 
     pub fn pop(&mut self) -> Option<T> {
-        self.head.take().map(|head| {
-            let head = *head;
+        if self.head.is_null() {
+            return None;
+        }
+        unsafe {
+            // Take back ownership of the node, so that it's freed:
+            let head = Box::from_raw(self.head);
             self.head = head.next;
 
-            if self.head.is_none() {
+            if self.head.is_null() {
                 self.tail = ptr::null_mut();
             }
 
-            head.elem
-        })
+            Some(head.elem)
+        }
     }
 
-    // pub fn remove_dups(&mut self) {
-    //     let mut set: HashSet<T> = HashSet::new();
-    //     let mut curr: Link<T> = self.head.take();
-    //     let mut prev: Link<T> = None;
-    //     while curr.is_some() {
-    //         if set.contains(&curr.as_ref().unwrap().elem) {
-    //             let raw_prev: *mut _ = &mut *(prev.unwrap());
-    //             let raw_curr: *mut _ = &mut *(curr.unwrap());
-
-    //             unsafe {
-    //                 // create a raw pointer for our "next" node:
-    //                 let next: *mut _ = &mut (*(raw_curr)).next;
-    //                 if (*next).is_some() {
-    //                     // ERROR: cannot move out of dereference of
-    //                     // raw pointer (cannot move out of dereference
-    //                     // of raw pointer) [E0507]
-    //                     (*raw_prev).next = *next;
-    //                     // (*raw_prev).next = (*raw_curr).next;  // same error
-
-    //                     // ERROR: cannot move out of dereference of
-    //                     // raw pointer (cannot move out of dereference
-    //                     // of raw pointer) [E0507]
-    //                     curr = *next;
-
-    //                     // ERROR: cannot move out of dereference of
-    //                     // raw pointer (cannot move out of dereference
-    //                     // of raw pointer) [E0507]
-    //                     prev = Some(Box::new(*raw_prev));
-    //                 } else {
-    //                     // If the tail is being removed, then set tail
-    //                     // pointer to be prev:
-    //                     self.tail = raw_prev;
-    //                     (*raw_prev).next = None;
-    //                     // ERROR: cannot move out of dereference of
-    //                     // raw pointer (cannot move out of dereference
-    //                     // of raw pointer) [E0507]
-    //                     prev = Some(Box::new(*raw_curr));
-    //                     curr = None;
-    //                 }
-    //             }
-    //         } else {
-    //             set.insert(curr.as_ref().unwrap().elem);
-    //             curr = curr.unwrap().next;
-    //             prev = curr.take();
-    //         }
-    //     }
-    // }
-}
-
-impl<T: PartialEq> PartialEq for Node<T> {
-    fn eq(&self, other: &Self) -> bool {
-        self.elem == other.elem && self.next == other.next
+    pub fn peek(&self) -> Option<&T> {
+        unsafe { self.head.as_ref().map(|node| &node.elem) }
+    }
+
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        unsafe { self.head.as_mut().map(|node| &mut node.elem) }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        unsafe {
+            Iter {
+                next: self.head.as_ref(),
+            }
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        unsafe {
+            IterMut {
+                next: self.head.as_mut(),
+            }
+        }
+    }
+
+    // Removes every element that's equal to an earlier one, keeping
+    // the first occurrence.
+    // time: O(n), space: O(n) for the set of elements seen so far
+    pub fn remove_dups(&mut self)
+    where
+        T: Hash + Eq + Clone,
+    {
+        let mut seen = HashSet::new();
+        let mut prev: Link<T> = ptr::null_mut();
+        let mut curr = self.head;
+        unsafe {
+            while !curr.is_null() {
+                let next = (*curr).next;
+                if seen.insert((*curr).elem.clone()) {
+                    prev = curr;
+                } else {
+                    // `prev` can't be null, since the head is never a
+                    // duplicate:
+                    (*prev).next = next;
+                    if curr == self.tail {
+                        self.tail = prev;
+                    }
+                    drop(Box::from_raw(curr));
+                }
+                curr = next;
+            }
+        }
+    }
+}
+
+impl<T> list::List for List<T> {
+    type Elem = T;
+
+    fn push_front(&mut self, elem: T) {
+        let new_head = Box::into_raw(Box::new(Node {
+            elem,
+            next: self.head,
+        }));
+        if self.tail.is_null() {
+            self.tail = new_head;
+        }
+        self.head = new_head;
+    }
+
+    fn push_back(&mut self, elem: T) {
+        self.push(elem)
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        self.pop()
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        // Empty, or a single node:
+        if self.head == self.tail {
+            return self.pop();
+        }
+        unsafe {
+            // There are no back links, so walk to the node before the
+            // tail:
+            let mut new_tail = self.head;
+            while (*new_tail).next != self.tail {
+                new_tail = (*new_tail).next;
+            }
+            let old_tail = Box::from_raw(self.tail);
+            (*new_tail).next = ptr::null_mut();
+            self.tail = new_tail;
+            Some(old_tail.elem)
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    fn visit<F>(&self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        for elem in self.iter() {
+            if !f(elem) {
+                break;
+            }
+        }
+    }
+}
+
+impl<T> Drop for List<T> {
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+    }
+}
+
+impl<T> IntoIterator for List<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter(self)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a List<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut List<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
     }
+}
 
-    fn ne(&self, other: &Self) -> bool {
-        self.elem != other.elem || self.next != other.next
+impl<T> Extend<T> for List<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push(elem);
+        }
     }
 }
 
 impl<T: PartialEq> PartialEq for List<T> {
     fn eq(&self, other: &Self) -> bool {
-        self.head == other.head
+        self.iter().eq(other.iter())
     }
+}
 
-    fn ne(&self, other: &Self) -> bool {
-        self.head != other.head
+impl<T: Eq> Eq for List<T> {}
+
+impl<T: fmt::Debug> fmt::Debug for List<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
     }
 }
 
-impl<T: Eq> Eq for List<T> {}
+// The list owns its nodes through raw pointers, so it's only as
+// thread-safe as the elements are:
+unsafe impl<T: Send> Send for List<T> {}
+unsafe impl<T: Sync> Sync for List<T> {}
 
 #[cfg(test)]
 mod test {
@@ -193,18 +356,129 @@ mod test {
         assert_eq!(list, list2);
     }
 
-    // #[test]
-    // fn remove_dups() {
-    //     let mut list = List::new();
-    //     list.push(1);
-    //     list.push(2);
-    //     list.push(2);
-    //     list.push(3);
-    //     list.remove_dups();
-    //     let mut list2 = List::new();
-    //     list.push(1);
-    //     list.push(2);
-    //     list.push(3);
-    //     assert_eq!(list, list2);
-    // }
+    #[test]
+    fn no_trait_bounds() {
+        // Neither Copy, nor Hash, nor even PartialEq:
+        struct Opaque(String);
+        let mut list = List::new();
+        list.push(Opaque("a".to_string()));
+        list.push(Opaque("b".to_string()));
+        assert_eq!(list.peek().map(|o| o.0.as_str()), Some("a"));
+        assert_eq!(list.pop().map(|o| o.0), Some("a".to_string()));
+        assert!(!list.is_empty());
+        // The rest is freed on drop
+    }
+
+    #[test]
+    fn peek() {
+        let mut list = List::new();
+        assert!(list.peek().is_none());
+        assert!(list.peek_mut().is_none());
+        list.push(1);
+        list.push(2);
+        assert_eq!(list.peek(), Some(&1));
+        if let Some(value) = list.peek_mut() {
+            *value = 10;
+        }
+        assert_eq!(list.peek(), Some(&10));
+        assert_eq!(list.pop(), Some(10));
+    }
+
+    #[test]
+    fn into_iter() {
+        let mut list = List::new();
+        list.extend(vec![1, 2, 3]);
+        let mut iter = list.into_iter();
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.next(), Some(3));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn iter() {
+        let mut list = List::new();
+        list.extend(vec![1, 2, 3]);
+        let mut iter = list.iter();
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next(), Some(&2));
+        assert_eq!(iter.next(), Some(&3));
+        assert_eq!(iter.next(), None);
+        assert_eq!((&list).into_iter().count(), 3);
+    }
+
+    #[test]
+    fn iter_mut() {
+        let mut list = List::new();
+        list.extend(vec![1, 2, 3]);
+        for value in &mut list {
+            *value *= 10;
+        }
+        assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![10, 20, 30]);
+        // The tail pointer is still valid after handing out `&mut`s:
+        list.push(40);
+        assert_eq!(format!("{:?}", list), "[10, 20, 30, 40]");
+    }
+
+    // The pattern that's UB when the head is a Box: mutable access
+    // through the head, interleaved with pushes through the tail.
+    #[test]
+    fn miri_food() {
+        let mut list = List::new();
+
+        list.push(1);
+        list.push(2);
+        list.push(3);
+
+        assert!(list.pop() == Some(1));
+        list.push(4);
+        assert!(list.pop() == Some(2));
+        list.push(5);
+
+        assert!(list.peek() == Some(&3));
+        list.push(6);
+        if let Some(x) = list.peek_mut() {
+            *x *= 10;
+        }
+        assert!(list.peek() == Some(&30));
+        assert!(list.pop() == Some(30));
+
+        for elem in list.iter_mut() {
+            *elem *= 100;
+        }
+
+        let mut iter = list.iter();
+        assert_eq!(iter.next(), Some(&400));
+        assert_eq!(iter.next(), Some(&500));
+        assert_eq!(iter.next(), Some(&600));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next(), None);
+
+        assert!(list.pop() == Some(400));
+        if let Some(x) = list.peek_mut() {
+            *x *= 10;
+        }
+        assert!(list.peek() == Some(&5000));
+        list.push(7);
+
+        // Drop it on the ground and let the dtor exercise itself
+    }
+
+    #[test]
+    fn remove_dups() {
+        let mut list = List::new();
+        list.extend(vec![1, 2, 2, 3, 1, 3]);
+        list.remove_dups();
+        let mut list2 = List::new();
+        list2.extend(vec![1, 2, 3]);
+        assert_eq!(list, list2);
+
+        // The tail was a duplicate, so pushing must follow the new tail:
+        list.push(4);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+
+        let mut empty = List::<u32>::new();
+        empty.remove_dups();
+        assert!(empty.is_empty());
+    }
 }
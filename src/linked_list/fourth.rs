@@ -2,6 +2,8 @@
 use std::cell::{Ref, RefCell};
 use std::rc::Rc;
 
+use super::list;
+
 pub struct List<T> {
     head: Link<T>,
     tail: Link<T>,
@@ -73,6 +75,36 @@ impl<T> List<T> {
         })
     }
 
+    pub fn push_back(&mut self, elem: T) {
+        let new_tail = Node::new(elem);
+        match self.tail.take() {
+            Some(old_tail) => {
+                old_tail.borrow_mut().next = Some(new_tail.clone());
+                new_tail.borrow_mut().prev = Some(old_tail);
+                self.tail = Some(new_tail);
+            }
+            None => {
+                self.head = Some(new_tail.clone());
+                self.tail = Some(new_tail);
+            }
+        }
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.tail.take().map(|old_tail| {
+            match old_tail.borrow_mut().prev.take() {
+                Some(new_tail) => {
+                    new_tail.borrow_mut().next.take();
+                    self.tail = Some(new_tail);
+                }
+                None => {
+                    self.head.take();
+                }
+            }
+            Rc::try_unwrap(old_tail).ok().unwrap().into_inner().elem
+        })
+    }
+
     pub fn peek_front(&self) -> Option<Ref<T>> {
         self.head
             .as_ref()
@@ -80,6 +112,54 @@ impl<T> List<T> {
     }
 }
 
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> list::List for List<T> {
+    type Elem = T;
+
+    fn push_front(&mut self, elem: T) {
+        self.push_front(elem)
+    }
+
+    fn push_back(&mut self, elem: T) {
+        self.push_back(elem)
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        self.pop_back()
+    }
+
+    fn len(&self) -> usize {
+        let mut len = 0;
+        self.visit(|_| {
+            len += 1;
+            true
+        });
+        len
+    }
+
+    fn visit<F>(&self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut node = self.head.clone();
+        while let Some(n) = node {
+            if !f(&n.borrow().elem) {
+                break;
+            }
+            node = n.borrow().next.clone();
+        }
+    }
+}
+
 impl<T> Drop for List<T> {
     fn drop(&mut self) {
         while self.pop_front().is_some() {}
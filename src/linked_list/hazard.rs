@@ -0,0 +1,213 @@
+// Hazard pointers, so that the lock-free lists can free the nodes they
+// unlink while other threads might still be reading them.
+//
+// Before reading a node, a thread publishes its address in a hazard
+// slot, and checks the node is still linked in. A node that's unlinked
+// is retired rather than freed, and it's only freed once a scan finds
+// no slot holding its address. Every slot and retired node is kept in
+// a lock-free stack of its own, so nothing here blocks either.
+//
+// Check the unsafe code with Miri:
+// MIRIFLAGS="-Zmiri-many-seeds=0..8" cargo +nightly miri test --lib linked_list::treiber
+use std::ptr;
+use std::sync::atomic::{fence, AtomicBool, AtomicPtr, AtomicUsize, Ordering::SeqCst};
+
+// Retired nodes wait for a scan until there are this many:
+const SCAN_THRESHOLD: usize = 64;
+
+pub(super) struct Domain {
+    // Slots are reused, and only freed with the domain:
+    slots: AtomicPtr<Slot>,
+    retired: AtomicPtr<Retired>,
+    retired_count: AtomicUsize,
+}
+
+struct Slot {
+    hazard: AtomicPtr<()>,
+    in_use: AtomicBool,
+    next: *mut Slot,
+}
+
+struct Retired {
+    pointer: *mut (),
+    free: unsafe fn(*mut ()),
+    next: *mut Retired,
+}
+
+// A claimed slot. The slot is released when the guard is dropped.
+pub(super) struct Guard<'a> {
+    slot: &'a Slot,
+}
+
+impl Domain {
+    pub(super) fn new() -> Self {
+        Domain {
+            slots: AtomicPtr::new(ptr::null_mut()),
+            retired: AtomicPtr::new(ptr::null_mut()),
+            retired_count: AtomicUsize::new(0),
+        }
+    }
+
+    pub(super) fn guard(&self) -> Guard<'_> {
+        // Reuse a free slot if there is one:
+        let mut slot = self.slots.load(SeqCst);
+        while !slot.is_null() {
+            // Slots live as long as the domain:
+            let existing = unsafe { &*slot };
+            if existing
+                .in_use
+                .compare_exchange(false, true, SeqCst, SeqCst)
+                .is_ok()
+            {
+                return Guard { slot: existing };
+            }
+            slot = existing.next;
+        }
+
+        let slot = Box::into_raw(Box::new(Slot {
+            hazard: AtomicPtr::new(ptr::null_mut()),
+            in_use: AtomicBool::new(true),
+            next: ptr::null_mut(),
+        }));
+        let mut head = self.slots.load(SeqCst);
+        loop {
+            // Nothing else can see the new slot until it's pushed:
+            unsafe { (*slot).next = head };
+            match self.slots.compare_exchange(head, slot, SeqCst, SeqCst) {
+                Ok(_) => break,
+                Err(current) => head = current,
+            }
+        }
+        Guard {
+            slot: unsafe { &*slot },
+        }
+    }
+
+    // Frees the node once no guard protects it. It has to be unlinked
+    // already, so that no guard can start protecting it from now on,
+    // and it has to have come from a Box<T>.
+    pub(super) unsafe fn retire<T>(&self, pointer: *mut T) {
+        unsafe fn free<T>(pointer: *mut ()) {
+            drop(Box::from_raw(pointer as *mut T));
+        }
+        let retired = Box::into_raw(Box::new(Retired {
+            pointer: pointer as *mut (),
+            free: free::<T>,
+            next: ptr::null_mut(),
+        }));
+        // Counted before it's pushed, since a scan on another thread can
+        // free it (and take it off the count) as soon as it's pushed:
+        let count = self.retired_count.fetch_add(1, SeqCst) + 1;
+        self.push_retired(retired, retired);
+        if count >= SCAN_THRESHOLD {
+            self.scan();
+        }
+    }
+
+    // Pushes a chain of retired nodes, from `first` to `last`.
+    fn push_retired(&self, first: *mut Retired, last: *mut Retired) {
+        let mut head = self.retired.load(SeqCst);
+        loop {
+            unsafe { (*last).next = head };
+            match self.retired.compare_exchange(head, first, SeqCst, SeqCst) {
+                Ok(_) => return,
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    // Frees every retired node that isn't protected, and puts the rest
+    // back for next time.
+    fn scan(&self) {
+        let mut retired = self.retired.swap(ptr::null_mut(), SeqCst);
+        fence(SeqCst);
+        let mut hazards = Vec::new();
+        let mut slot = self.slots.load(SeqCst);
+        while !slot.is_null() {
+            let existing = unsafe { &*slot };
+            let hazard = existing.hazard.load(SeqCst);
+            if !hazard.is_null() {
+                hazards.push(hazard);
+            }
+            slot = existing.next;
+        }
+
+        let mut kept_first: *mut Retired = ptr::null_mut();
+        let mut kept_last: *mut Retired = ptr::null_mut();
+        let mut freed = 0;
+        while !retired.is_null() {
+            // The chain was taken off the stack, so this thread owns it:
+            let next = unsafe { (*retired).next };
+            let pointer = unsafe { (*retired).pointer };
+            if hazards.contains(&pointer) {
+                unsafe { (*retired).next = kept_first };
+                if kept_last.is_null() {
+                    kept_last = retired;
+                }
+                kept_first = retired;
+            } else {
+                unsafe {
+                    let retired = Box::from_raw(retired);
+                    (retired.free)(retired.pointer);
+                }
+                freed += 1;
+            }
+            retired = next;
+        }
+        self.retired_count.fetch_sub(freed, SeqCst);
+        if !kept_first.is_null() {
+            self.push_retired(kept_first, kept_last);
+        }
+    }
+}
+
+impl Drop for Domain {
+    fn drop(&mut self) {
+        // No guards can be left, since they borrow the domain:
+        let mut retired = *self.retired.get_mut();
+        while !retired.is_null() {
+            unsafe {
+                let boxed = Box::from_raw(retired);
+                (boxed.free)(boxed.pointer);
+                retired = boxed.next;
+            }
+        }
+        let mut slot = *self.slots.get_mut();
+        while !slot.is_null() {
+            let boxed = unsafe { Box::from_raw(slot) };
+            slot = boxed.next;
+        }
+    }
+}
+
+impl<'a> Guard<'a> {
+    // Loads the pointer, and keeps what it points to from being freed
+    // until the guard protects something else or is dropped. Retries
+    // until the pointer is the same after publishing it, since the
+    // node could have been retired in between.
+    pub(super) fn protect<T>(&self, source: &AtomicPtr<T>) -> *mut T {
+        let mut pointer = source.load(SeqCst);
+        loop {
+            self.slot.hazard.store(pointer as *mut (), SeqCst);
+            // Pairs with the fence in `scan`: either the scan sees the
+            // hazard, or this load sees the node unlinked.
+            fence(SeqCst);
+            let current = source.load(SeqCst);
+            if current == pointer {
+                return pointer;
+            }
+            pointer = current;
+        }
+    }
+
+    pub(super) fn clear(&self) {
+        self.slot.hazard.store(ptr::null_mut(), SeqCst);
+    }
+}
+
+impl<'a> Drop for Guard<'a> {
+    fn drop(&mut self) {
+        self.clear();
+        self.slot.in_use.store(false, SeqCst);
+    }
+}
@@ -0,0 +1,390 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+
+// The operations every list in this module supports, whatever it's
+// built from, so that the chapter 2 algorithms can be written once.
+//
+// Not every list can do everything in O(1): the singly linked lists
+// walk to the back for `push_back` and `pop_back`, and the persistent
+// `third::List` copies the nodes it can't share. The lists built on
+// Rc nodes clone an element on pop if something else still holds its
+// node, so they need `T: Clone`.
+pub trait List: Default {
+    type Elem;
+
+    fn push_front(&mut self, elem: Self::Elem);
+    fn push_back(&mut self, elem: Self::Elem);
+    fn pop_front(&mut self) -> Option<Self::Elem>;
+    fn pop_back(&mut self) -> Option<Self::Elem>;
+    fn len(&self) -> usize;
+
+    // Calls `f` on each element from front to back, until it returns
+    // false. Elements are only borrowed for the call, since the
+    // RefCell lists can't hand out references that outlive it.
+    fn visit<F>(&self, f: F)
+    where
+        F: FnMut(&Self::Elem) -> bool;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn to_vec(&self) -> Vec<Self::Elem>
+    where
+        Self::Elem: Clone,
+    {
+        let mut values = Vec::new();
+        self.visit(|elem| {
+            values.push(elem.clone());
+            true
+        });
+        values
+    }
+
+    // Builds the list with push_front, which is O(1) for every list.
+    fn from_values<I>(values: I) -> Self
+    where
+        I: IntoIterator<Item = Self::Elem>,
+    {
+        let mut list = Self::default();
+        let values: Vec<Self::Elem> = values.into_iter().collect();
+        for value in values.into_iter().rev() {
+            list.push_front(value);
+        }
+        list
+    }
+
+    // Removes every element, front to back.
+    fn drain_to_vec(&mut self) -> Vec<Self::Elem> {
+        let mut values = Vec::new();
+        while let Some(value) = self.pop_front() {
+            values.push(value);
+        }
+        values
+    }
+
+    // Element-wise equality with any other kind of list.
+    fn list_eq<L>(&self, other: &L) -> bool
+    where
+        L: List<Elem = Self::Elem>,
+        Self::Elem: PartialEq + Clone,
+    {
+        if self.len() != other.len() {
+            return false;
+        }
+        let others = other.to_vec();
+        let mut others = others.iter();
+        let mut equal = true;
+        self.visit(|elem| {
+            equal = others.next() == Some(elem);
+            equal
+        });
+        equal
+    }
+}
+
+// Rebuilds the list from values in front to back order.
+fn refill<L: List>(list: &mut L, values: Vec<L::Elem>) {
+    for value in values.into_iter().rev() {
+        list.push_front(value);
+    }
+}
+
+// 2.1: keeps the first occurrence of every element, with a hash set
+// as the buffer. `unique_in_place` is the version without one.
+pub fn remove_dups<L>(list: &mut L)
+where
+    L: List,
+    L::Elem: Hash + Eq,
+{
+    unique(list)
+}
+
+// Keeps the first occurrence of every element.
+// time: O(n), space: O(n)
+pub fn unique<L>(list: &mut L)
+where
+    L: List,
+    L::Elem: Hash + Eq,
+{
+    let values = list.drain_to_vec();
+    let keep: Vec<bool> = {
+        let mut seen = HashSet::new();
+        values.iter().map(|value| seen.insert(value)).collect()
+    };
+    let unique = values
+        .into_iter()
+        .zip(keep)
+        .filter(|(_, keep)| *keep)
+        .map(|(value, _)| value)
+        .collect();
+    refill(list, unique);
+}
+
+// Keeps the first occurrence of every element, checking each one
+// against the ones kept so far instead of remembering them: the list
+// is rotated through, popping from the front and pushing what's kept
+// onto the back.
+// time: O(n²), space: O(1)
+pub fn unique_in_place<L>(list: &mut L)
+where
+    L: List,
+    L::Elem: PartialEq,
+{
+    let len = list.len();
+    for unchecked in (0..len).rev() {
+        let elem = list.pop_front().unwrap();
+        // The kept elements are the ones after the unchecked ones:
+        let mut index = 0;
+        let mut seen = false;
+        list.visit(|other| {
+            seen = index >= unchecked && *other == elem;
+            index += 1;
+            !seen
+        });
+        if !seen {
+            list.push_back(elem);
+        }
+    }
+}
+
+// Removes every element that's the same as the one before it, as
+// decided by `same(elem, previous)`, like `Vec::dedup_by`. Rebuilt
+// through a Vec like `unique`, since pushing to the back isn't O(1) on
+// every list.
+// time: O(n), space: O(n)
+pub fn dedup_by<L, F>(list: &mut L, mut same: F)
+where
+    L: List,
+    F: FnMut(&L::Elem, &L::Elem) -> bool,
+{
+    let mut values = list.drain_to_vec();
+    values.dedup_by(|elem, previous| same(elem, previous));
+    refill(list, values);
+}
+
+// Removes consecutive repeated elements.
+pub fn dedup<L>(list: &mut L)
+where
+    L: List,
+    L::Elem: PartialEq,
+{
+    dedup_by(list, |elem, previous| elem == previous)
+}
+
+// Removes consecutive elements with the same key.
+pub fn dedup_by_key<L, K, F>(list: &mut L, mut key: F)
+where
+    L: List,
+    K: PartialEq,
+    F: FnMut(&L::Elem) -> K,
+{
+    dedup_by(list, |elem, previous| key(elem) == key(previous))
+}
+
+// 2.2: the element k from the back, where 0 is the last one.
+// time: O(n), space: O(1)
+pub fn kth_to_last<L>(list: &L, k: usize) -> Option<L::Elem>
+where
+    L: List,
+    L::Elem: Clone,
+{
+    let index = list.len().checked_sub(k + 1)?;
+    let mut found = None;
+    let mut i = 0;
+    list.visit(|elem| {
+        if i == index {
+            found = Some(elem.clone());
+        }
+        i += 1;
+        found.is_none()
+    });
+    found
+}
+
+// 2.4: moves every element less than the pivot before every element
+// that isn't. Both halves keep their order.
+// time: O(n), space: O(n)
+pub fn partition<L>(list: &mut L, pivot: &L::Elem)
+where
+    L: List,
+    L::Elem: PartialOrd,
+{
+    let (mut below, rest): (Vec<L::Elem>, Vec<L::Elem>) = list
+        .drain_to_vec()
+        .into_iter()
+        .partition(|value| value < pivot);
+    below.extend(rest);
+    refill(list, below);
+}
+
+pub fn is_partitioned<L>(list: &L, pivot: &L::Elem) -> bool
+where
+    L: List,
+    L::Elem: PartialOrd,
+{
+    let mut is_above = false;
+    let mut partitioned = true;
+    list.visit(|elem| {
+        if elem < pivot {
+            partitioned = !is_above;
+        } else {
+            is_above = true;
+        }
+        partitioned
+    });
+    partitioned
+}
+
+// 2.6
+// time: O(n), space: O(n)
+pub fn is_palindrome<L>(list: &L) -> bool
+where
+    L: List,
+    L::Elem: PartialEq + Clone,
+{
+    let values = list.to_vec();
+    values.iter().eq(values.iter().rev())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linked_list::{
+        brandon, fifth, fourth, mutex_list, refcell, refcell_singly, second, third, vec_list,
+    };
+
+    fn deque_ops<L>()
+    where
+        L: List<Elem = i32>,
+    {
+        let mut list = L::default();
+        assert!(list.is_empty());
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.pop_back(), None);
+
+        list.push_back(2);
+        list.push_front(1);
+        list.push_back(3);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.to_vec(), vec![1, 2, 3]);
+
+        assert_eq!(list.pop_back(), Some(3));
+        assert_eq!(list.pop_front(), Some(1));
+        assert_eq!(list.pop_back(), Some(2));
+        assert_eq!(list.pop_front(), None);
+        assert!(list.is_empty());
+
+        // The back is still right after emptying the list:
+        list.push_front(5);
+        list.push_back(6);
+        assert_eq!(list.pop_back(), Some(6));
+        assert_eq!(list.pop_back(), Some(5));
+        assert_eq!(list.pop_back(), None);
+    }
+
+    fn algorithms<L>()
+    where
+        L: List<Elem = i32>,
+    {
+        let mut list = L::from_values(vec![1, 2, 2, 3, 1, 3, 4]);
+        remove_dups(&mut list);
+        assert_eq!(list.to_vec(), vec![1, 2, 3, 4]);
+        assert!(list.list_eq(&second::List::from_values(vec![1, 2, 3, 4])));
+        assert!(!list.list_eq(&second::List::from_values(vec![1, 2, 3])));
+        assert!(!list.list_eq(&second::List::from_values(vec![1, 2, 3, 5])));
+
+        assert_eq!(kth_to_last(&list, 0), Some(4));
+        assert_eq!(kth_to_last(&list, 3), Some(1));
+        assert_eq!(kth_to_last(&list, 4), None);
+
+        let mut list = L::from_values(vec![3, 5, 8, 5, 10, 2, 1]);
+        assert!(!is_partitioned(&list, &5));
+        partition(&mut list, &5);
+        assert!(is_partitioned(&list, &5));
+        assert_eq!(list.to_vec(), vec![3, 2, 1, 5, 8, 5, 10]);
+
+        assert!(is_palindrome(&L::from_values(vec![1, 2, 3, 2, 1])));
+        assert!(is_palindrome(&L::from_values(vec![1, 2, 2, 1])));
+        assert!(is_palindrome(&L::default()));
+        assert!(!is_palindrome(&L::from_values(vec![1, 2, 3])));
+    }
+
+    fn dedup_algorithms<L>()
+    where
+        L: List<Elem = i32>,
+    {
+        let values = vec![1, 1, 2, 3, 3, 3, 1, 2, 2];
+        let mut list = L::from_values(values.clone());
+        dedup(&mut list);
+        assert_eq!(list.to_vec(), vec![1, 2, 3, 1, 2]);
+
+        let mut list = L::from_values(vec![1, 3, 5, 2, 4, 7, 6]);
+        dedup_by_key(&mut list, |x| x % 2);
+        assert_eq!(list.to_vec(), vec![1, 2, 7, 6]);
+
+        let mut list = L::from_values(values.clone());
+        unique(&mut list);
+        assert_eq!(list.to_vec(), vec![1, 2, 3]);
+        let mut list = L::from_values(values);
+        unique_in_place(&mut list);
+        assert_eq!(list.to_vec(), vec![1, 2, 3]);
+
+        for values in [vec![], vec![4], vec![4, 4]].iter() {
+            let mut list = L::from_values(values.clone());
+            unique_in_place(&mut list);
+            dedup(&mut list);
+            assert_eq!(list.len(), values.len().min(1));
+        }
+    }
+
+    // f64 isn't Hash or Eq, so only the algorithms that don't need
+    // them work, but they work on every list:
+    fn without_hash<L>()
+    where
+        L: List<Elem = f64>,
+    {
+        let mut list = L::from_values(vec![0.5, 1.5, 0.5, 2.5, 1.5]);
+        unique_in_place(&mut list);
+        assert_eq!(list.to_vec(), vec![0.5, 1.5, 2.5]);
+
+        let mut list = L::from_values(vec![0.5, 0.5, 1.5, 0.5]);
+        dedup(&mut list);
+        assert_eq!(list.to_vec(), vec![0.5, 1.5, 0.5]);
+        partition(&mut list, &1.0);
+        assert_eq!(list.to_vec(), vec![0.5, 0.5, 1.5]);
+    }
+
+    #[test]
+    fn unique_without_hash() {
+        without_hash::<fifth::List<f64>>();
+        without_hash::<brandon::LinkedList<f64>>();
+        without_hash::<refcell::LinkedList<f64>>();
+        without_hash::<refcell_singly::LinkedList<f64>>();
+    }
+
+    macro_rules! every_list {
+        ($($name:ident: $list:ty,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    deque_ops::<$list>();
+                    algorithms::<$list>();
+                    dedup_algorithms::<$list>();
+                }
+            )*
+        };
+    }
+
+    every_list! {
+        second_list: second::List<i32>,
+        third_list: third::List<i32>,
+        fourth_list: fourth::List<i32>,
+        fifth_list: fifth::List<i32>,
+        brandon_list: brandon::LinkedList<i32>,
+        refcell_list: refcell::LinkedList<i32>,
+        refcell_singly_list: refcell_singly::LinkedList<i32>,
+        mutex_list: mutex_list::MutexLinkedList<i32>,
+        vec_list: vec_list::VecList<i32>,
+    }
+}
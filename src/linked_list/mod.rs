@@ -11,11 +11,28 @@ pub mod fourth;
 pub mod brandon;
 
 // unsafe version, which is closest to the official implementation,
-// and how linked lists *should* be implemented, but I ran into some
-// bugs that I couldn't resolve...
+// and how linked lists *should* be implemented. Raw pointers all the
+// way down, so that it passes Miri:
 pub mod fifth;
 
 // custom refcell implementation:
 pub mod refcell;
 // custom refcell implementation, as a single linked list:
 pub mod refcell_singly;
+// doubly linked list with its nodes in a Vec, linked by index:
+pub mod vec_list;
+
+// lock-free lists that can be shared between threads, freeing nodes
+// through hazard pointers:
+pub mod ms_queue;
+pub mod treiber;
+mod hazard;
+// doubly linked list behind a Mutex, for sharing between threads:
+pub mod mutex_list;
+
+// one interface for all of the lists above, and the chapter 2
+// algorithms written against it:
+pub mod list;
+
+// relinking algorithms shared by the refcell lists:
+mod relink;
@@ -0,0 +1,249 @@
+// A lock-free queue (M. M. Michael and M. L. Scott, 1996). The head is
+// always a dummy node, and the element at the front lives in the node
+// after it: popping swings the head to that node, which becomes the
+// new dummy. The tail can lag a node behind, and any thread that sees
+// it lagging helps it along before retrying. Unlinked dummies are
+// freed through hazard pointers.
+//
+// Check the unsafe code with Miri:
+// cargo +nightly miri test --lib linked_list::ms_queue
+use std::fmt;
+use std::mem::MaybeUninit;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+use super::hazard::Domain;
+
+pub struct MsQueue<T> {
+    head: AtomicPtr<Node<T>>,
+    tail: AtomicPtr<Node<T>>,
+    domain: Domain,
+}
+
+struct Node<T> {
+    // Uninitialized in the first dummy, and moved out of the node that
+    // becomes the dummy when it's popped:
+    elem: MaybeUninit<T>,
+    next: AtomicPtr<Node<T>>,
+}
+
+impl<T> Node<T> {
+    fn new(elem: MaybeUninit<T>) -> *mut Self {
+        Box::into_raw(Box::new(Node {
+            elem,
+            next: AtomicPtr::new(ptr::null_mut()),
+        }))
+    }
+}
+
+// Elements are sent to whichever thread pops them.
+unsafe impl<T: Send> Send for MsQueue<T> {}
+unsafe impl<T: Send> Sync for MsQueue<T> {}
+
+impl<T> MsQueue<T> {
+    pub fn new() -> Self {
+        let dummy = Node::new(MaybeUninit::uninit());
+        MsQueue {
+            head: AtomicPtr::new(dummy),
+            tail: AtomicPtr::new(dummy),
+            domain: Domain::new(),
+        }
+    }
+
+    pub fn push(&self, elem: T) {
+        let node = Node::new(MaybeUninit::new(elem));
+        let guard = self.domain.guard();
+        loop {
+            let tail = guard.protect(&self.tail);
+            // The guard keeps the tail node alive:
+            let next = unsafe { (*tail).next.load(Ordering::Acquire) };
+            if !next.is_null() {
+                // The tail is lagging, so help it along:
+                let _ = self
+                    .tail
+                    .compare_exchange(tail, next, Ordering::AcqRel, Ordering::Acquire);
+                continue;
+            }
+            let linked = unsafe {
+                (*tail).next.compare_exchange(
+                    ptr::null_mut(),
+                    node,
+                    Ordering::AcqRel,
+                    Ordering::Acquire,
+                )
+            };
+            if linked.is_ok() {
+                // If this fails, another thread already helped:
+                let _ = self
+                    .tail
+                    .compare_exchange(tail, node, Ordering::AcqRel, Ordering::Acquire);
+                return;
+            }
+        }
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        let head_guard = self.domain.guard();
+        let next_guard = self.domain.guard();
+        loop {
+            let head = head_guard.protect(&self.head);
+            // The head guard keeps the dummy alive, and so its `next`:
+            let next = next_guard.protect(unsafe { &(*head).next });
+            // Only if the dummy is still the head is `next` still linked
+            // in, and so protected in time:
+            if self.head.load(Ordering::Acquire) != head {
+                continue;
+            }
+            if next.is_null() {
+                return None;
+            }
+            let tail = self.tail.load(Ordering::Acquire);
+            if head == tail {
+                // Don't let the head pass the tail:
+                let _ = self
+                    .tail
+                    .compare_exchange(tail, next, Ordering::AcqRel, Ordering::Acquire);
+                continue;
+            }
+            if self
+                .head
+                .compare_exchange(head, next, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+            {
+                head_guard.clear();
+                unsafe {
+                    // Only the thread that unlinked the dummy takes the
+                    // element out of its successor, the new dummy:
+                    let elem = ptr::read((*next).elem.as_ptr());
+                    self.domain.retire(head);
+                    return Some(elem);
+                }
+            }
+        }
+    }
+
+    // A snapshot: other threads can change the queue straight after.
+    pub fn is_empty(&self) -> bool {
+        let guard = self.domain.guard();
+        let head = guard.protect(&self.head);
+        unsafe { (*head).next.load(Ordering::Acquire).is_null() }
+    }
+}
+
+impl<T> Default for MsQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for MsQueue<T> {
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+        // The dummy is all that's left, and its element is gone:
+        unsafe { drop(Box::from_raw(*self.head.get_mut())) };
+    }
+}
+
+impl<T> fmt::Debug for MsQueue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MsQueue")
+            .field("is_empty", &self.is_empty())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::MsQueue;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    // Fewer rounds under Miri, which is much slower:
+    const ROUNDS: usize = if cfg!(miri) { 50 } else { 20_000 };
+    const THREADS: usize = 4;
+
+    #[test]
+    fn basics() {
+        let queue = MsQueue::new();
+        assert_eq!(queue.pop(), None);
+        assert!(queue.is_empty());
+        queue.push(1);
+        queue.push(2);
+        queue.push(3);
+        assert!(!queue.is_empty());
+        assert_eq!(queue.pop(), Some(1));
+        assert_eq!(queue.pop(), Some(2));
+        queue.push(4);
+        assert_eq!(queue.pop(), Some(3));
+        assert_eq!(queue.pop(), Some(4));
+        assert_eq!(queue.pop(), None);
+        assert!(queue.is_empty());
+    }
+
+    struct Counted(Arc<AtomicUsize>);
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.0.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn drops_every_element_once() {
+        let alive = Arc::new(AtomicUsize::new(0));
+        let queue = MsQueue::new();
+        for _ in 0..100 {
+            alive.fetch_add(1, Ordering::SeqCst);
+            queue.push(Counted(alive.clone()));
+        }
+        for _ in 0..40 {
+            queue.pop();
+        }
+        assert_eq!(alive.load(Ordering::SeqCst), 60);
+        drop(queue);
+        assert_eq!(alive.load(Ordering::SeqCst), 0);
+    }
+
+    // Each consumer sees every producer's elements in the order they
+    // were pushed, and every element comes out exactly once.
+    #[test]
+    fn stress() {
+        let queue = Arc::new(MsQueue::new());
+        let producers: Vec<_> = (0..THREADS / 2)
+            .map(|t| {
+                let queue = queue.clone();
+                thread::spawn(move || {
+                    for i in 0..ROUNDS {
+                        queue.push((t, i));
+                    }
+                })
+            })
+            .collect();
+        let consumers: Vec<_> = (0..THREADS / 2)
+            .map(|_| {
+                let queue = queue.clone();
+                thread::spawn(move || {
+                    let mut last = [None; THREADS / 2];
+                    let mut count = 0;
+                    while count < ROUNDS {
+                        if let Some((t, i)) = queue.pop() {
+                            assert!(last[t] < Some(i), "out of order");
+                            last[t] = Some(i);
+                            count += 1;
+                        } else {
+                            thread::yield_now();
+                        }
+                    }
+                    count
+                })
+            })
+            .collect();
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        let popped: usize = consumers.into_iter().map(|c| c.join().unwrap()).sum();
+        assert_eq!(popped, ROUNDS * (THREADS / 2));
+        assert!(queue.is_empty());
+    }
+}
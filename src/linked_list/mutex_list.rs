@@ -0,0 +1,379 @@
+// A doubly linked list behind a single Mutex, so that it can be shared
+// between threads: every method takes `&self` and holds the lock for
+// the whole operation. Simpler than the lock-free lists, and it can do
+// what they can't (both ends, retain), but threads wait for each other.
+//
+// Check the unsafe code with Miri:
+// cargo +nightly miri test --lib linked_list::mutex_list
+use std::fmt;
+use std::marker::PhantomData;
+use std::ptr;
+use std::sync::{Mutex, MutexGuard};
+
+use super::list;
+
+pub struct MutexLinkedList<T> {
+    inner: Mutex<Inner<T>>,
+}
+
+type Link<T> = *mut Node<T>;
+
+struct Node<T> {
+    elem: T,
+    prev: Link<T>,
+    next: Link<T>,
+}
+
+// The list itself, only ever touched with the lock held.
+struct Inner<T> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+    // The list owns its nodes, for variance and drop checking:
+    _boo: PhantomData<Box<Node<T>>>,
+}
+
+// The nodes are only reachable through the Mutex, so the list can move
+// to whichever thread holds the lock.
+unsafe impl<T: Send> Send for Inner<T> {}
+
+impl<T> Inner<T> {
+    fn push_front(&mut self, elem: T) {
+        let node = Box::into_raw(Box::new(Node {
+            elem,
+            prev: ptr::null_mut(),
+            next: self.head,
+        }));
+        if self.head.is_null() {
+            self.tail = node;
+        } else {
+            unsafe { (*self.head).prev = node };
+        }
+        self.head = node;
+        self.len += 1;
+    }
+
+    fn push_back(&mut self, elem: T) {
+        let node = Box::into_raw(Box::new(Node {
+            elem,
+            prev: self.tail,
+            next: ptr::null_mut(),
+        }));
+        if self.tail.is_null() {
+            self.head = node;
+        } else {
+            unsafe { (*self.tail).next = node };
+        }
+        self.tail = node;
+        self.len += 1;
+    }
+
+    // Unlinks a node of this list and takes its element.
+    // time: O(1)
+    unsafe fn unlink(&mut self, node: Link<T>) -> T {
+        let node = Box::from_raw(node);
+        if node.prev.is_null() {
+            self.head = node.next;
+        } else {
+            (*node.prev).next = node.next;
+        }
+        if node.next.is_null() {
+            self.tail = node.prev;
+        } else {
+            (*node.next).prev = node.prev;
+        }
+        self.len -= 1;
+        node.elem
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        if self.head.is_null() {
+            None
+        } else {
+            Some(unsafe { self.unlink(self.head) })
+        }
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        if self.tail.is_null() {
+            None
+        } else {
+            Some(unsafe { self.unlink(self.tail) })
+        }
+    }
+
+    fn visit<F>(&self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut node = self.head;
+        while !node.is_null() {
+            // The nodes are alive for as long as the lock is held:
+            let node_ref = unsafe { &*node };
+            if !f(&node_ref.elem) {
+                return;
+            }
+            node = node_ref.next;
+        }
+    }
+
+    fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut node = self.head;
+        while !node.is_null() {
+            unsafe {
+                let next = (*node).next;
+                if !f(&(*node).elem) {
+                    self.unlink(node);
+                }
+                node = next;
+            }
+        }
+    }
+}
+
+impl<T> Drop for Inner<T> {
+    fn drop(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+}
+
+impl<T> MutexLinkedList<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // A panic while the lock was held can't leave the links half
+    // updated, since user code only runs between whole operations:
+    fn lock(&self) -> MutexGuard<'_, Inner<T>> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn inner_mut(&mut self) -> &mut Inner<T> {
+        self.inner
+            .get_mut()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn push_front(&self, elem: T) {
+        self.lock().push_front(elem)
+    }
+
+    pub fn push_back(&self, elem: T) {
+        self.lock().push_back(elem)
+    }
+
+    pub fn pop_front(&self) -> Option<T> {
+        self.lock().pop_front()
+    }
+
+    pub fn pop_back(&self) -> Option<T> {
+        self.lock().pop_back()
+    }
+
+    // A snapshot: other threads can change the list straight after.
+    pub fn len(&self) -> usize {
+        self.lock().len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Clones, since a reference couldn't outlive the lock.
+    pub fn front(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        let inner = self.lock();
+        unsafe { inner.head.as_ref() }.map(|node| node.elem.clone())
+    }
+
+    pub fn back(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        let inner = self.lock();
+        unsafe { inner.tail.as_ref() }.map(|node| node.elem.clone())
+    }
+
+    // Keeps only the elements `f` returns true for, in one pass under
+    // the lock.
+    // time: O(n)
+    pub fn retain<F>(&self, f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.lock().retain(f)
+    }
+
+    pub fn clear(&self) {
+        // Swap the nodes out, so they're dropped after the lock is
+        // released:
+        let old = std::mem::take(&mut *self.lock());
+        drop(old);
+    }
+}
+
+impl<T> Default for Inner<T> {
+    fn default() -> Self {
+        Inner {
+            head: ptr::null_mut(),
+            tail: ptr::null_mut(),
+            len: 0,
+            _boo: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for MutexLinkedList<T> {
+    fn default() -> Self {
+        MutexLinkedList {
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+}
+
+// `&mut self` means no other thread can hold the lock, so these skip
+// locking altogether.
+impl<T> list::List for MutexLinkedList<T> {
+    type Elem = T;
+
+    fn push_front(&mut self, elem: T) {
+        self.inner_mut().push_front(elem)
+    }
+
+    fn push_back(&mut self, elem: T) {
+        self.inner_mut().push_back(elem)
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        self.inner_mut().pop_front()
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        self.inner_mut().pop_back()
+    }
+
+    fn len(&self) -> usize {
+        MutexLinkedList::len(self)
+    }
+
+    fn visit<F>(&self, f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.lock().visit(f)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for MutexLinkedList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let inner = self.lock();
+        let mut list = f.debug_list();
+        inner.visit(|elem| {
+            list.entry(elem);
+            true
+        });
+        list.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::MutexLinkedList;
+    use crate::linked_list::list::List;
+    use std::sync::Arc;
+    use std::thread;
+
+    // Fewer rounds under Miri, which is much slower:
+    const ROUNDS: usize = if cfg!(miri) { 50 } else { 20_000 };
+    const THREADS: usize = 4;
+
+    #[test]
+    fn basics() {
+        let list = MutexLinkedList::new();
+        assert_eq!(list.pop_front(), None);
+        list.push_back(2);
+        list.push_front(1);
+        list.push_back(3);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.front(), Some(1));
+        assert_eq!(list.back(), Some(3));
+        assert_eq!(format!("{:?}", list), "[1, 2, 3]");
+        assert_eq!(list.pop_back(), Some(3));
+        assert_eq!(list.pop_front(), Some(1));
+        assert_eq!(list.pop_front(), Some(2));
+        assert_eq!(list.pop_back(), None);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn retain_and_clear() {
+        let list = MutexLinkedList::from_values(1..=10);
+        list.retain(|&x| x % 3 != 0);
+        assert_eq!(list.to_vec(), vec![1, 2, 4, 5, 7, 8, 10]);
+        assert_eq!(list.len(), 7);
+        list.retain(|&x| x > 7);
+        assert_eq!(list.to_vec(), vec![8, 10]);
+        assert_eq!(list.back(), Some(10));
+        list.clear();
+        assert!(list.is_empty());
+        assert_eq!(list.front(), None);
+    }
+
+    // Producers push to both ends while consumers pop from both, and
+    // every element comes out exactly once.
+    #[test]
+    fn stress() {
+        let list = Arc::new(MutexLinkedList::new());
+        let producers: Vec<_> = (0..THREADS / 2)
+            .map(|t| {
+                let list = list.clone();
+                thread::spawn(move || {
+                    for i in 0..ROUNDS {
+                        if i % 2 == 0 {
+                            list.push_back(t * ROUNDS + i);
+                        } else {
+                            list.push_front(t * ROUNDS + i);
+                        }
+                    }
+                })
+            })
+            .collect();
+        let consumers: Vec<_> = (0..THREADS / 2)
+            .map(|t| {
+                let list = list.clone();
+                thread::spawn(move || {
+                    let mut popped = Vec::new();
+                    while popped.len() < ROUNDS {
+                        let value = if t % 2 == 0 {
+                            list.pop_front()
+                        } else {
+                            list.pop_back()
+                        };
+                        match value {
+                            Some(value) => popped.push(value),
+                            None => thread::yield_now(),
+                        }
+                    }
+                    popped
+                })
+            })
+            .collect();
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        let mut popped: Vec<usize> = consumers
+            .into_iter()
+            .flat_map(|c| c.join().unwrap())
+            .collect();
+        popped.sort_unstable();
+        assert_eq!(popped, (0..THREADS / 2 * ROUNDS).collect::<Vec<_>>());
+        assert!(list.is_empty());
+    }
+}
@@ -1,8 +1,11 @@
-use std::cell::RefCell;
+use std::cell::{RefCell, RefMut};
+use std::cmp::Ordering;
 use std::fmt;
 use std::fmt::Display;
 use std::rc::Rc;
 
+use super::{list, relink};
+
 type NodeRef<T> = Rc<RefCell<Node<T>>>;
 
 #[derive(Debug)]
@@ -44,10 +47,11 @@ where
             next: self.head.take(),
             prev: None,
         })));
-        self.head = new_node.clone();
-        if let None = self.tail {
-            self.tail = new_node;
+        match new_node.as_ref().unwrap().borrow().next.as_ref() {
+            Some(old_head) => old_head.borrow_mut().prev = new_node.clone(),
+            None => self.tail = new_node.clone(),
         }
+        self.head = new_node;
     }
 
     pub fn append(&mut self, new_value: T) {
@@ -76,6 +80,7 @@ where
     }
 
     /// Warning: this will not check that the provided node belongs to the current list.
+    /// Use `cursor_at` for a checked version.
     pub fn unlink_node(&mut self, node_to_remove: Option<NodeRef<T>>) {
         let node_to_remove = node_to_remove.unwrap();
 
@@ -89,14 +94,358 @@ where
             // if we remove the tail, assign new tail:
             None => self.tail = node_to_remove.borrow().prev.clone(),
         };
+
+        // So that the node can't still look like it's in the list:
+        let mut node = node_to_remove.borrow_mut();
+        node.prev = None;
+        node.next = None;
     }
+}
 
+// Iterating and the cursor methods don't need any of the bounds above:
+impl<T> LinkedList<T> {
     pub fn iter(&self) -> Iter<T> {
         Iter {
             next: self.head.clone(),
             last: self.tail.clone(),
         }
     }
+
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        let current = self.head.clone();
+        CursorMut {
+            list: self,
+            current,
+        }
+    }
+
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        let current = self.tail.clone();
+        CursorMut {
+            list: self,
+            current,
+        }
+    }
+
+    // A cursor at the given node, or None if the node isn't in this
+    // list. The check walks back to the head, so it's O(k) for the
+    // node at index k. Every cursor operation after that is O(1).
+    pub fn cursor_at(&mut self, node: &NodeRef<T>) -> Option<CursorMut<'_, T>> {
+        // The links on both sides have to point back at the node, so
+        // that a node that was unlinked doesn't pass:
+        let next = node.borrow().next.clone();
+        let after = match &next {
+            Some(next) => next.borrow().prev.clone(),
+            None => self.tail.clone(),
+        };
+        if !after.is_some_and(|after| Rc::ptr_eq(&after, node)) {
+            return None;
+        }
+        let mut first = node.clone();
+        loop {
+            let prev = first.borrow().prev.clone();
+            match prev {
+                Some(prev) => {
+                    let prev_next = prev.borrow().next.clone();
+                    if !prev_next.is_some_and(|next| Rc::ptr_eq(&next, &first)) {
+                        return None;
+                    }
+                    first = prev;
+                }
+                None => break,
+            }
+        }
+        match &self.head {
+            Some(head) if Rc::ptr_eq(head, &first) => Some(CursorMut {
+                current: Some(node.clone()),
+                list: self,
+            }),
+            _ => None,
+        }
+    }
+}
+
+// A cursor over a list that can edit it. It's either at a node, or at
+// the "ghost" position between the tail and the head, where moving
+// next wraps around to the head and moving back to the tail.
+//
+// The cursor holds the list's only mutable borrow, and only ever
+// reaches nodes through the list, so every node it touches belongs to
+// the list. Lists spliced in are taken by value, so their nodes can't
+// stay in two lists at once.
+pub struct CursorMut<'a, T> {
+    list: &'a mut LinkedList<T>,
+    current: Option<NodeRef<T>>,
+}
+
+impl<'a, T> CursorMut<'a, T> {
+    // None at the ghost position.
+    pub fn current(&mut self) -> Option<RefMut<'_, T>> {
+        self.current
+            .as_ref()
+            .map(|node| RefMut::map(node.borrow_mut(), |node| &mut node.data))
+    }
+
+    pub fn current_node(&self) -> Option<NodeRef<T>> {
+        self.current.clone()
+    }
+
+    pub fn move_next(&mut self) {
+        self.current = match self.current.take() {
+            Some(node) => node.borrow().next.clone(),
+            None => self.list.head.clone(),
+        };
+    }
+
+    pub fn move_prev(&mut self) {
+        self.current = match self.current.take() {
+            Some(node) => node.borrow().prev.clone(),
+            None => self.list.tail.clone(),
+        };
+    }
+
+    // At the ghost position, inserts at the back of the list.
+    pub fn insert_before(&mut self, data: T) {
+        let (prev, next) = match &self.current {
+            Some(node) => (node.borrow().prev.clone(), Some(node.clone())),
+            None => (self.list.tail.clone(), None),
+        };
+        self.link_between(prev, next, data);
+    }
+
+    // At the ghost position, inserts at the front of the list.
+    pub fn insert_after(&mut self, data: T) {
+        let (prev, next) = match &self.current {
+            Some(node) => (Some(node.clone()), node.borrow().next.clone()),
+            None => (None, self.list.head.clone()),
+        };
+        self.link_between(prev, next, data);
+    }
+
+    fn link_between(&mut self, prev: Option<NodeRef<T>>, next: Option<NodeRef<T>>, data: T) {
+        let node = Rc::new(RefCell::new(Node {
+            data,
+            next: next.clone(),
+            prev: prev.clone(),
+        }));
+        match prev {
+            Some(prev) => prev.borrow_mut().next = Some(node.clone()),
+            None => self.list.head = Some(node.clone()),
+        }
+        match next {
+            Some(next) => next.borrow_mut().prev = Some(node),
+            None => self.list.tail = Some(node),
+        }
+    }
+
+    // Unlinks the current node and moves to the next one. The removed
+    // node is returned with its links cleared, so it doesn't keep the
+    // rest of the list alive.
+    pub fn remove_current(&mut self) -> Option<NodeRef<T>> {
+        let node = self.current.take()?;
+        let prev = node.borrow_mut().prev.take();
+        let next = node.borrow_mut().next.take();
+        match &prev {
+            Some(prev) => prev.borrow_mut().next = next.clone(),
+            None => self.list.head = next.clone(),
+        }
+        match &next {
+            Some(next) => next.borrow_mut().prev = prev,
+            None => self.list.tail = prev,
+        }
+        self.current = next;
+        Some(node)
+    }
+
+    // Splits off everything after the current node into a new list. At
+    // the ghost position, that's the whole list.
+    pub fn split_after(&mut self) -> LinkedList<T> {
+        let node = match &self.current {
+            Some(node) => node.clone(),
+            None => {
+                return LinkedList {
+                    head: self.list.head.take(),
+                    tail: self.list.tail.take(),
+                }
+            }
+        };
+        let head = node.borrow_mut().next.take();
+        match head {
+            Some(head) => {
+                head.borrow_mut().prev = None;
+                let tail = self.list.tail.replace(node);
+                LinkedList {
+                    head: Some(head),
+                    tail,
+                }
+            }
+            None => LinkedList {
+                head: None,
+                tail: None,
+            },
+        }
+    }
+
+    // Moves every node of `other` in before the current node. At the
+    // ghost position, they go at the back of the list.
+    pub fn splice_before(&mut self, mut other: LinkedList<T>) {
+        let (first, last) = match (other.head.take(), other.tail.take()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return,
+        };
+        let (prev, next) = match &self.current {
+            Some(node) => (node.borrow().prev.clone(), Some(node.clone())),
+            None => (self.list.tail.clone(), None),
+        };
+        first.borrow_mut().prev = prev.clone();
+        last.borrow_mut().next = next.clone();
+        match prev {
+            Some(prev) => prev.borrow_mut().next = Some(first),
+            None => self.list.head = Some(first),
+        }
+        match next {
+            Some(next) => next.borrow_mut().prev = Some(last),
+            None => self.list.tail = Some(last),
+        }
+    }
+}
+
+// Rearranging the list by relinking its nodes, so nothing is
+// allocated or cloned, and `NodeRef`s into the list stay valid:
+impl<T> LinkedList<T> {
+    // Moves the elements `pred` is true for in front of the rest, and
+    // returns how many there are. Both parts keep their order.
+    // time: O(n), space: O(1)
+    pub fn partition_by<F>(&mut self, mut pred: F) -> usize
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let (chain, sizes) =
+            relink::stable_partition(self.take_chain(), 2, |data| if pred(data) { 0 } else { 1 });
+        self.set_chain(chain);
+        sizes[0]
+    }
+
+    // Orders the elements less than the pivot, then the ones equal to
+    // it, then the ones greater, each part in its original order.
+    // Returns how many are less, and how many are equal.
+    // time: O(n), space: O(1)
+    pub fn partition_three_way(&mut self, pivot: &T) -> (usize, usize)
+    where
+        T: Ord,
+    {
+        let (chain, sizes) =
+            relink::stable_partition(self.take_chain(), 3, |data| match data.cmp(pivot) {
+                Ordering::Less => 0,
+                Ordering::Equal => 1,
+                Ordering::Greater => 2,
+            });
+        self.set_chain(chain);
+        (sizes[0], sizes[1])
+    }
+
+    // Merges a sorted list into this sorted list. Equal elements from
+    // this list come first.
+    // time: O(n + m), space: O(1)
+    pub fn merge(&mut self, mut other: LinkedList<T>)
+    where
+        T: Ord,
+    {
+        let merged = relink::merge(self.take_chain(), other.take_chain(), &mut T::cmp);
+        self.set_chain(merged);
+    }
+
+    // Merges any number of sorted lists, with a heap of their fronts.
+    // time: O(n log k), space: O(k)
+    pub fn merge_all(lists: Vec<LinkedList<T>>) -> LinkedList<T>
+    where
+        T: Ord,
+    {
+        let chains = lists.into_iter().map(|mut list| list.take_chain()).collect();
+        let mut merged = LinkedList::default();
+        merged.set_chain(relink::merge_k(chains));
+        merged
+    }
+
+    // A stable merge sort.
+    // time: O(n log n), space: O(log n)
+    pub fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        self.sort_by(T::cmp)
+    }
+
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let sorted = relink::sort(self.take_chain(), &mut compare);
+        self.set_chain(sorted);
+    }
+
+    // The nodes as a chain of `next` links, leaving the list empty.
+    fn take_chain(&mut self) -> relink::Chain<Node<T>> {
+        self.tail = None;
+        self.head.take()
+    }
+
+    // Takes the nodes of a chain, and links them back up.
+    fn set_chain(&mut self, chain: relink::Chain<Node<T>>) {
+        self.head = chain;
+        let mut prev: Option<NodeRef<T>> = None;
+        let mut node = self.head.clone();
+        while let Some(current) = node {
+            current.borrow_mut().prev = prev;
+            node = current.borrow().next.clone();
+            prev = Some(current);
+        }
+        self.tail = prev;
+    }
+}
+
+impl<T> Default for LinkedList<T> {
+    fn default() -> Self {
+        Self {
+            head: None,
+            tail: None,
+        }
+    }
+}
+
+impl<T: Clone> list::List for LinkedList<T> {
+    type Elem = T;
+
+    fn push_front(&mut self, elem: T) {
+        self.cursor_front_mut().insert_before(elem)
+    }
+
+    fn push_back(&mut self, elem: T) {
+        self.cursor_back_mut().insert_after(elem)
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        self.cursor_front_mut().remove_current().map(relink::into_data)
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        self.cursor_back_mut().remove_current().map(relink::into_data)
+    }
+
+    fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    fn visit<F>(&self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        for node in self.iter() {
+            if !f(&node.borrow().data) {
+                break;
+            }
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -272,4 +621,265 @@ mod tests {
         assert_eq!(None, iter.next_back());
         assert_eq!(None, iter.next());
     }
+
+    fn data(list: &LinkedList<i32>) -> Vec<i32> {
+        list.iter().map(|node| node.borrow().data).collect()
+    }
+
+    // Checks the prev links too, which `data` doesn't:
+    fn data_back(list: &LinkedList<i32>) -> Vec<i32> {
+        let mut back: Vec<i32> = list.iter().rev().map(|node| node.borrow().data).collect();
+        back.reverse();
+        back
+    }
+
+    fn from(values: &[i32]) -> LinkedList<i32> {
+        let mut list = LinkedList::new();
+        for value in values.iter() {
+            list.append(*value);
+        }
+        list
+    }
+
+    #[test]
+    fn prepend_links_back() {
+        let mut list = LinkedList::new();
+        list.prepend(2);
+        list.prepend(1);
+        assert_eq!(data_back(&list), vec![1, 2]);
+    }
+
+    #[test]
+    fn cursor_moves_and_wraps() {
+        let mut list = from(&[1, 2, 3]);
+        let mut cursor = list.cursor_front_mut();
+        assert_eq!(cursor.current().map(|d| *d), Some(1));
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.current().map(|d| *d), Some(3));
+        // Past the tail is the ghost position, and then the head again:
+        cursor.move_next();
+        assert!(cursor.current().is_none());
+        cursor.move_next();
+        assert_eq!(cursor.current().map(|d| *d), Some(1));
+        cursor.move_prev();
+        cursor.move_prev();
+        assert_eq!(cursor.current().map(|d| *d), Some(3));
+        *cursor.current().unwrap() = 30;
+        assert_eq!(data(&list), vec![1, 2, 30]);
+
+        let mut empty = LinkedList::<i32>::new();
+        let mut cursor = empty.cursor_back_mut();
+        cursor.move_next();
+        assert!(cursor.current().is_none());
+    }
+
+    #[test]
+    fn cursor_inserts() {
+        let mut list = from(&[2, 4]);
+        let mut cursor = list.cursor_front_mut();
+        cursor.insert_before(1);
+        cursor.insert_after(3);
+        cursor.move_next();
+        cursor.move_next();
+        cursor.insert_after(5);
+        // At the ghost position, before is the back, and after is the
+        // front:
+        cursor.move_next();
+        cursor.move_next();
+        assert!(cursor.current().is_none());
+        cursor.insert_before(6);
+        cursor.insert_after(0);
+        assert_eq!(data(&list), vec![0, 1, 2, 3, 4, 5, 6]);
+        assert_eq!(data_back(&list), vec![0, 1, 2, 3, 4, 5, 6]);
+
+        let mut empty = LinkedList::new();
+        empty.cursor_front_mut().insert_before(1);
+        assert_eq!(data_back(&empty), vec![1]);
+    }
+
+    #[test]
+    fn cursor_removes() {
+        let mut list = from(&[1, 2, 3]);
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        let removed = cursor.remove_current().unwrap();
+        assert_eq!(removed.borrow().data, 2);
+        assert!(removed.borrow().next.is_none() && removed.borrow().prev.is_none());
+        assert_eq!(cursor.current().map(|d| *d), Some(3));
+        // Removing the tail moves to the ghost position:
+        cursor.remove_current();
+        assert!(cursor.current().is_none());
+        assert!(cursor.remove_current().is_none());
+        cursor.move_next();
+        cursor.remove_current();
+        assert_eq!(data(&list), Vec::<i32>::new());
+        assert!(list.head.is_none() && list.tail.is_none());
+    }
+
+    #[test]
+    fn cursor_split_after() {
+        let mut list = from(&[1, 2, 3, 4]);
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        let rest = cursor.split_after();
+        assert_eq!(data_back(&list), vec![1, 2]);
+        assert_eq!(data_back(&rest), vec![3, 4]);
+
+        let mut cursor = list.cursor_back_mut();
+        assert_eq!(data(&cursor.split_after()), Vec::<i32>::new());
+        cursor.move_next();
+        let all = cursor.split_after();
+        assert_eq!(data(&all), vec![1, 2]);
+        assert!(list.head.is_none() && list.tail.is_none());
+    }
+
+    #[test]
+    fn cursor_splice_before() {
+        let mut list = from(&[1, 4]);
+        let mut cursor = list.cursor_back_mut();
+        cursor.splice_before(from(&[2, 3]));
+        assert_eq!(cursor.current().map(|d| *d), Some(4));
+        cursor.splice_before(LinkedList::new());
+        cursor.move_next();
+        cursor.splice_before(from(&[5, 6]));
+        cursor.move_next();
+        cursor.splice_before(from(&[0]));
+        assert_eq!(data(&list), vec![0, 1, 2, 3, 4, 5, 6]);
+        assert_eq!(data_back(&list), vec![0, 1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn cursor_at_checks_ownership() {
+        let mut list = from(&[1, 2, 3]);
+        let mut other = from(&[1, 2, 3]);
+        let node = list.iter().nth(1).unwrap();
+        assert!(other.cursor_at(&node).is_none());
+
+        let mut cursor = list.cursor_at(&node).unwrap();
+        cursor.remove_current();
+        assert_eq!(data(&list), vec![1, 3]);
+        // Once it's removed, it isn't in the list anymore:
+        assert!(list.cursor_at(&node).is_none());
+
+        // Split off nodes belong to the new list:
+        let tail = list.tail.clone().unwrap();
+        let mut rest = list.cursor_front_mut().split_after();
+        assert!(list.cursor_at(&tail).is_none());
+        assert!(rest.cursor_at(&tail).is_some());
+    }
+
+    #[test]
+    fn cursor_at_rejects_unlinked_nodes() {
+        let mut list = from(&[1, 2, 3, 4]);
+        let node = list.iter().nth(1).unwrap();
+        list.unlink_node(Some(node.clone()));
+        assert!(node.borrow().prev.is_none() && node.borrow().next.is_none());
+        assert!(list.cursor_at(&node).is_none());
+        assert_eq!(data(&list), vec![1, 3, 4]);
+        assert_eq!(data_back(&list), vec![1, 3, 4]);
+
+        // Nor one whose neighbours no longer point back at it:
+        let head = list.head.clone().unwrap();
+        list::List::pop_front(&mut list);
+        assert!(list.cursor_at(&head).is_none());
+        let tail = list.tail.clone().unwrap();
+        list::List::pop_back(&mut list);
+        assert!(list.cursor_at(&tail).is_none());
+        assert_eq!(data(&list), vec![3]);
+    }
+
+    fn list_of<T>(values: &[T]) -> LinkedList<T>
+    where
+        T: Eq + std::hash::Hash + Clone + PartialOrd + fmt::Debug,
+    {
+        let mut list = LinkedList::new();
+        for value in values {
+            list.append(value.clone());
+        }
+        list
+    }
+
+    // The data front to back, checking the prev links and the tail
+    // give the same thing back to front:
+    fn values<T: Clone + PartialEq + fmt::Debug>(list: &LinkedList<T>) -> Vec<T> {
+        let forward: Vec<T> = list.iter().map(|node| node.borrow().data.clone()).collect();
+        let mut backward: Vec<T> = list
+            .iter()
+            .rev()
+            .map(|node| node.borrow().data.clone())
+            .collect();
+        backward.reverse();
+        assert_eq!(forward, backward);
+        forward
+    }
+
+    #[test]
+    fn partition_by() {
+        let mut list = list_of(&[3, 5, 8, 5, 10, 2, 1]);
+        let node = list.iter().nth(2).unwrap();
+        assert_eq!(list.partition_by(|x| *x < 5), 3);
+        assert_eq!(values(&list), vec![3, 2, 1, 5, 8, 5, 10]);
+        // The nodes were moved, not copied:
+        assert!(Rc::ptr_eq(&list.iter().nth(4).unwrap(), &node));
+
+        assert_eq!(list.partition_by(|_| false), 0);
+        assert_eq!(values(&list), vec![3, 2, 1, 5, 8, 5, 10]);
+        let mut empty = LinkedList::<i32>::new();
+        assert_eq!(empty.partition_by(|_| true), 0);
+        assert_eq!(values(&empty), vec![]);
+    }
+
+    #[test]
+    fn partition_three_way() {
+        let mut list = list_of(&[5, 1, 9, 5, 3, 7, 5, 2]);
+        assert_eq!(list.partition_three_way(&5), (3, 3));
+        assert_eq!(values(&list), vec![1, 3, 2, 5, 5, 5, 9, 7]);
+        assert_eq!(list.partition_three_way(&0), (0, 0));
+        assert_eq!(list.partition_three_way(&10), (8, 0));
+    }
+
+    #[test]
+    fn merge() {
+        let mut list = list_of(&[(1, 'a'), (3, 'a'), (5, 'a')]);
+        list.merge(list_of(&[(0, 'b'), (3, 'b'), (6, 'b')]));
+        assert_eq!(
+            values(&list).iter().map(|(_, c)| *c).collect::<String>(),
+            "baabab"
+        );
+        let mut empty = LinkedList::new();
+        empty.merge(list_of(&[1, 2]));
+        assert_eq!(values(&empty), vec![1, 2]);
+        empty.merge(LinkedList::new());
+        assert_eq!(values(&empty), vec![1, 2]);
+    }
+
+    #[test]
+    fn merge_all() {
+        let lists = vec![
+            list_of(&[1, 4, 7]),
+            LinkedList::new(),
+            list_of(&[2, 5, 8, 9]),
+            list_of(&[0, 3, 6]),
+        ];
+        let merged = LinkedList::merge_all(lists);
+        assert_eq!(values(&merged), (0..10).collect::<Vec<_>>());
+        assert_eq!(values(&LinkedList::<i32>::merge_all(vec![])), vec![]);
+    }
+
+    #[test]
+    fn sort() {
+        let mut numbers: Vec<i64> = (0..200).map(|i| (i * 7919) % 211 - 100).collect();
+        let mut list = list_of(&numbers);
+        list.sort();
+        numbers.sort();
+        assert_eq!(values(&list), numbers);
+
+        // Stable:
+        let mut list = list_of(&[(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd')]);
+        list.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(values(&list), vec![(1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]);
+        list.sort_by(|a, b| b.cmp(a));
+        assert_eq!(values(&list), vec![(2, 'c'), (2, 'a'), (1, 'd'), (1, 'b')]);
+    }
 }
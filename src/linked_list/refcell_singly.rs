@@ -1,20 +1,29 @@
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::convert::From;
+use std::cmp::Ordering;
 use std::fmt;
 use std::fmt::Display;
 use std::hash::{Hash, Hasher};
 use std::ptr;
-use std::rc::Rc;
+use std::mem;
+use std::rc::{Rc, Weak};
+
+use super::{list, relink};
 
 pub type NodeRef<T> = Rc<RefCell<Node<T>>>;
 
 // Used specifically for hashing needs, like HashSet:
 pub struct HashedNode<T>(NodeRef<T>);
 
-#[derive(Debug)]
 pub struct LinkedList<T> {
     pub head: Option<NodeRef<T>>,
+    // A token for each element a handle was given out for, by its
+    // node's key. The handle keeps a Weak to it, and it's dropped once
+    // the element leaves its node, so that even a node someone else
+    // still holds can't pass for being in the list.
+    tokens: HashMap<usize, Rc<()>>,
 }
 
 pub struct Node<T> {
@@ -22,63 +31,150 @@ pub struct Node<T> {
     pub next: Option<NodeRef<T>>,
 }
 
+// The nodes after this one are written as a list, like the list's own
+// Debug, so that a cycle doesn't recurse forever:
+// "Node { data: 1, next: [2, 3, ↺2] }".
 impl<T: fmt::Debug> fmt::Debug for Node<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Node {{ data: {:?}, next: {:?} }}", self.data, self.next)
+        write!(f, "Node {{ data: {:?}, next: ", self.data)?;
+        fmt_chain(f, Iter::new(self.next.clone()), |f, data| {
+            write!(f, "{:?}", data)
+        })?;
+        write!(f, " }}")
     }
 }
 
-impl<T> LinkedList<T>
-where
-    T: std::cmp::Eq
-        + std::hash::Hash
-        + std::clone::Clone
-        + std::cmp::PartialOrd
-        + std::fmt::Debug,
-{
+// Removing needs T: Clone, for when someone else still holds the
+// node, and that's all these need:
+impl<T: Clone> LinkedList<T> {
     pub fn new() -> Self {
-        Self { head: None }
+        Self::default()
     }
 
-    pub fn prepend(&mut self, new_value: T) {
-        let new_node = Some(Rc::new(RefCell::new(Node {
+    pub fn prepend(&mut self, new_value: T) -> NodeHandle<T> {
+        let new_node = Rc::new(RefCell::new(Node {
             data: new_value,
             next: self.head.take(),
-        })));
-        self.head = new_node.clone();
+        }));
+        self.head = Some(new_node.clone());
+        self.handle(&new_node)
     }
 
-    pub fn append(&mut self, new_value: T) {
-        if let Some(tail) = self.tail() {
-            tail.borrow_mut().next = Some(Rc::new(RefCell::new(Node {
-                data: new_value,
-                next: None,
-            })));
+    // A list with a cycle has no tail to append to.
+    pub fn append(&mut self, new_value: T) -> Result<NodeHandle<T>, CycleDetected<T>> {
+        let tail = self.tail()?;
+        let new_node = Rc::new(RefCell::new(Node {
+            data: new_value,
+            next: None,
+        }));
+        if let Some(tail) = tail {
+            tail.borrow_mut().next = Some(new_node.clone());
         } else {
-            self.head = Some(Rc::new(RefCell::new(Node {
-                data: new_value,
-                next: None,
-            })));
+            self.head = Some(new_node.clone());
         }
+        Ok(self.handle(&new_node))
     }
 
-    pub fn append_node(&mut self, node: NodeRef<T>) {
-        if let Some(tail) = self.tail() {
-            tail.borrow_mut().next = Some(node.clone());
-        } else {
-            self.head = Some(node.clone());
+    fn handle(&mut self, node: &NodeRef<T>) -> NodeHandle<T> {
+        let token = Rc::new(());
+        let handle = NodeHandle {
+            node: Rc::downgrade(node),
+            token: Rc::downgrade(&token),
+        };
+        self.tokens.insert(node_key(node), token);
+        handle
+    }
+
+    // The handle's node, if its element is still in it and in this list.
+    pub fn node(&self, handle: &NodeHandle<T>) -> Option<NodeRef<T>> {
+        let node = handle.node.upgrade()?;
+        let token = self.tokens.get(&node_key(&node))?;
+        if !Weak::ptr_eq(&handle.token, &Rc::downgrade(token)) {
+            return None;
         }
+        Some(node)
     }
 
-    // TODO: this isn't loop safe!
-    // If we try and append to a LL that has a cycle, we'll be in an infinite loop.
-    pub fn tail(&self) -> Option<NodeRef<T>> {
-        for node in self.iter() {
-            if let None = node.clone().borrow().next {
-                return Some(node);
+    // Removes the handle's element. Without the node before it, the
+    // only way to do that in O(1) is to move the next element into
+    // this node and unlink the next node instead: so removing anything
+    // but the tail makes the next element's handle stale, even though
+    // the element is still in the list. Removing the tail has to find
+    // the node before it.
+    // time: O(1), or O(n) for the tail
+    pub fn remove(&mut self, handle: NodeHandle<T>) -> Result<T, StaleHandle> {
+        let node = self.node(&handle).ok_or(StaleHandle)?;
+        let next = node.borrow().next.clone();
+        match next {
+            Some(next) if !Rc::ptr_eq(&next, &node) => {
+                let after = next.borrow().next.clone();
+                // If the next node loops back to itself, this one has to:
+                let after = match after {
+                    Some(after) if Rc::ptr_eq(&after, &next) => Some(node.clone()),
+                    after => after,
+                };
+                node.borrow_mut().next = after;
+                self.tokens.remove(&node_key(&node));
+                self.tokens.remove(&node_key(&next));
+                let data = relink::into_data(next);
+                Ok(mem::replace(&mut node.borrow_mut().data, data))
+            }
+            // The tail, or a node that loops back to itself:
+            _ => {
+                if Rc::ptr_eq(self.head.as_ref().ok_or(StaleHandle)?, &node) {
+                    self.head = None;
+                } else {
+                    let before = self
+                        .iter()
+                        .find(|before| is_entry(&before.borrow().next, &node))
+                        .ok_or(StaleHandle)?;
+                    before.borrow_mut().next = None;
+                }
+                node.borrow_mut().next = None;
+                self.tokens.remove(&node_key(&node));
+                Ok(relink::into_data(node))
             }
         }
-        None
+    }
+
+    // Fails if the list has a cycle. Appending a node that's already
+    // in the list is how to make one.
+    pub fn append_node(&mut self, node: NodeRef<T>) -> Result<(), CycleDetected<T>> {
+        if let Some(tail) = self.tail()? {
+            tail.borrow_mut().next = Some(node);
+        } else {
+            self.head = Some(node);
+        }
+        Ok(())
+    }
+
+    // Walks to the node before the tail, so it's O(n). A list with a
+    // cycle has no back to pop.
+    pub fn pop_back(&mut self) -> Result<Option<T>, CycleDetected<T>> {
+        let tail = match self.tail()? {
+            Some(tail) => tail,
+            None => return Ok(None),
+        };
+        if Rc::ptr_eq(self.head.as_ref().unwrap(), &tail) {
+            self.head = None;
+        } else {
+            let before = self
+                .iter()
+                .find(|before| is_entry(&before.borrow().next, &tail))
+                .unwrap();
+            before.borrow_mut().next = None;
+        }
+        self.tokens.remove(&node_key(&tail));
+        Ok(Some(relink::into_data(tail)))
+    }
+
+    // A list with a cycle has no tail.
+    pub fn tail(&self) -> Result<Option<NodeRef<T>>, CycleDetected<T>> {
+        let nodes = self.iter();
+        if let Some(entry) = nodes.entry.clone() {
+            return Err(CycleDetected { entry });
+        }
+        Ok(nodes.last())
     }
 
     /// Warning: this will not check that the provided node belongs to the current list.
@@ -99,10 +195,600 @@ where
             }
         }
     }
+}
+
+// Returned instead of walking around a cycle forever. `entry` is the
+// first node that's in the cycle.
+#[derive(Clone)]
+pub struct CycleDetected<T> {
+    pub entry: NodeRef<T>,
+}
+
+impl<T: fmt::Debug> fmt::Debug for CycleDetected<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Not the entry's Debug, which would follow the cycle:
+        write!(f, "CycleDetected {{ entry: {:?} }}", self.entry.borrow().data)
+    }
+}
+
+impl<T: Display> Display for CycleDetected<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "cycle detected, starting at {}", self.entry.borrow().data)
+    }
+}
+
+impl<T: Display + fmt::Debug> std::error::Error for CycleDetected<T> {}
+
+// The same entry node:
+impl<T> PartialEq for CycleDetected<T> {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.entry, &other.entry)
+    }
+}
+
+// A cycle that's `loop_len` nodes long, starting `tail_len` nodes
+// from the head.
+#[derive(Clone)]
+pub struct LoopInfo<T> {
+    pub entry: NodeRef<T>,
+    pub loop_len: usize,
+    pub tail_len: usize,
+}
+
+impl<T: fmt::Debug> fmt::Debug for LoopInfo<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "LoopInfo {{ entry: {:?}, loop_len: {}, tail_len: {} }}",
+            self.entry.borrow().data,
+            self.loop_len,
+            self.tail_len
+        )
+    }
+}
+
+// A node shared by two lists, at index `offset_a` in the first one and
+// `offset_b` in the second.
+#[derive(Clone)]
+pub struct Intersection<T> {
+    pub node: NodeRef<T>,
+    pub offset_a: usize,
+    pub offset_b: usize,
+}
+
+impl<T: fmt::Debug> fmt::Debug for Intersection<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Intersection {{ node: {:?}, offset_a: {}, offset_b: {} }}",
+            self.node.borrow().data,
+            self.offset_a,
+            self.offset_b
+        )
+    }
+}
+
+// Tells nodes apart by address, for `LinkedList::tokens`. A handle's
+// Weak keeps its node's address from being reused.
+fn node_key<T>(node: &NodeRef<T>) -> usize {
+    Rc::as_ptr(node) as usize
+}
+
+fn next_node<T>(node: &NodeRef<T>) -> Option<NodeRef<T>> {
+    node.borrow().next.clone()
+}
+
+fn advance<T>(mut node: Option<NodeRef<T>>, steps: usize) -> Option<NodeRef<T>> {
+    for _ in 0..steps {
+        node = next_node(&node?);
+    }
+    node
+}
+
+// `LinkedList::loop_info` for the nodes from `head` on.
+fn loop_info<T>(head: Option<NodeRef<T>>) -> Option<LoopInfo<T>> {
+    let head = head?;
+
+    // Find the cycle's length. The tortoise jumps to the hare every
+    // power of two steps, so it's waiting inside the cycle once the
+    // power is larger than the cycle:
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = head.clone();
+    let mut hare = next_node(&head)?;
+    while !Rc::ptr_eq(&tortoise, &hare) {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = next_node(&hare)?;
+        length += 1;
+    }
+
+    // With the hare a cycle's length ahead, they meet at the entry:
+    let mut tortoise = head.clone();
+    let mut hare = advance(Some(head), length).unwrap();
+    let mut tail_len = 0;
+    while !Rc::ptr_eq(&tortoise, &hare) {
+        tortoise = next_node(&tortoise).unwrap();
+        hare = next_node(&hare).unwrap();
+        tail_len += 1;
+    }
+    Some(LoopInfo {
+        entry: tortoise,
+        loop_len: length,
+        tail_len,
+    })
+}
+
+// The first node the two lists share within their first `len_a` and
+// `len_b` nodes, where those are the nodes up to the same end. Skips
+// ahead in the longer one, so that both are the same distance from it.
+fn merge_point<T>(
+    a: Option<NodeRef<T>>,
+    len_a: usize,
+    b: Option<NodeRef<T>>,
+    len_b: usize,
+) -> Option<Intersection<T>> {
+    let (skip_a, skip_b) = (len_a.saturating_sub(len_b), len_b.saturating_sub(len_a));
+    let (mut a, mut b) = (advance(a, skip_a), advance(b, skip_b));
+    for i in 0..len_a.min(len_b) {
+        let (node_a, node_b) = (a?, b?);
+        if Rc::ptr_eq(&node_a, &node_b) {
+            return Some(Intersection {
+                node: node_a,
+                offset_a: skip_a + i,
+                offset_b: skip_b + i,
+            });
+        }
+        a = next_node(&node_a);
+        b = next_node(&node_b);
+    }
+    None
+}
+
+// Names a node that `append` or `prepend` added, to remove it later
+// without a search. Handles aren't Clone, so removing an element uses
+// up the only handle to it. A handle goes stale once its element
+// leaves its node, or its node leaves the list, and using it is then an
+// error rather than a change to some other node.
+pub struct NodeHandle<T> {
+    node: Weak<RefCell<Node<T>>>,
+    token: Weak<()>,
+}
+
+impl<T> fmt::Debug for NodeHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "NodeHandle")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StaleHandle;
+
+impl Display for StaleHandle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the handle's node is no longer in the list")
+    }
+}
+
+impl std::error::Error for StaleHandle {}
+
+// Traversals that are safe on lists with cycles:
+impl<T> LinkedList<T> {
+    // The first node of the cycle, if there is one.
+    // time: O(n), space: O(1)
+    pub fn cycle_entry(&self) -> Option<NodeRef<T>> {
+        self.loop_info().map(|info| info.entry)
+    }
+
+    // Where the cycle is and how long it is, using Brent's algorithm.
+    // time: O(n), space: O(1)
+    pub fn loop_info(&self) -> Option<LoopInfo<T>> {
+        loop_info(self.head.clone())
+    }
+
+    // The first node of `a` that's also in `b`, with its index in both.
+    // Once two lists share a node they share everything after it, so
+    // that's where they merge, or where `a` enters a cycle they share.
+    // time: O(A + B), space: O(1)
+    pub fn intersection_node(a: &Self, b: &Self) -> Option<Intersection<T>> {
+        match (a.loop_info(), b.loop_info()) {
+            (None, None) => {
+                let (len_a, len_b) = (a.iter().count(), b.iter().count());
+                merge_point(a.head.clone(), len_a, b.head.clone(), len_b)
+            }
+            // Each list can only reach its own cycle, so they either
+            // share all of it or none of it:
+            (Some(info_a), Some(info_b)) => {
+                if Rc::ptr_eq(&info_a.entry, &info_b.entry) {
+                    // They merge before the cycle, or at its entry:
+                    return merge_point(
+                        a.head.clone(),
+                        info_a.tail_len + 1,
+                        b.head.clone(),
+                        info_b.tail_len + 1,
+                    );
+                }
+                let mut node = info_b.entry;
+                for steps in 1..info_b.loop_len {
+                    node = next_node(&node).unwrap();
+                    if Rc::ptr_eq(&node, &info_a.entry) {
+                        return Some(Intersection {
+                            node,
+                            offset_a: info_a.tail_len,
+                            offset_b: info_b.tail_len + steps,
+                        });
+                    }
+                }
+                None
+            }
+            _ => None,
+        }
+    }
 
+    // Cuts the list just before `node`, returning the list that starts
+    // at it, or None if it isn't in this list. If `node` is inside a
+    // cycle, the cycle is cut instead, and both lists keep the nodes
+    // from the old cycle's entry up to the cut. Every handle into the
+    // list goes stale, since it can't tell which side its node is on.
+    // time: O(n), space: O(1)
+    pub fn split_at_node(&mut self, node: &NodeRef<T>) -> Option<LinkedList<T>> {
+        let mut rest = LinkedList::default();
+        if Rc::ptr_eq(self.head.as_ref()?, node) {
+            rest.head = self.head.take();
+        } else {
+            let before = self
+                .iter()
+                .find(|before| is_entry(&before.borrow().next, node))?;
+            rest.head = before.borrow_mut().next.take();
+        }
+        self.tokens.clear();
+        Some(rest)
+    }
+
+    // Every node once, stopping before coming back around a cycle.
+    // Finding the cycle first makes this O(n) before the first node.
     pub fn iter(&self) -> Iter<T> {
-        Iter {
-            next: self.head.clone(),
+        Iter::new(self.head.clone())
+    }
+}
+
+// Writes the data as a list, ending with a marker pointing back at the
+// cycle's entry if there is one: "[1, 2, 3, ↺2]".
+fn fmt_chain<T, F>(f: &mut fmt::Formatter, mut nodes: Iter<T>, mut write_data: F) -> fmt::Result
+where
+    F: FnMut(&mut fmt::Formatter, &T) -> fmt::Result,
+{
+    write!(f, "[")?;
+    let mut first = true;
+    for node in &mut nodes {
+        if !first {
+            write!(f, ", ")?;
+        }
+        first = false;
+        write_data(f, &node.borrow().data)?;
+    }
+    if let Some(entry) = nodes.entry {
+        write!(f, ", ↺")?;
+        write_data(f, &entry.borrow().data)?;
+    }
+    write!(f, "]")
+}
+
+// Rearranging the list by relinking its nodes, so nothing is
+// allocated or cloned, and handles into the list stay valid. A list
+// with a cycle can't be rearranged, so these all fail on one, like
+// `append`, and leave it as it was.
+impl<T> LinkedList<T> {
+    // Moves the elements `pred` is true for in front of the rest, and
+    // returns how many there are. Both parts keep their order.
+    // time: O(n), space: O(1)
+    pub fn partition_by<F>(&mut self, mut pred: F) -> Result<usize, CycleDetected<T>>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let (chain, sizes) =
+            relink::stable_partition(self.take_chain()?, 2, |data| if pred(data) { 0 } else { 1 });
+        self.head = chain;
+        Ok(sizes[0])
+    }
+
+    // Orders the elements less than the pivot, then the ones equal to
+    // it, then the ones greater, each part in its original order.
+    // Returns how many are less, and how many are equal.
+    // time: O(n), space: O(1)
+    pub fn partition_three_way(&mut self, pivot: &T) -> Result<(usize, usize), CycleDetected<T>>
+    where
+        T: Ord,
+    {
+        let (chain, sizes) =
+            relink::stable_partition(self.take_chain()?, 3, |data| match data.cmp(pivot) {
+                Ordering::Less => 0,
+                Ordering::Equal => 1,
+                Ordering::Greater => 2,
+            });
+        self.head = chain;
+        Ok((sizes[0], sizes[1]))
+    }
+
+    // Merges a sorted list into this sorted list. Equal elements from
+    // this list come first. Handles into `other` go stale.
+    // time: O(n + m), space: O(1)
+    pub fn merge(&mut self, mut other: LinkedList<T>) -> Result<(), CycleDetected<T>>
+    where
+        T: Ord,
+    {
+        let chain = self.take_chain()?;
+        let other_chain = match other.take_chain() {
+            Ok(other_chain) => other_chain,
+            Err(cycle) => {
+                self.head = chain;
+                return Err(cycle);
+            }
+        };
+        self.head = relink::merge(chain, other_chain, &mut T::cmp);
+        Ok(())
+    }
+
+    // Merges any number of sorted lists, with a heap of their fronts.
+    // time: O(n log k), space: O(k)
+    pub fn merge_all(lists: Vec<LinkedList<T>>) -> Result<LinkedList<T>, CycleDetected<T>>
+    where
+        T: Ord,
+    {
+        let chains = lists
+            .into_iter()
+            .map(|mut list| list.take_chain())
+            .collect::<Result<_, _>>()?;
+        Ok(LinkedList {
+            head: relink::merge_k(chains),
+            ..LinkedList::default()
+        })
+    }
+
+    // A stable merge sort.
+    // time: O(n log n), space: O(log n)
+    pub fn sort(&mut self) -> Result<(), CycleDetected<T>>
+    where
+        T: Ord,
+    {
+        self.sort_by(T::cmp)
+    }
+
+    pub fn sort_by<F>(&mut self, mut compare: F) -> Result<(), CycleDetected<T>>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.head = relink::sort(self.take_chain()?, &mut compare);
+        Ok(())
+    }
+
+    // time: O(n), space: O(1)
+    pub fn reverse(&mut self) -> Result<(), CycleDetected<T>> {
+        self.head = relink::reverse_onto(self.take_chain()?, None);
+        Ok(())
+    }
+
+    // Reverses the elements from index m up to, but not including, n.
+    // time: O(n), space: O(1)
+    pub fn reverse_between(&mut self, m: usize, n: usize) -> Result<(), CycleDetected<T>> {
+        let mut chain = self.take_chain()?;
+        let mut middle = relink::split_off(&mut chain, m);
+        let rest = relink::split_off(&mut middle, n.saturating_sub(m));
+        relink::append(&mut chain, relink::reverse_onto(middle, rest));
+        self.head = chain;
+        Ok(())
+    }
+
+    // Reverses each group of k elements in turn. If there's a shorter
+    // group left at the end, it stays as it is.
+    // time: O(n), space: O(1)
+    pub fn reverse_in_groups(&mut self, k: usize) -> Result<(), CycleDetected<T>> {
+        let mut rest = self.take_chain()?;
+        if k < 2 {
+            self.head = rest;
+            return Ok(());
+        }
+        let mut reversed: relink::Chain<Node<T>> = None;
+        let mut last: Option<NodeRef<T>> = None;
+        for _ in 0..relink::len(&rest) / k {
+            let mut group = rest.take();
+            rest = relink::split_off(&mut group, k);
+            // The group's first node ends up last:
+            let group_last = group.clone();
+            let group = relink::reverse_onto(group, None);
+            match &last {
+                Some(last) => last.borrow_mut().next = group,
+                None => reversed = group,
+            }
+            last = group_last;
+        }
+        relink::append(&mut reversed, rest);
+        self.head = reversed;
+        Ok(())
+    }
+
+    // Compares the first half with the second half reversed in place,
+    // then reverses the second half back.
+    // time: O(n), space: O(1)
+    pub fn is_palindrome(&mut self) -> Result<bool, CycleDetected<T>>
+    where
+        T: PartialEq,
+    {
+        let mut chain = self.take_chain()?;
+        let len = relink::len(&chain);
+        let back = relink::reverse_onto(relink::split_off(&mut chain, len.div_ceil(2)), None);
+        let (mut front_node, mut back_node) = (chain.clone(), back.clone());
+        let mut is_palindrome = true;
+        while let (Some(front), Some(back)) = (front_node, back_node) {
+            if front.borrow().data != back.borrow().data {
+                is_palindrome = false;
+                break;
+            }
+            front_node = front.borrow().next.clone();
+            back_node = back.borrow().next.clone();
+        }
+        relink::append(&mut chain, relink::reverse_onto(back, None));
+        self.head = chain;
+        Ok(is_palindrome)
+    }
+
+    // The nodes as a chain of `next` links, leaving the list empty.
+    fn take_chain(&mut self) -> Result<relink::Chain<Node<T>>, CycleDetected<T>> {
+        if let Some(entry) = self.cycle_entry() {
+            return Err(CycleDetected { entry });
+        }
+        Ok(self.head.take())
+    }
+}
+
+// Walks with two pointers, a fixed distance apart or one twice as fast
+// as the other, so these don't need the length up front. They aren't a
+// single pass though: a list with a cycle has no back to count from, so
+// `iter` first walks the list to check for one (Brent's algorithm), and
+// these find nothing on a cycle. That's two passes, O(n) time in all.
+// The nodes are shared, so these hand out nodes rather than references.
+impl<T> LinkedList<T> {
+    // The nodes from the front, unless there's a cycle.
+    fn acyclic_iter(&self) -> Option<Iter<T>> {
+        let nodes = self.iter();
+        match nodes.entry {
+            Some(_) => None,
+            None => Some(nodes),
+        }
+    }
+
+    // The node k from the back, where 0 is the last one.
+    pub fn nth_from_back(&self, k: usize) -> Option<NodeRef<T>> {
+        let mut trail = self.acyclic_iter()?;
+        let mut lead = trail.clone();
+        // There have to be at least k + 1 nodes:
+        for _ in 0..=k {
+            lead.next()?;
+        }
+        while lead.next().is_some() {
+            trail.next();
+        }
+        trail.next()
+    }
+
+    // The node halfway along, or the second of the two middle ones if
+    // the length is even.
+    pub fn middle(&self) -> Option<NodeRef<T>> {
+        let mut slow = self.acyclic_iter()?;
+        let mut fast = slow.clone();
+        while fast.next().is_some() && fast.next().is_some() {
+            slow.next();
+        }
+        slow.next()
+    }
+
+    // The last k nodes, or all of them if there are fewer.
+    pub fn last_n(&self, k: usize) -> Iter<T> {
+        let mut trail = match self.acyclic_iter() {
+            Some(nodes) => nodes,
+            None => return Iter::new(None),
+        };
+        let mut lead = trail.clone();
+        for _ in 0..k {
+            if lead.next().is_none() {
+                return trail;
+            }
+        }
+        while lead.next().is_some() {
+            trail.next();
+        }
+        trail
+    }
+
+    // Every run of k consecutive nodes, front to back. There are no
+    // windows of 0 nodes.
+    pub fn sliding_windows(&self, k: usize) -> Windows<T> {
+        let start = match self.acyclic_iter() {
+            Some(nodes) if k != 0 => nodes,
+            _ => Iter::new(None),
+        };
+        let mut end = start.clone();
+        // The end of the window runs k - 1 nodes ahead of the start:
+        for _ in 1..k {
+            end.next();
+        }
+        Windows {
+            start,
+            end,
+            size: k,
+        }
+    }
+}
+
+pub struct Windows<T> {
+    start: Iter<T>,
+    end: Iter<T>,
+    size: usize,
+}
+
+impl<T> Iterator for Windows<T> {
+    type Item = std::iter::Take<Iter<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.end.next()?;
+        let window = self.start.clone().take(self.size);
+        self.start.next();
+        Some(window)
+    }
+}
+
+impl<T> Default for LinkedList<T> {
+    fn default() -> Self {
+        Self {
+            head: None,
+            tokens: HashMap::new(),
+        }
+    }
+}
+
+impl<T: Clone> list::List for LinkedList<T> {
+    type Elem = T;
+
+    fn push_front(&mut self, elem: T) {
+        self.prepend(elem);
+    }
+
+    // The trait has no way to report a cycle, so this panics on one,
+    // where `append` returns the error.
+    fn push_back(&mut self, elem: T) {
+        if self.append(elem).is_err() {
+            panic!("can't push to the back of a list with a cycle");
+        }
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        self.head.take().map(|head| {
+            self.head = head.borrow_mut().next.take();
+            self.tokens.remove(&node_key(&head));
+            relink::into_data(head)
+        })
+    }
+
+    // A list with a cycle has no back to pop.
+    fn pop_back(&mut self) -> Option<T> {
+        LinkedList::pop_back(self).unwrap_or(None)
+    }
+
+    fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    fn visit<F>(&self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        for node in self.iter() {
+            if !f(&node.borrow().data) {
+                break;
+            }
         }
     }
 }
@@ -110,49 +796,68 @@ where
 #[derive(Debug)]
 pub struct Iter<T> {
     next: Option<NodeRef<T>>,
+    entry: Option<NodeRef<T>>,
+    passed_entry: bool,
+}
+
+impl<T> Iter<T> {
+    fn new(head: Option<NodeRef<T>>) -> Self {
+        Iter {
+            entry: loop_info(head.clone()).map(|info| info.entry),
+            next: head,
+            passed_entry: false,
+        }
+    }
 }
 
-impl<'a, T> Iterator for Iter<T> {
+// Not derived, since that would need T: Clone:
+impl<T> Clone for Iter<T> {
+    fn clone(&self) -> Self {
+        Iter {
+            next: self.next.clone(),
+            entry: self.entry.clone(),
+            passed_entry: self.passed_entry,
+        }
+    }
+}
+
+impl<T> Iterator for Iter<T> {
     type Item = NodeRef<T>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some(next) = self.next.clone() {
-            // Set the new self.next:
-            if let Some(new_next) = next.borrow().next.clone() {
-                self.next = Some(new_next);
-            } else {
-                self.next = None;
+        let node = self.next.take()?;
+        if is_entry(&self.entry, &node) {
+            if self.passed_entry {
+                return None;
             }
-            return Some(next);
-        } else {
-            None
+            self.passed_entry = true;
         }
+        self.next = node.borrow().next.clone();
+        Some(node)
     }
 }
 
 impl<T: Display> Display for LinkedList<T> {
-    fn fmt(&self, w: &mut std::fmt::Formatter) -> std::result::Result<(), std::fmt::Error> {
-        write!(w, "[")?;
-        let mut node = self.head.clone();
-        while let Some(n) = node {
-            write!(w, "{}", n.borrow().data)?;
-            node = n.borrow().next.clone();
-            if node.is_some() {
-                write!(w, ", ")?;
-            }
-        }
-        write!(w, "]")
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_chain(f, self.iter(), |f, data| write!(f, "{}", data))
     }
 }
 
+impl<T: fmt::Debug> fmt::Debug for LinkedList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "LinkedList ")?;
+        fmt_chain(f, self.iter(), |f, data| write!(f, "{:?}", data))
+    }
+}
+
+// Nodes are equal if they have the same data, and the nodes after them
+// are equal the way lists are.
 impl<T: PartialEq> PartialEq for Node<T> {
     fn eq(&self, other: &Self) -> bool {
         if ptr::eq(self, other) {
-            // For loop detection - if the nodes share the same
-            // reference, they are equal.
             return true;
         }
-        self.data == other.data && self.next == other.next
+        self.data == other.data && chain_eq(&self.next, &other.next)
     }
 }
 
@@ -190,26 +895,50 @@ impl<T: PartialEq> PartialEq for HashedNode<T> {
             // reference, they are equal.
             return true;
         }
-        let other_node = other.0.borrow();
-        let self_node = self.0.borrow();
-        self_node.data == other_node.data && self_node.next == other_node.next
+        *self.0.borrow() == *other.0.borrow()
     }
 }
 
 impl<T: Eq> Eq for HashedNode<T> {}
 
+// Lists are equal if they have the same data in the same shape: either
+// both without a cycle, or both with a cycle starting at the same
+// index.
 impl<T: PartialEq> PartialEq for LinkedList<T> {
     fn eq(&self, other: &Self) -> bool {
-        self.head == other.head
+        chain_eq(&self.head, &other.head)
     }
+}
 
-    fn ne(&self, other: &Self) -> bool {
-        // println!(
-        //     "comparing two lists: self.head: {:?}, other.head: {:?}",
-        //     self.head, other.head
-        // );
-        self.head != other.head
+// Whether the nodes from two heads have the same data in the same
+// shape: the same length, and any cycle coming back to the same index.
+fn chain_eq<T: PartialEq>(head: &Option<NodeRef<T>>, other_head: &Option<NodeRef<T>>) -> bool {
+    let (mut nodes, mut other_nodes) = (Iter::new(head.clone()), Iter::new(other_head.clone()));
+    let mut index = 0;
+    let (mut entry_index, mut other_entry_index) = (None, None);
+    loop {
+        match (nodes.next(), other_nodes.next()) {
+            (None, None) => break,
+            (Some(node), Some(other_node)) => {
+                if node.borrow().data != other_node.borrow().data {
+                    return false;
+                }
+                if is_entry(&nodes.entry, &node) {
+                    entry_index = Some(index);
+                }
+                if is_entry(&other_nodes.entry, &other_node) {
+                    other_entry_index = Some(index);
+                }
+                index += 1;
+            }
+            _ => return false,
+        }
     }
+    entry_index == other_entry_index
+}
+
+fn is_entry<T>(entry: &Option<NodeRef<T>>, node: &NodeRef<T>) -> bool {
+    entry.as_ref().is_some_and(|entry| Rc::ptr_eq(entry, node))
 }
 
 impl<T: Eq + std::fmt::Debug> Eq for LinkedList<T> {}
@@ -221,20 +950,20 @@ mod tests {
     #[test]
     fn eq() {
         let mut list = LinkedList::new();
-        list.append(1);
-        list.append(2);
-        list.append(3);
+        list.append(1).unwrap();
+        list.append(2).unwrap();
+        list.append(3).unwrap();
         let mut list2 = LinkedList::new();
-        list2.append(1);
-        list2.append(2);
-        list2.append(3);
+        list2.append(1).unwrap();
+        list2.append(2).unwrap();
+        list2.append(3).unwrap();
 
         assert_eq!(list, list2);
         list2 = LinkedList::new();
-        list2.append(3);
+        list2.append(3).unwrap();
         assert_ne!(list, list2);
         list = LinkedList::new();
-        list.append(3);
+        list.append(3).unwrap();
         assert_eq!(list, list2);
     }
 
@@ -243,11 +972,11 @@ mod tests {
         let mut list = LinkedList::new();
         list.prepend(2);
         list.prepend(1);
-        list.append(3);
+        list.append(3).unwrap();
         let mut list2 = LinkedList::new();
-        list2.append(1);
-        list2.append(2);
-        list2.append(3);
+        list2.append(1).unwrap();
+        list2.append(2).unwrap();
+        list2.append(3).unwrap();
 
         assert_eq!(list, list2);
         list2.prepend(1);
@@ -263,28 +992,28 @@ mod tests {
             next: None,
         }));
         let mut list1 = LinkedList::new();
-        list1.append_node(shared_node.clone());
+        list1.append_node(shared_node.clone()).unwrap();
 
         let mut list2 = LinkedList::new();
-        list2.append(1);
+        list2.append(1).unwrap();
 
         assert_eq!(list1, list2);
 
         let mut list3 = LinkedList::new();
-        list3.append_node(shared_node);
-        list3.append(2);
+        list3.append_node(shared_node).unwrap();
+        list3.append(2).unwrap();
 
         let mut list4 = LinkedList::new();
-        list4.append(1);
-        list4.append(2);
+        list4.append(1).unwrap();
+        list4.append(2).unwrap();
 
         assert_eq!(list3, list4);
 
         // These are no longer equal! Because a new node has been added to the shared_node:
         assert_ne!(list1, list2);
         let mut list2 = LinkedList::new();
-        list2.append(1);
-        list2.append(2);
+        list2.append(1).unwrap();
+        list2.append(2).unwrap();
 
         assert_eq!(list1, list2);
     }
@@ -296,27 +1025,27 @@ mod tests {
             next: None,
         }));
         let mut list1 = LinkedList::new();
-        list1.append(1);
-        list1.append(2);
-        list1.append(3);
+        list1.append(1).unwrap();
+        list1.append(2).unwrap();
+        list1.append(3).unwrap();
 
         let mut list2 = LinkedList::new();
-        list2.append(1);
-        list2.append(2);
-        list2.append_node(node);
+        list2.append(1).unwrap();
+        list2.append(2).unwrap();
+        list2.append_node(node).unwrap();
 
         assert_eq!(list1, list2);
-        list1.append(4);
+        list1.append(4).unwrap();
         assert_ne!(list1, list2);
-        list2.append(4);
+        list2.append(4).unwrap();
         assert_eq!(list1, list2);
         let node = Rc::new(RefCell::new(Node {
             data: 9,
             next: None,
         }));
-        list1.append_node(node.clone());
-        list2.append_node(node);
-        list2.append(3);
+        list1.append_node(node.clone()).unwrap();
+        list2.append_node(node).unwrap();
+        list2.append(3).unwrap();
         assert_eq!(list1, list2);
     }
 
@@ -327,18 +1056,18 @@ mod tests {
             next: None,
         }));
         let mut list = LinkedList::new();
-        list.append_node(first_node.clone());
-        list.append(2);
-        list.append(3);
-        list.append_node(first_node.clone());
+        list.append_node(first_node.clone()).unwrap();
+        list.append(2).unwrap();
+        list.append(3).unwrap();
+        list.append_node(first_node.clone()).unwrap();
 
         let mut list2 = LinkedList::new();
-        list2.append(1);
+        list2.append(1).unwrap();
 
         assert_ne!(list, list2);
 
         let mut list2 = LinkedList::new();
-        list2.append_node(first_node);
+        list2.append_node(first_node).unwrap();
 
         assert_eq!(list, list2);
     }
@@ -348,10 +1077,10 @@ mod tests {
     fn hashset_iter_nodes() {
         let node = Rc::new(RefCell::new(Node{ data: 9, next: None}));
         let mut list = LinkedList::new();
-        list.append(1);
-        list.append(2);
-        list.append(3);
-        list.append_node(node.clone());
+        list.append(1).unwrap();
+        list.append(2).unwrap();
+        list.append(3).unwrap();
+        list.append_node(node.clone()).unwrap();
 
         let mut set: HashSet<HashedNode<i32>> = HashSet::new();
         // iterate over nodes, adding each node to our hashset:
@@ -362,4 +1091,509 @@ mod tests {
         assert_eq!(set.contains(&HashedNode::from(4)), false);
         assert_eq!(set.len(), 4);
     }
+
+    // A list of `len` nodes, 0 to len - 1, where the last node links
+    // back to the node at `entry`:
+    fn with_cycle(len: i32, entry: Option<i32>) -> LinkedList<i32> {
+        let mut list = LinkedList::new();
+        for i in 0..len {
+            list.append(i).unwrap();
+        }
+        if let Some(entry) = entry {
+            let node = list.iter().nth(entry as usize).unwrap();
+            let tail = list.tail().unwrap().unwrap();
+            tail.borrow_mut().next = Some(node);
+        }
+        list
+    }
+
+    fn break_cycle(list: &LinkedList<i32>) {
+        // So that the nodes are freed:
+        if let Some(entry) = list.cycle_entry() {
+            let last = list.iter().last().unwrap();
+            assert!(Rc::ptr_eq(&last.borrow().next.clone().unwrap(), &entry));
+            last.borrow_mut().next = None;
+        }
+    }
+
+    #[test]
+    fn cycle_entry() {
+        assert!(LinkedList::<i32>::new().cycle_entry().is_none());
+        for len in 1..12 {
+            assert!(with_cycle(len, None).cycle_entry().is_none());
+            for entry in 0..len {
+                let list = with_cycle(len, Some(entry));
+                let found = list.cycle_entry().unwrap();
+                assert_eq!(found.borrow().data, entry);
+                assert_eq!(list.iter().count(), len as usize);
+                break_cycle(&list);
+            }
+        }
+    }
+
+    #[test]
+    fn tail_with_cycle() {
+        let list = with_cycle(4, Some(1));
+        let err = list.tail().unwrap_err();
+        assert_eq!(err.entry.borrow().data, 1);
+        assert_eq!(err.to_string(), "cycle detected, starting at 1");
+        assert_eq!(format!("{:?}", err), "CycleDetected { entry: 1 }");
+        break_cycle(&list);
+        assert_eq!(list.tail().unwrap().unwrap().borrow().data, 3);
+        assert!(LinkedList::<i32>::new().tail().unwrap().is_none());
+    }
+
+    #[test]
+    fn append_with_cycle() {
+        let mut list = with_cycle(3, Some(1));
+        let err = list.append(3).unwrap_err();
+        assert_eq!(err.entry.borrow().data, 1);
+        let node = list.iter().next().unwrap();
+        assert!(list.append_node(node).is_err());
+        assert_eq!(list.pop_back().unwrap_err().entry.borrow().data, 1);
+        assert_eq!(list::List::pop_back(&mut list), None);
+        assert_eq!(list.to_string(), "[0, 1, 2, ↺1]");
+        break_cycle(&list);
+        assert_eq!(list.pop_back(), Ok(Some(2)));
+        assert!(list.append(3).is_ok());
+        assert_eq!(list.to_string(), "[0, 1, 3]");
+    }
+
+    #[test]
+    fn display_with_cycle() {
+        let list = with_cycle(4, Some(1));
+        assert_eq!(list.to_string(), "[0, 1, 2, 3, ↺1]");
+        assert_eq!(format!("{:?}", list), "LinkedList [0, 1, 2, 3, ↺1]");
+        break_cycle(&list);
+        assert_eq!(list.to_string(), "[0, 1, 2, 3]");
+        let self_loop = with_cycle(1, Some(0));
+        assert_eq!(self_loop.to_string(), "[0, ↺0]");
+        break_cycle(&self_loop);
+        assert_eq!(LinkedList::<i32>::new().to_string(), "[]");
+    }
+
+    #[test]
+    fn eq_compares_cycle_shape() {
+        let list = with_cycle(4, Some(1));
+        let same = with_cycle(4, Some(1));
+        let other_entry = with_cycle(4, Some(2));
+        let no_cycle = with_cycle(4, None);
+        assert_eq!(list, same);
+        assert_ne!(list, other_entry);
+        assert_ne!(list, no_cycle);
+        assert_ne!(no_cycle, list);
+        assert_ne!(list, with_cycle(5, Some(1)));
+        for list in [list, same, other_entry].iter() {
+            break_cycle(list);
+        }
+    }
+
+    #[test]
+    fn iter_and_nodes_with_cycle() {
+        let list = with_cycle(4, Some(1));
+        let data: Vec<_> = list.iter().map(|node| node.borrow().data).collect();
+        assert_eq!(data, vec![0, 1, 2, 3]);
+        let node = list.iter().nth(2).unwrap();
+        assert_eq!(
+            format!("{:?}", node.borrow()),
+            "Node { data: 2, next: [3, 1, 2, ↺3] }"
+        );
+        let same = with_cycle(4, Some(1));
+        let same_node = same.iter().nth(2).unwrap();
+        let other_entry = with_cycle(4, Some(2));
+        let other_node = other_entry.iter().nth(2).unwrap();
+        assert!(*node.borrow() == *node.borrow());
+        assert!(*node.borrow() == *same_node.borrow());
+        assert!(*node.borrow() != *other_node.borrow());
+        assert!(HashedNode::from_node(node.clone()) == HashedNode::from_node(same_node));
+        for list in [list, same, other_entry].iter() {
+            break_cycle(list);
+        }
+        assert_eq!(
+            format!("{:?}", node.borrow()),
+            "Node { data: 2, next: [3] }"
+        );
+    }
+
+    #[test]
+    fn loop_info() {
+        assert!(with_cycle(5, None).loop_info().is_none());
+        for len in 1..8 {
+            for entry in 0..len {
+                let list = with_cycle(len, Some(entry));
+                let info = list.loop_info().unwrap();
+                assert_eq!(info.entry.borrow().data, entry);
+                assert_eq!(info.loop_len, (len - entry) as usize);
+                assert_eq!(info.tail_len, entry as usize);
+                break_cycle(&list);
+            }
+        }
+        let list = with_cycle(5, Some(2));
+        assert_eq!(
+            format!("{:?}", list.loop_info().unwrap()),
+            "LoopInfo { entry: 2, loop_len: 3, tail_len: 2 }"
+        );
+        break_cycle(&list);
+    }
+
+    // `a_only` and `b_only` values in front of a shared list `common`:
+    fn merged(a_only: i32, b_only: i32, common: &LinkedList<i32>) -> [LinkedList<i32>; 2] {
+        let mut lists = [LinkedList::new(), LinkedList::new()];
+        for (list, count) in lists.iter_mut().zip(&[a_only, b_only]) {
+            for i in 0..*count {
+                list.append(100 + i).unwrap();
+            }
+            if let Some(head) = &common.head {
+                list.append_node(head.clone()).unwrap();
+            }
+        }
+        lists
+    }
+
+    #[test]
+    fn intersection_node() {
+        let common = with_cycle(3, None);
+        let [a, b] = merged(2, 4, &common);
+        let found = LinkedList::intersection_node(&a, &b).unwrap();
+        assert!(Rc::ptr_eq(&found.node, common.head.as_ref().unwrap()));
+        assert_eq!((found.offset_a, found.offset_b), (2, 4));
+        let found = LinkedList::intersection_node(&b, &a).unwrap();
+        assert_eq!((found.offset_a, found.offset_b), (4, 2));
+        assert_eq!(
+            format!("{:?}", found),
+            "Intersection { node: 0, offset_a: 4, offset_b: 2 }"
+        );
+
+        // A list intersects itself at its head:
+        let found = LinkedList::intersection_node(&a, &a).unwrap();
+        assert_eq!((found.node.borrow().data, found.offset_a), (100, 0));
+
+        let [c, d] = merged(0, 1, &common);
+        let found = LinkedList::intersection_node(&c, &d).unwrap();
+        assert_eq!((found.offset_a, found.offset_b), (0, 1));
+
+        let separate = with_cycle(3, None);
+        assert!(LinkedList::intersection_node(&a, &separate).is_none());
+        assert!(LinkedList::intersection_node(&a, &LinkedList::new()).is_none());
+    }
+
+    #[test]
+    fn intersection_node_with_cycles() {
+        // Merging before the cycle:
+        let common = with_cycle(4, Some(2));
+        let [a, b] = merged(1, 3, &common);
+        let found = LinkedList::intersection_node(&a, &b).unwrap();
+        assert!(Rc::ptr_eq(&found.node, common.head.as_ref().unwrap()));
+        assert_eq!((found.offset_a, found.offset_b), (1, 3));
+
+        // Entering the same cycle at different nodes:
+        let cycle = with_cycle(4, Some(0));
+        let mut c = LinkedList::new();
+        c.append(100).unwrap();
+        c.append_node(cycle.iter().nth(1).unwrap()).unwrap();
+        let mut d = LinkedList::new();
+        d.append_node(cycle.iter().nth(3).unwrap()).unwrap();
+        let found = LinkedList::intersection_node(&c, &d).unwrap();
+        assert_eq!(found.node.borrow().data, 1);
+        assert_eq!((found.offset_a, found.offset_b), (1, 2));
+        let found = LinkedList::intersection_node(&d, &c).unwrap();
+        assert_eq!(found.node.borrow().data, 3);
+        assert_eq!((found.offset_a, found.offset_b), (0, 3));
+
+        // A cycle and a list without one never share a node, and
+        // neither do two separate cycles:
+        let other = with_cycle(3, Some(1));
+        assert!(LinkedList::intersection_node(&a, &with_cycle(3, None)).is_none());
+        assert!(LinkedList::intersection_node(&a, &other).is_none());
+        for list in [common, cycle, other].iter() {
+            break_cycle(list);
+        }
+    }
+
+    #[test]
+    fn split_at_node() {
+        let mut list = with_cycle(5, None);
+        let node = list.iter().nth(2).unwrap();
+        let rest = list.split_at_node(&node).unwrap();
+        assert_eq!(list.to_string(), "[0, 1]");
+        assert_eq!(rest.to_string(), "[2, 3, 4]");
+        assert!(list.split_at_node(&node).is_none());
+
+        let mut list = with_cycle(3, None);
+        let head = list.head.clone().unwrap();
+        let all = list.split_at_node(&head).unwrap();
+        assert_eq!(all.to_string(), "[0, 1, 2]");
+        assert_eq!(list.to_string(), "[]");
+        assert!(list.split_at_node(&head).is_none());
+    }
+
+    #[test]
+    fn split_at_node_in_cycle() {
+        // At the entry, the cycle splits off whole:
+        let mut list = with_cycle(5, Some(2));
+        let entry = list.cycle_entry().unwrap();
+        let cycle = list.split_at_node(&entry).unwrap();
+        assert_eq!(list.to_string(), "[0, 1]");
+        assert_eq!(cycle.to_string(), "[2, 3, 4, ↺2]");
+        break_cycle(&cycle);
+
+        // Inside it, the cycle is cut:
+        let mut list = with_cycle(5, Some(1));
+        let node = list.iter().nth(3).unwrap();
+        let rest = list.split_at_node(&node).unwrap();
+        assert_eq!(list.to_string(), "[0, 1, 2]");
+        assert_eq!(rest.to_string(), "[3, 4, 1, 2]");
+    }
+
+    #[test]
+    fn remove_by_handle() {
+        let mut list = LinkedList::new();
+        let handles: Vec<_> = "abcdef".chars().map(|c| list.append(c).unwrap()).collect();
+        let mut handles = handles.into_iter();
+        let (a, b, c, d) = (
+            handles.next().unwrap(),
+            handles.next().unwrap(),
+            handles.next().unwrap(),
+            handles.next().unwrap(),
+        );
+        let (e, f) = (handles.next().unwrap(), handles.next().unwrap());
+
+        // c's node takes d's element, so d's handle goes stale:
+        assert_eq!(list.remove(c), Ok('c'));
+        assert_eq!(list.to_string(), "[a, b, d, e, f]");
+        assert!(list.node(&d).is_none());
+        assert_eq!(list.remove(d), Err(StaleHandle));
+        assert_eq!(list.to_string(), "[a, b, d, e, f]");
+
+        // The tail:
+        assert_eq!(list.remove(f), Ok('f'));
+        assert_eq!(list.to_string(), "[a, b, d, e]");
+        assert_eq!(list.remove(e), Ok('e'));
+        assert_eq!(list.tail().unwrap().unwrap().borrow().data, 'd');
+
+        // The head, and a handle from prepend:
+        assert_eq!(list.remove(a), Ok('a'));
+        assert_eq!(list.to_string(), "[b, d]");
+        let z = list.prepend('z');
+        assert_eq!(list.node(&z).unwrap().borrow().data, 'z');
+        assert_eq!(list.remove(z), Ok('z'));
+        // b moved into a's node when a was removed:
+        assert_eq!(list.remove(b), Err(StaleHandle));
+        assert_eq!(list.to_string(), "[b, d]");
+    }
+
+    #[test]
+    fn held_nodes_dont_keep_handles_alive() {
+        let mut list = LinkedList::new();
+        list.append('a').unwrap();
+        let c = list.append('c').unwrap();
+        let d = list.append('d').unwrap();
+        list.append('e').unwrap();
+        // Someone else holding d's node keeps it alive after its
+        // element moves into c's node:
+        let held = list.node(&d).unwrap();
+        assert_eq!(list.remove(c), Ok('c'));
+        assert!(list.node(&d).is_none());
+        assert_eq!(list.remove(d), Err(StaleHandle));
+        assert_eq!(list.to_string(), "[a, d, e]");
+        assert_eq!(held.borrow().data, 'd');
+
+        // And after the tail is unlinked:
+        let f = list.append('f').unwrap();
+        let tail = list.iter().last().unwrap();
+        assert_eq!(list::List::pop_back(&mut list), Some('f'));
+        assert_eq!(list.remove(f), Err(StaleHandle));
+        assert_eq!(list.to_string(), "[a, d, e]");
+        assert_eq!(tail.borrow().data, 'f');
+    }
+
+    #[test]
+    fn remove_only_node() {
+        let mut list = LinkedList::new();
+        let only = list.append(1).unwrap();
+        assert_eq!(list.remove(only), Ok(1));
+        assert_eq!(list.to_string(), "[]");
+        let popped = list.append(2).unwrap();
+        list::List::pop_front(&mut list);
+        assert_eq!(list.remove(popped), Err(StaleHandle));
+    }
+
+    #[test]
+    fn stale_handles_leave_the_list_alone() {
+        let mut list = LinkedList::new();
+        let mut other = LinkedList::new();
+        list.append(1).unwrap();
+        let two = list.append(2).unwrap();
+        other.append(1).unwrap();
+        // A handle only works on its own list:
+        assert!(other.node(&two).is_none());
+        assert_eq!(other.remove(two), Err(StaleHandle));
+        assert_eq!(other.to_string(), "[1]");
+
+        // Splitting makes every handle stale:
+        let three = list.append(3).unwrap();
+        let node = list.iter().nth(1).unwrap();
+        let mut rest = list.split_at_node(&node).unwrap();
+        let four = rest.append(4).unwrap();
+        assert_eq!(list.to_string(), "[1]");
+        assert_eq!(rest.remove(three), Err(StaleHandle));
+        assert_eq!(rest.remove(four), Ok(4));
+        assert_eq!(rest.to_string(), "[2, 3]");
+        assert_eq!(StaleHandle.to_string(), "the handle's node is no longer in the list");
+    }
+
+    fn values<T: Clone>(list: &LinkedList<T>) -> Vec<T> {
+        list.iter().map(|node| node.borrow().data.clone()).collect()
+    }
+
+    #[test]
+    fn partitions_keep_handles() {
+        let mut list = LinkedList::new();
+        let handles: Vec<_> = [3, 5, 8, 5, 10, 2, 1]
+            .iter()
+            .map(|v| list.append(*v).unwrap())
+            .collect();
+        assert_eq!(list.partition_by(|x| *x < 5), Ok(3));
+        assert_eq!(values(&list), vec![3, 2, 1, 5, 8, 5, 10]);
+        assert_eq!(list.partition_three_way(&5), Ok((3, 2)));
+        assert_eq!(values(&list), vec![3, 2, 1, 5, 5, 8, 10]);
+        // Every node is still there, so every handle still works:
+        for handle in &handles {
+            assert!(list.node(handle).is_some());
+        }
+        let ten = handles.into_iter().nth(4).unwrap();
+        assert_eq!(list.remove(ten), Ok(10));
+    }
+
+    #[test]
+    fn merge_and_sort() {
+        let mut list = LinkedList::new();
+        for v in [1, 3, 5].iter() {
+            list.append(*v).unwrap();
+        }
+        let mut other = LinkedList::new();
+        for v in [2, 3, 4, 6].iter() {
+            other.append(*v).unwrap();
+        }
+        list.merge(other).unwrap();
+        assert_eq!(values(&list), vec![1, 2, 3, 3, 4, 5, 6]);
+
+        let lists = (0..3)
+            .map(|k| {
+                let mut list = LinkedList::new();
+                for v in (k..12).step_by(3) {
+                    list.append(v).unwrap();
+                }
+                list
+            })
+            .collect();
+        let merged = LinkedList::merge_all(lists).unwrap();
+        assert_eq!(values(&merged), (0..12).collect::<Vec<_>>());
+
+        let mut list = LinkedList::new();
+        for i in 0..100 {
+            list.append((i * 37) % 101).unwrap();
+        }
+        list.sort_by(|a, b| b.cmp(a)).unwrap();
+        let mut expected: Vec<i32> = (0..100).map(|i| (i * 37) % 101).collect();
+        expected.sort_by(|a, b| b.cmp(a));
+        assert_eq!(values(&list), expected);
+    }
+
+    #[test]
+    fn rearrange_with_cycle() {
+        let mut list = with_cycle(3, Some(0));
+        assert!(list.sort().is_err());
+        assert!(list.reverse().is_err());
+        assert!(list.reverse_in_groups(1).is_err());
+        assert!(list.partition_by(|x| *x < 1).is_err());
+        assert!(list.is_palindrome().is_err());
+        assert!(list.merge(list_of(&[1])).is_err());
+        // Left as it was:
+        assert_eq!(list.to_string(), "[0, 1, 2, ↺0]");
+        break_cycle(&list);
+    }
+
+    fn list_of(values: &[i32]) -> LinkedList<i32> {
+        let mut list = LinkedList::new();
+        for value in values {
+            list.append(*value).unwrap();
+        }
+        list
+    }
+
+    #[test]
+    fn reversals() {
+        let mut list = list_of(&[0, 1, 2, 3, 4, 5, 6]);
+        let three = list.iter().nth(3).unwrap();
+        list.reverse().unwrap();
+        assert_eq!(values(&list), vec![6, 5, 4, 3, 2, 1, 0]);
+        // The same nodes, relinked:
+        assert!(Rc::ptr_eq(&list.iter().nth(3).unwrap(), &three));
+
+        list.reverse_between(1, 4).unwrap();
+        assert_eq!(values(&list), vec![6, 3, 4, 5, 2, 1, 0]);
+        list.reverse_between(5, 100).unwrap();
+        assert_eq!(values(&list), vec![6, 3, 4, 5, 2, 0, 1]);
+        list.reverse_between(2, 2).unwrap();
+        assert_eq!(values(&list), vec![6, 3, 4, 5, 2, 0, 1]);
+
+        list.reverse_in_groups(2).unwrap();
+        assert_eq!(values(&list), vec![3, 6, 5, 4, 0, 2, 1]);
+        list.reverse_in_groups(7).unwrap();
+        assert_eq!(values(&list), vec![1, 2, 0, 4, 5, 6, 3]);
+        let mut empty = list_of(&[]);
+        empty.reverse().unwrap();
+        empty.reverse_in_groups(3).unwrap();
+        assert_eq!(empty.to_string(), "[]");
+    }
+
+    #[test]
+    fn is_palindrome_restores_list() {
+        for values_ in [vec![], vec![1], vec![1, 2, 1], vec![1, 2, 2, 1]].iter() {
+            let mut list = list_of(values_);
+            assert!(list.is_palindrome().unwrap());
+            assert_eq!(&values(&list), values_);
+        }
+        for values_ in [vec![1, 2], vec![1, 2, 3], vec![1, 2, 1, 1]].iter() {
+            let mut list = list_of(values_);
+            assert!(!list.is_palindrome().unwrap());
+            assert_eq!(&values(&list), values_);
+        }
+    }
+
+    #[test]
+    fn two_pointer_walks() {
+        let list = list_of(&[1, 2, 3, 4, 5, 6]);
+        let data = |node: Option<NodeRef<i32>>| node.map(|node| node.borrow().data);
+        assert_eq!(data(list.nth_from_back(0)), Some(6));
+        assert_eq!(data(list.nth_from_back(5)), Some(1));
+        assert_eq!(data(list.nth_from_back(6)), None);
+        assert_eq!(data(list.middle()), Some(4));
+        assert_eq!(data(list_of(&[1, 2, 3]).middle()), Some(2));
+        assert_eq!(data(list_of(&[]).middle()), None);
+
+        let last: Vec<i32> = list.last_n(3).map(|node| node.borrow().data).collect();
+        assert_eq!(last, vec![4, 5, 6]);
+        assert_eq!(list.last_n(10).count(), 6);
+
+        let sums: Vec<i32> = list
+            .sliding_windows(4)
+            .map(|window| window.map(|node| node.borrow().data).sum())
+            .collect();
+        assert_eq!(sums, vec![10, 14, 18]);
+        assert_eq!(list.sliding_windows(7).count(), 0);
+        assert_eq!(list.sliding_windows(0).count(), 0);
+    }
+
+    #[test]
+    fn two_pointer_walks_with_cycle() {
+        let list = with_cycle(6, Some(2));
+        assert!(list.nth_from_back(0).is_none());
+        assert!(list.middle().is_none());
+        assert_eq!(list.last_n(3).count(), 0);
+        assert_eq!(list.sliding_windows(2).count(), 0);
+        break_cycle(&list);
+        assert_eq!(list.middle().unwrap().borrow().data, 3);
+    }
 }
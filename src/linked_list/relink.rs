@@ -0,0 +1,308 @@
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+use super::{refcell, refcell_singly};
+use crate::{Heap, HeapOrder};
+
+// Algorithms that rearrange a chain of Rc<RefCell<_>> nodes by
+// relinking their `next` pointers, without allocating or cloning any
+// data. They're shared by the refcell lists: the doubly linked one
+// fixes up its `prev` links and tail afterwards. `into_data` is shared
+// by every list built on Rc nodes, Brandon's included.
+
+pub(super) type Chain<N> = Option<Rc<RefCell<N>>>;
+
+pub(super) trait Link: Sized {
+    type Data;
+
+    fn data(&self) -> &Self::Data;
+    fn next_mut(&mut self) -> &mut Chain<Self>;
+    fn into_data(self) -> Self::Data;
+}
+
+impl<T> Link for refcell::Node<T> {
+    type Data = T;
+
+    fn data(&self) -> &T {
+        &self.data
+    }
+
+    fn next_mut(&mut self) -> &mut Chain<Self> {
+        &mut self.next
+    }
+
+    fn into_data(self) -> T {
+        self.data
+    }
+}
+
+impl<T> Link for refcell_singly::Node<T> {
+    type Data = T;
+
+    fn data(&self) -> &T {
+        &self.data
+    }
+
+    fn next_mut(&mut self) -> &mut Chain<Self> {
+        &mut self.next
+    }
+
+    fn into_data(self) -> T {
+        self.data
+    }
+}
+
+// The data of a node that's been unlinked. The node might still be
+// held by someone else, from `iter`, in which case its data is cloned.
+pub(super) fn into_data<N>(node: Rc<RefCell<N>>) -> N::Data
+where
+    N: Link,
+    N::Data: Clone,
+{
+    Rc::try_unwrap(node)
+        .map(|node| node.into_inner().into_data())
+        .unwrap_or_else(|node| node.borrow().data().clone())
+}
+
+fn pop_front<N: Link>(chain: &mut Chain<N>) -> Chain<N> {
+    let node = chain.take()?;
+    *chain = node.borrow_mut().next_mut().take();
+    Some(node)
+}
+
+fn next<N: Link>(node: &Rc<RefCell<N>>) -> Chain<N> {
+    node.borrow_mut().next_mut().clone()
+}
+
+pub(super) fn len<N: Link>(chain: &Chain<N>) -> usize {
+    let mut len = 0;
+    let mut node = chain.clone();
+    while let Some(current) = node {
+        node = next(&current);
+        len += 1;
+    }
+    len
+}
+
+// Cuts the chain after its first `at` nodes, returning the rest.
+// time: O(at)
+pub(super) fn split_off<N: Link>(chain: &mut Chain<N>, at: usize) -> Chain<N> {
+    if at == 0 {
+        return chain.take();
+    }
+    let mut last = chain.clone();
+    for _ in 1..at {
+        last = last.as_ref().and_then(next);
+    }
+    last.and_then(|last| last.borrow_mut().next_mut().take())
+}
+
+// Links `rest` onto the end of the chain.
+// time: O(n)
+pub(super) fn append<N: Link>(chain: &mut Chain<N>, rest: Chain<N>) {
+    match chain.clone() {
+        None => *chain = rest,
+        Some(mut last) => {
+            while let Some(node) = next(&last) {
+                last = node;
+            }
+            *last.borrow_mut().next_mut() = rest;
+        }
+    }
+}
+
+// The chain reversed, followed by `onto`.
+// time: O(n), space: O(1)
+pub(super) fn reverse_onto<N: Link>(mut chain: Chain<N>, mut onto: Chain<N>) -> Chain<N> {
+    while let Some(node) = pop_front(&mut chain) {
+        *node.borrow_mut().next_mut() = onto;
+        onto = Some(node);
+    }
+    onto
+}
+
+// Builds a chain one node at a time, in O(1) per node.
+struct Builder<N> {
+    head: Chain<N>,
+    tail: Chain<N>,
+    len: usize,
+}
+
+impl<N: Link> Builder<N> {
+    fn new() -> Self {
+        Builder {
+            head: None,
+            tail: None,
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, node: Rc<RefCell<N>>) {
+        match self.tail.take() {
+            Some(tail) => *tail.borrow_mut().next_mut() = Some(node.clone()),
+            None => self.head = Some(node.clone()),
+        }
+        self.tail = Some(node);
+        self.len += 1;
+    }
+
+    // The chain, followed by `rest`.
+    fn finish(self, rest: Chain<N>) -> Chain<N> {
+        match self.tail {
+            Some(tail) => {
+                *tail.borrow_mut().next_mut() = rest;
+                self.head
+            }
+            None => rest,
+        }
+    }
+}
+
+// Moves every node into the bucket `bucket_of` picks for it, and
+// chains the buckets back together in order. Each bucket keeps the
+// nodes in the order they came in. Returns the size of every bucket.
+// time: O(n), space: O(buckets)
+pub(super) fn stable_partition<N, F>(
+    mut chain: Chain<N>,
+    buckets: usize,
+    mut bucket_of: F,
+) -> (Chain<N>, Vec<usize>)
+where
+    N: Link,
+    F: FnMut(&N::Data) -> usize,
+{
+    let mut builders: Vec<Builder<N>> = (0..buckets).map(|_| Builder::new()).collect();
+    while let Some(node) = pop_front(&mut chain) {
+        let bucket = bucket_of(node.borrow().data());
+        builders[bucket].push(node);
+    }
+    let sizes = builders.iter().map(|builder| builder.len).collect();
+    let chain = builders
+        .into_iter()
+        .rev()
+        .fold(None, |rest, builder| builder.finish(rest));
+    (chain, sizes)
+}
+
+// Merges two sorted chains. On a tie, `a`'s node comes first.
+// time: O(A + B), space: O(1)
+pub(super) fn merge<N, F>(mut a: Chain<N>, mut b: Chain<N>, compare: &mut F) -> Chain<N>
+where
+    N: Link,
+    F: FnMut(&N::Data, &N::Data) -> Ordering,
+{
+    let mut merged = Builder::new();
+    while let (Some(a_front), Some(b_front)) = (&a, &b) {
+        let b_first = compare(b_front.borrow().data(), a_front.borrow().data()) == Ordering::Less;
+        let node = if b_first {
+            pop_front(&mut b)
+        } else {
+            pop_front(&mut a)
+        };
+        merged.push(node.unwrap());
+    }
+    merged.finish(a.or(b))
+}
+
+// A stable merge sort.
+// time: O(n log n), space: O(log n) for the recursion
+pub(super) fn sort<N, F>(chain: Chain<N>, compare: &mut F) -> Chain<N>
+where
+    N: Link,
+    F: FnMut(&N::Data, &N::Data) -> Ordering,
+{
+    let len = len(&chain);
+    merge_sort(chain, len, compare)
+}
+
+// Sorts a chain `len` nodes long.
+fn merge_sort<N, F>(chain: Chain<N>, len: usize, compare: &mut F) -> Chain<N>
+where
+    N: Link,
+    F: FnMut(&N::Data, &N::Data) -> Ordering,
+{
+    if len < 2 {
+        return chain;
+    }
+    let half = len / 2;
+    let mut last = chain.clone().unwrap();
+    for _ in 1..half {
+        last = next(&last).unwrap();
+    }
+    let second = last.borrow_mut().next_mut().take();
+    let first = merge_sort(chain, half, compare);
+    let second = merge_sort(second, len - half, compare);
+    merge(first, second, compare)
+}
+
+// The front node of one of the chains in a k-way merge. Ties go to the
+// earlier chain, so the merge is stable.
+struct Front<N> {
+    node: Rc<RefCell<N>>,
+    chain: usize,
+}
+
+impl<N: Link> Front<N>
+where
+    N::Data: Ord,
+{
+    fn key_cmp(&self, other: &Self) -> Ordering {
+        let data = self.node.borrow().data().cmp(other.node.borrow().data());
+        data.then(self.chain.cmp(&other.chain))
+    }
+}
+
+impl<N: Link> PartialEq for Front<N>
+where
+    N::Data: Ord,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.key_cmp(other) == Ordering::Equal
+    }
+}
+
+impl<N: Link> Eq for Front<N> where N::Data: Ord {}
+
+impl<N: Link> PartialOrd for Front<N>
+where
+    N::Data: Ord,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N: Link> Ord for Front<N>
+where
+    N::Data: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key_cmp(other)
+    }
+}
+
+// Merges k sorted chains, keeping the front node of each in a min-heap.
+// time: O(n log k), space: O(k)
+pub(super) fn merge_k<N>(chains: Vec<Chain<N>>) -> Chain<N>
+where
+    N: Link,
+    N::Data: Ord,
+{
+    let mut fronts = Heap::new().with_order(HeapOrder::Min);
+    for (index, chain) in chains.into_iter().enumerate() {
+        // The rest of each chain stays linked from its front:
+        if let Some(node) = chain {
+            fronts.push(Front { node, chain: index });
+        }
+    }
+    let mut merged = Builder::new();
+    while let Some(Front { node, chain }) = fronts.pop() {
+        let next = node.borrow_mut().next_mut().take();
+        if let Some(next) = next {
+            fronts.push(Front { node: next, chain });
+        }
+        merged.push(node);
+    }
+    merged.finish(None)
+}
@@ -1,4 +1,5 @@
 // use std::mem;
+use super::list;
 
 // List is public to avoid having to make Node public
 
@@ -32,6 +33,13 @@ pub struct Iter<'a, T> {
     next: Option<&'a Node<T>>,
 }
 
+// Not derived, since that would need T: Clone:
+impl<'a, T> Clone for Iter<'a, T> {
+    fn clone(&self) -> Self {
+        Iter { next: self.next }
+    }
+}
+
 impl<'a, T> Iterator for Iter<'a, T> {
     type Item = &'a T;
 
@@ -68,10 +76,6 @@ impl<T> List<T> {
         List { head: None }
     }
 
-    pub fn into_iter(self) -> IntoIter<T> {
-        IntoIter(self)
-    }
-
     // lifetimes elided here:
     pub fn iter(&self) -> Iter<T> {
         // pub fn iter(&self) -> Iter<'_, T> {
@@ -126,6 +130,225 @@ impl<T> List<T> {
     }
 }
 
+// Reversals that move the boxed nodes around, rather than the
+// elements. None of them need any more memory than a few links.
+impl<T> List<T> {
+    // time: O(n), space: O(1)
+    pub fn reverse(&mut self) {
+        self.head = reverse_onto(self.head.take(), None);
+    }
+
+    // Reverses the elements from index m up to, but not including, n.
+    // time: O(n), space: O(1)
+    pub fn reverse_between(&mut self, m: usize, n: usize) {
+        let start = link_at(&mut self.head, m);
+        let mut middle = start.take();
+        let rest = link_at(&mut middle, n.saturating_sub(m)).take();
+        *start = reverse_onto(middle, rest);
+    }
+
+    // Reverses each group of k elements in turn. If there's a shorter
+    // group left at the end, it stays as it is.
+    // time: O(n), space: O(1)
+    pub fn reverse_in_groups(&mut self, k: usize) {
+        if k < 2 {
+            return;
+        }
+        let groups = self.iter().count() / k;
+        let mut start = &mut self.head;
+        for _ in 0..groups {
+            let mut group = start.take();
+            let rest = link_at(&mut group, k).take();
+            *start = reverse_onto(group, rest);
+            start = link_at(start, k);
+        }
+    }
+
+    // Compares the first half with the second half reversed in place,
+    // then reverses the second half back.
+    // time: O(n), space: O(1)
+    pub fn is_palindrome(&mut self) -> bool
+    where
+        T: PartialEq,
+    {
+        let len = self.iter().count();
+        let back = link_at(&mut self.head, len.div_ceil(2)).take();
+        let back = List {
+            head: reverse_onto(back, None),
+        };
+        // The front half is at least as long, so zip stops at the end
+        // of the back half:
+        let is_palindrome = self.iter().zip(back.iter()).all(|(a, b)| a == b);
+        *link_at(&mut self.head, len.div_ceil(2)) = reverse_onto(back.into_link(), None);
+        is_palindrome
+    }
+
+    fn into_link(mut self) -> Link<T> {
+        self.head.take()
+    }
+}
+
+// Walks with two pointers, a fixed distance apart or one twice as fast
+// as the other, so that each of these is a single pass.
+impl<T> List<T> {
+    // The element k from the back, where 0 is the last one.
+    pub fn nth_from_back(&self, k: usize) -> Option<&T> {
+        let mut lead = self.iter();
+        // There have to be at least k + 1 elements:
+        for _ in 0..=k {
+            lead.next()?;
+        }
+        let mut trail = self.iter();
+        while lead.next().is_some() {
+            trail.next();
+        }
+        trail.next()
+    }
+
+    // The element halfway along, or the second of the two middle ones
+    // if the length is even.
+    pub fn middle(&self) -> Option<&T> {
+        let mut slow = self.iter();
+        let mut fast = self.iter();
+        while fast.next().is_some() && fast.next().is_some() {
+            slow.next();
+        }
+        slow.next()
+    }
+
+    // The last k elements, or all of them if there are fewer.
+    pub fn last_n(&self, k: usize) -> Iter<'_, T> {
+        let mut lead = self.iter();
+        for _ in 0..k {
+            if lead.next().is_none() {
+                return self.iter();
+            }
+        }
+        let mut trail = self.iter();
+        while lead.next().is_some() {
+            trail.next();
+        }
+        trail
+    }
+
+    // Every run of k consecutive elements, front to back. There are no
+    // windows of 0 elements.
+    pub fn sliding_windows(&self, k: usize) -> Windows<'_, T> {
+        let mut end = if k == 0 {
+            Iter { next: None }
+        } else {
+            self.iter()
+        };
+        // The end of the window runs k - 1 elements ahead of the start:
+        for _ in 1..k {
+            end.next();
+        }
+        Windows {
+            start: self.iter(),
+            end,
+            size: k,
+        }
+    }
+}
+
+pub struct Windows<'a, T> {
+    start: Iter<'a, T>,
+    end: Iter<'a, T>,
+    size: usize,
+}
+
+impl<'a, T> Iterator for Windows<'a, T> {
+    type Item = std::iter::Take<Iter<'a, T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.end.next()?;
+        let window = self.start.clone().take(self.size);
+        self.start.next();
+        Some(window)
+    }
+}
+
+// The link after the first `n` nodes, or the empty one at the end.
+fn link_at<T>(mut link: &mut Link<T>, n: usize) -> &mut Link<T> {
+    for _ in 0..n {
+        if link.is_none() {
+            break;
+        }
+        link = &mut link.as_mut().unwrap().next;
+    }
+    link
+}
+
+// The nodes of `link` in reverse, followed by `onto`.
+fn reverse_onto<T>(mut link: Link<T>, mut onto: Link<T>) -> Link<T> {
+    while let Some(mut node) = link {
+        link = node.next.take();
+        node.next = onto;
+        onto = Some(node);
+    }
+    onto
+}
+
+impl<T> IntoIterator for List<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter(self)
+    }
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        List { head: None }
+    }
+}
+
+impl<T> list::List for List<T> {
+    type Elem = T;
+
+    fn push_front(&mut self, elem: T) {
+        self.push(elem)
+    }
+
+    fn push_back(&mut self, elem: T) {
+        // Walk to the empty link at the end:
+        let mut link = &mut self.head;
+        while let Some(node) = link {
+            link = &mut node.next;
+        }
+        *link = Some(Box::new(Node { elem, next: None }));
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        self.pop()
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        // Walk to the link that holds the last node:
+        let mut link = &mut self.head;
+        while link.as_ref()?.next.is_some() {
+            link = &mut link.as_mut().unwrap().next;
+        }
+        link.take().map(|node| node.elem)
+    }
+
+    fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    fn visit<F>(&self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        for elem in self.iter() {
+            if !f(elem) {
+                break;
+            }
+        }
+    }
+}
+
 impl<T> Drop for List<T> {
     fn drop(&mut self) {
         // let mut cur_link = mem::replace(&mut self.head, None);
@@ -163,6 +386,7 @@ impl<T: PartialEq> PartialEq for List<T> {
 
 impl<T: Eq> Eq for List<T> {}
 
+#[cfg(test)]
 mod test {
     use super::List;
 
@@ -270,4 +494,109 @@ mod test {
         list2.pop();
         assert_ne!(list, list2);
     }
+
+    fn list_of(values: &[i32]) -> List<i32> {
+        let mut list = List::new();
+        for value in values.iter().rev() {
+            list.push(*value);
+        }
+        list
+    }
+
+    fn values(list: &List<i32>) -> Vec<i32> {
+        list.iter().cloned().collect()
+    }
+
+    #[test]
+    fn reverse() {
+        let mut list = list_of(&[1, 2, 3, 4]);
+        list.reverse();
+        assert_eq!(values(&list), vec![4, 3, 2, 1]);
+        let mut empty = list_of(&[]);
+        empty.reverse();
+        assert_eq!(values(&empty), vec![]);
+    }
+
+    #[test]
+    fn reverse_between() {
+        let mut list = list_of(&[0, 1, 2, 3, 4, 5]);
+        list.reverse_between(1, 4);
+        assert_eq!(values(&list), vec![0, 3, 2, 1, 4, 5]);
+        list.reverse_between(0, 6);
+        assert_eq!(values(&list), vec![5, 4, 1, 2, 3, 0]);
+        // Past the end, and empty ranges:
+        list.reverse_between(4, 10);
+        assert_eq!(values(&list), vec![5, 4, 1, 2, 0, 3]);
+        list.reverse_between(3, 3);
+        list.reverse_between(4, 2);
+        list.reverse_between(8, 9);
+        assert_eq!(values(&list), vec![5, 4, 1, 2, 0, 3]);
+    }
+
+    #[test]
+    fn reverse_in_groups() {
+        let mut list = list_of(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        list.reverse_in_groups(3);
+        assert_eq!(values(&list), vec![3, 2, 1, 6, 5, 4, 7, 8]);
+        list.reverse_in_groups(1);
+        assert_eq!(values(&list), vec![3, 2, 1, 6, 5, 4, 7, 8]);
+        list.reverse_in_groups(4);
+        assert_eq!(values(&list), vec![6, 1, 2, 3, 8, 7, 4, 5]);
+        list.reverse_in_groups(9);
+        assert_eq!(values(&list), vec![6, 1, 2, 3, 8, 7, 4, 5]);
+    }
+
+    #[test]
+    fn is_palindrome() {
+        for values_ in [vec![], vec![1], vec![1, 1], vec![1, 2, 1], vec![1, 2, 2, 1]].iter() {
+            let mut list = list_of(values_);
+            assert!(list.is_palindrome());
+            // And the list is put back the way it was:
+            assert_eq!(&values(&list), values_);
+        }
+        for values_ in [vec![1, 2], vec![1, 2, 3, 1], vec![1, 2, 3, 2, 2]].iter() {
+            let mut list = list_of(values_);
+            assert!(!list.is_palindrome());
+            assert_eq!(&values(&list), values_);
+        }
+    }
+
+    #[test]
+    fn nth_from_back_and_middle() {
+        let list = list_of(&[1, 2, 3, 4, 5]);
+        assert_eq!(list.nth_from_back(0), Some(&5));
+        assert_eq!(list.nth_from_back(4), Some(&1));
+        assert_eq!(list.nth_from_back(5), None);
+        assert_eq!(list.nth_from_back(usize::MAX), None);
+        assert_eq!(list_of(&[]).nth_from_back(0), None);
+
+        assert_eq!(list.middle(), Some(&3));
+        assert_eq!(list_of(&[1, 2, 3, 4]).middle(), Some(&3));
+        assert_eq!(list_of(&[1]).middle(), Some(&1));
+        assert_eq!(list_of(&[]).middle(), None);
+    }
+
+    #[test]
+    fn last_n_and_windows() {
+        let list = list_of(&[1, 2, 3, 4, 5]);
+        assert_eq!(list.last_n(2).cloned().collect::<Vec<_>>(), vec![4, 5]);
+        assert_eq!(list.last_n(0).count(), 0);
+        assert_eq!(list.last_n(9).count(), 5);
+
+        let windows: Vec<Vec<i32>> = list
+            .sliding_windows(3)
+            .map(|window| window.cloned().collect())
+            .collect();
+        assert_eq!(windows, vec![vec![1, 2, 3], vec![2, 3, 4], vec![3, 4, 5]]);
+        assert_eq!(list.sliding_windows(1).count(), 5);
+        assert_eq!(list.sliding_windows(5).count(), 1);
+        assert_eq!(list.sliding_windows(6).count(), 0);
+        assert_eq!(list_of(&[]).sliding_windows(2).count(), 0);
+    }
+
+    #[test]
+    fn empty_windows() {
+        assert_eq!(list_of(&[1]).sliding_windows(0).count(), 0);
+        assert_eq!(list_of(&[]).sliding_windows(0).count(), 0);
+    }
 }
@@ -1,71 +1,296 @@
 use std::collections::HashSet;
+use std::fmt;
+use std::hash::Hash;
+use std::ops::Deref;
 use std::rc::Rc;
+use std::sync::Arc;
 
-#[derive(Debug)]
-pub struct List<T> {
-    head: Link<T>,
+use super::list;
+
+// A persistent list: nothing is ever changed in place, every operation
+// returns a new list, and lists share whatever nodes they can. Nodes
+// after the last change are shared, and nodes before it are copied
+// ("path copying").
+//
+// The nodes are behind either Rc, or Arc so that lists can be shared
+// between threads: `List<T>` and `ArcList<T>`.
+pub struct PersistentList<T, P: SharedPointer> {
+    head: Link<T, P>,
 }
 
-type Link<T> = Option<Rc<Node<T>>>;
+pub type List<T> = PersistentList<T, RcPointer>;
+pub type ArcList<T> = PersistentList<T, ArcPointer>;
+
+type Link<T, P> = Option<<P as SharedPointer>::Pointer<Node<T, P>>>;
 
-#[derive(Debug)]
-struct Node<T> {
+struct Node<T, P: SharedPointer> {
     elem: T,
-    next: Link<T>,
+    next: Link<T, P>,
+}
+
+// A kind of reference counted pointer.
+pub trait SharedPointer {
+    type Pointer<T>: Deref<Target = T> + Clone;
+
+    fn new<T>(value: T) -> Self::Pointer<T>;
+    // The value, if this is the only pointer to it.
+    fn try_unwrap<T>(pointer: Self::Pointer<T>) -> Result<T, Self::Pointer<T>>;
 }
 
-impl<T> List<T>
-where
-    T: std::hash::Hash + std::cmp::Eq + std::marker::Copy,
-{
+pub enum RcPointer {}
+
+impl SharedPointer for RcPointer {
+    type Pointer<T> = Rc<T>;
+
+    fn new<T>(value: T) -> Rc<T> {
+        Rc::new(value)
+    }
+
+    fn try_unwrap<T>(pointer: Rc<T>) -> Result<T, Rc<T>> {
+        Rc::try_unwrap(pointer)
+    }
+}
+
+pub enum ArcPointer {}
+
+impl SharedPointer for ArcPointer {
+    type Pointer<T> = Arc<T>;
+
+    fn new<T>(value: T) -> Arc<T> {
+        Arc::new(value)
+    }
+
+    fn try_unwrap<T>(pointer: Arc<T>) -> Result<T, Arc<T>> {
+        Arc::try_unwrap(pointer)
+    }
+}
+
+impl<T, P: SharedPointer> PersistentList<T, P> {
     pub fn new() -> Self {
-        List { head: None }
+        PersistentList { head: None }
     }
 
-    pub fn append(&mut self, elem: T) -> Self {
-        List {
-            head: Some(Rc::new(Node {
-                elem: elem,
+    // The list with `elem` in front, sharing every node of this one.
+    pub fn cons(&self, elem: T) -> Self {
+        PersistentList {
+            head: Some(P::new(Node {
+                elem,
                 next: self.head.clone(),
-                // next: self.head.take(), // equivalent
-                // next: self.head,  // cannot move out of borrowed
             })),
         }
     }
+
+    // Everything after the head, shared.
     pub fn tail(&self) -> Self {
-        List {
+        PersistentList {
             head: self.head.as_ref().and_then(|node| node.next.clone()),
         }
     }
+
     pub fn head(&self) -> Option<&T> {
         self.head.as_ref().map(|node| &node.elem)
     }
-    // pub fn remove_dups(&mut self) -> &Self {
-    //     let mut set: HashSet<T> = HashSet::new();
-    //     let mut curr: Link<T> = self.head.take();
-    //     let mut prev: Link<T> = None;
-    //     while curr.is_some() {
-    //         if set.contains(&curr.as_ref().unwrap().elem) {
-    //             // Can't transfer ownership out of an Rc???
-    //             // "cannot assign to data in a `&` reference"???
-    //             prev.unwrap().next = curr.as_ref().and_then(|node| node.next.clone());
-    //             curr = curr.as_ref().and_then(|node| node.next.clone());
-    //         } else {
-    //             set.insert(curr.as_ref().unwrap().elem);
-    //             curr = curr.as_ref().and_then(|node| node.next.clone());
-    //             prev = curr.take();
-    //         }
-    //     }
-
-    //     &*self // make immutable before returning
-    // }
-}
-
-impl<T> Drop for List<T> {
+
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    pub fn nth(&self, n: usize) -> Option<&T> {
+        self.iter().nth(n)
+    }
+
+    // Everything after the first n elements, shared.
+    pub fn drop(&self, n: usize) -> Self {
+        let mut link = &self.head;
+        for _ in 0..n {
+            match link {
+                Some(node) => link = &node.next,
+                None => break,
+            }
+        }
+        PersistentList { head: link.clone() }
+    }
+
+    // Nothing can be shared, since every node is new.
+    pub fn map<U, F>(&self, f: F) -> PersistentList<U, P>
+    where
+        F: FnMut(&T) -> U,
+    {
+        let values: Vec<U> = self.iter().map(f).collect();
+        PersistentList::new().cons_all(values)
+    }
+
+    // Conses the values in reverse, so they end up in the same order in
+    // front of this list.
+    fn cons_all(&self, values: Vec<T>) -> Self {
+        let mut list = self.clone();
+        for value in values.into_iter().rev() {
+            list = list.cons(value);
+        }
+        list
+    }
+
+    // Keeps the elements where `keep` is true, copying the nodes up to
+    // the last one that's removed, and sharing everything after it.
+    fn retain_mask(&self, keep: &[bool]) -> Self
+    where
+        T: Clone,
+    {
+        let last_removed = match keep.iter().rposition(|keep| !keep) {
+            Some(index) => index,
+            // Nothing is removed, so it's all shared:
+            None => return self.clone(),
+        };
+        let copied: Vec<T> = self
+            .iter()
+            .zip(keep)
+            .take(last_removed)
+            .filter(|(_, keep)| **keep)
+            .map(|(elem, _)| elem.clone())
+            .collect();
+        self.drop(last_removed + 1).cons_all(copied)
+    }
+}
+
+impl<T: Clone, P: SharedPointer> PersistentList<T, P> {
+    // Copies every node, since the last one changes.
+    pub fn append(&self, elem: T) -> Self {
+        PersistentList::new().cons(elem).cons_all(self.to_values())
+    }
+
+    // Copies the nodes of this list, and shares all of `other`.
+    pub fn concat(&self, other: &Self) -> Self {
+        other.cons_all(self.to_values())
+    }
+
+    pub fn reverse(&self) -> Self {
+        let mut reversed = PersistentList::new();
+        for elem in self.iter() {
+            reversed = reversed.cons(elem.clone());
+        }
+        reversed
+    }
+
+    // The first n elements. They're copied, since the node after them
+    // has to change.
+    pub fn take(&self, n: usize) -> Self {
+        if n >= self.len() {
+            return self.clone();
+        }
+        PersistentList::new().cons_all(self.iter().take(n).cloned().collect())
+    }
+
+    pub fn filter<F>(&self, f: F) -> Self
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let keep: Vec<bool> = self.iter().map(f).collect();
+        self.retain_mask(&keep)
+    }
+
+    // The list with the element at `index` replaced, or None if it's
+    // out of bounds. Only the nodes up to `index` are copied.
+    pub fn update_at(&self, index: usize, elem: T) -> Option<Self> {
+        let rest = self.drop(index);
+        let old = rest.head.as_ref()?;
+        let updated = PersistentList {
+            head: old.next.clone(),
+        }
+        .cons(elem);
+        Some(updated.cons_all(self.iter().take(index).cloned().collect()))
+    }
+
+    // Keeps the first occurrence of each element, sharing everything
+    // after the last duplicate.
+    // time: O(n), space: O(n)
+    pub fn remove_dups(&self) -> Self
+    where
+        T: Hash + Eq,
+    {
+        let mut seen = HashSet::new();
+        let keep: Vec<bool> = self.iter().map(|elem| seen.insert(elem)).collect();
+        self.retain_mask(&keep)
+    }
+
+    fn to_values(&self) -> Vec<T> {
+        self.iter().cloned().collect()
+    }
+}
+
+// Cheap: only the head pointer is cloned.
+impl<T, P: SharedPointer> Clone for PersistentList<T, P> {
+    fn clone(&self) -> Self {
+        PersistentList {
+            head: self.head.clone(),
+        }
+    }
+}
+
+impl<T, P: SharedPointer> Default for PersistentList<T, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// The nodes might be shared with other lists, so they can't be
+// changed: popping clones the element if its node is shared, and
+// anything at the back means copying the whole list.
+impl<T: Clone, P: SharedPointer> list::List for PersistentList<T, P> {
+    type Elem = T;
+
+    fn push_front(&mut self, elem: T) {
+        *self = self.cons(elem);
+    }
+
+    fn push_back(&mut self, elem: T) {
+        *self = self.append(elem);
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        self.head.take().map(|node| match P::try_unwrap(node) {
+            Ok(mut node) => {
+                self.head = node.next.take();
+                node.elem
+            }
+            Err(node) => {
+                self.head = node.next.clone();
+                node.elem.clone()
+            }
+        })
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        let last = self.iter().last()?.clone();
+        *self = self.take(self.len() - 1);
+        Some(last)
+    }
+
+    fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    fn visit<F>(&self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        for elem in self.iter() {
+            if !f(elem) {
+                break;
+            }
+        }
+    }
+}
+
+impl<T, P: SharedPointer> Drop for PersistentList<T, P> {
     fn drop(&mut self) {
         let mut head = self.head.take();
         while let Some(node) = head {
-            if let Ok(mut node) = Rc::try_unwrap(node) {
+            // Stop at the first node that another list still holds:
+            if let Ok(mut node) = P::try_unwrap(node) {
                 head = node.next.take();
             } else {
                 break;
@@ -73,50 +298,43 @@ impl<T> Drop for List<T> {
         }
     }
 }
-impl<T: PartialEq> PartialEq for Node<T> {
-    fn eq(&self, other: &Self) -> bool {
-        self.elem == other.elem && self.next == other.next
-    }
 
-    fn ne(&self, other: &Self) -> bool {
-        self.elem != other.elem || self.next != other.next
+impl<T: PartialEq, P: SharedPointer> PartialEq for PersistentList<T, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
     }
 }
 
-impl<T: PartialEq> PartialEq for List<T> {
-    fn eq(&self, other: &Self) -> bool {
-        self.head == other.head
-    }
+impl<T: Eq, P: SharedPointer> Eq for PersistentList<T, P> {}
 
-    fn ne(&self, other: &Self) -> bool {
-        self.head != other.head
+impl<T: fmt::Debug, P: SharedPointer> fmt::Debug for PersistentList<T, P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
     }
 }
 
-impl<T: Eq> Eq for List<T> {}
-
 // Iter implementation is identical to second
 
 // Note that we can't implement IntoIter or IterMut for this type. We
 // only have shared access to elements.
-pub struct Iter<'a, T> {
-    next: Option<&'a Node<T>>,
+pub struct Iter<'a, T, P: SharedPointer> {
+    next: Option<&'a Node<T, P>>,
 }
 
-impl<T> List<T> {
-    pub fn iter(&self) -> Iter<'_, T> {
+impl<T, P: SharedPointer> PersistentList<T, P> {
+    pub fn iter(&self) -> Iter<'_, T, P> {
         Iter {
-            next: self.head.as_ref().map(|node| &**node),
+            next: self.head.as_deref(),
         }
     }
 }
 
-impl<'a, T> Iterator for Iter<'a, T> {
+impl<'a, T, P: SharedPointer> Iterator for Iter<'a, T, P> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.next.map(|node| {
-            self.next = node.next.as_ref().map(|node| &**node);
+            self.next = node.next.as_deref();
             &node.elem
         })
     }
@@ -124,14 +342,34 @@ impl<'a, T> Iterator for Iter<'a, T> {
 
 #[cfg(test)]
 mod test {
-    use super::List;
+    use super::{ArcList, List, Node, PersistentList, SharedPointer};
+
+    fn list_of(values: &[i32]) -> List<i32> {
+        values
+            .iter()
+            .rev()
+            .fold(List::new(), |list, v| list.cons(*v))
+    }
+
+    fn values<P: SharedPointer>(list: &PersistentList<i32, P>) -> Vec<i32> {
+        list.iter().cloned().collect()
+    }
+
+    // The address of the nth node, to check which nodes are shared:
+    fn node_at<T, P: SharedPointer>(list: &PersistentList<T, P>, n: usize) -> *const Node<T, P> {
+        let mut node = list.head.as_deref().unwrap();
+        for _ in 0..n {
+            node = node.next.as_deref().unwrap();
+        }
+        node
+    }
 
     #[test]
     fn basics() {
-        let mut list = List::new();
+        let list = List::new();
         assert_eq!(list.head(), None);
 
-        let list = list.append(1).append(2).append(3);
+        let list = list.cons(1).cons(2).cons(3);
         assert_eq!(list.head(), Some(&3));
 
         let list = list.tail();
@@ -147,9 +385,10 @@ mod test {
         let list = list.tail();
         assert_eq!(list.head(), None);
     }
+
     #[test]
     fn iter() {
-        let list = List::new().append(1).append(2).append(3);
+        let list = List::new().cons(1).cons(2).cons(3);
 
         let mut iter = list.iter();
         assert_eq!(iter.next(), Some(&3));
@@ -159,8 +398,8 @@ mod test {
 
     #[test]
     fn eq() {
-        let mut list = List::new().append(1).append(2).append(3);
-        let mut list2 = List::new().append(1).append(2).append(3);
+        let mut list = List::new().cons(1).cons(2).cons(3);
+        let mut list2 = List::new().cons(1).cons(2).cons(3);
 
         assert_eq!(list, list2);
         list2 = list2.tail();
@@ -168,4 +407,112 @@ mod test {
         list = list.tail();
         assert_eq!(list, list2);
     }
+
+    #[test]
+    fn append_reverse_concat() {
+        let list = list_of(&[1, 2, 3]);
+        assert_eq!(values(&list.append(4)), vec![1, 2, 3, 4]);
+        assert_eq!(values(&list.reverse()), vec![3, 2, 1]);
+        assert!(List::<i32>::new().reverse().is_empty());
+
+        let other = list_of(&[4, 5]);
+        let both = list.concat(&other);
+        assert_eq!(values(&both), vec![1, 2, 3, 4, 5]);
+        // `other` is shared, not copied:
+        assert_eq!(node_at(&both, 3), node_at(&other, 0));
+        // And the originals are untouched:
+        assert_eq!(values(&list), vec![1, 2, 3]);
+        assert_eq!(format!("{:?}", other), "[4, 5]");
+    }
+
+    #[test]
+    fn map_filter_take_drop_nth() {
+        let list = list_of(&[1, 2, 3, 4, 5]);
+        let doubled = list.map(|x| x * 2);
+        assert_eq!(values(&doubled), vec![2, 4, 6, 8, 10]);
+        let strings = list.map(|x| x.to_string());
+        assert_eq!(strings.head().map(String::as_str), Some("1"));
+
+        let odd = list.filter(|x| x % 2 == 1);
+        assert_eq!(values(&odd), vec![1, 3, 5]);
+        // Everything after the last removed element is shared:
+        assert_eq!(node_at(&odd, 2), node_at(&list, 4));
+        let small = list.filter(|x| *x < 3);
+        assert_eq!(values(&small), vec![1, 2]);
+        let all = list.filter(|_| true);
+        assert_eq!(node_at(&all, 0), node_at(&list, 0));
+
+        assert_eq!(values(&list.take(2)), vec![1, 2]);
+        assert_eq!(values(&list.take(9)), values(&list));
+        assert_eq!(values(&list.drop(3)), vec![4, 5]);
+        assert_eq!(node_at(&list.drop(3), 0), node_at(&list, 3));
+        assert!(list.drop(9).is_empty());
+
+        assert_eq!(list.nth(0), Some(&1));
+        assert_eq!(list.nth(4), Some(&5));
+        assert_eq!(list.nth(5), None);
+        assert_eq!(list.len(), 5);
+    }
+
+    #[test]
+    fn remove_dups_shares_suffix() {
+        let list = list_of(&[1, 2, 1, 3, 4, 5]);
+        let unique = list.remove_dups();
+        assert_eq!(values(&unique), vec![1, 2, 3, 4, 5]);
+        // 3, 4 and 5 come after the last duplicate:
+        for i in 0..3 {
+            assert_eq!(node_at(&unique, 2 + i), node_at(&list, 3 + i));
+        }
+        assert_eq!(values(&list), vec![1, 2, 1, 3, 4, 5]);
+
+        let already_unique = list_of(&[1, 2]);
+        assert_eq!(
+            node_at(&already_unique.remove_dups(), 0),
+            node_at(&already_unique, 0)
+        );
+    }
+
+    #[test]
+    fn update_at_shares_suffix() {
+        let list = list_of(&[1, 2, 3, 4]);
+        let updated = list.update_at(1, 20).unwrap();
+        assert_eq!(values(&updated), vec![1, 20, 3, 4]);
+        assert_eq!(node_at(&updated, 2), node_at(&list, 2));
+        assert_ne!(node_at(&updated, 0), node_at(&list, 0));
+        assert_eq!(values(&list.update_at(3, 40).unwrap()), vec![1, 2, 3, 40]);
+        assert!(list.update_at(4, 0).is_none());
+        assert_eq!(values(&list), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn long_list_drops_without_recursion() {
+        let mut list = List::new();
+        for i in 0..100_000 {
+            list = list.cons(i);
+        }
+        let shared = list.drop(50_000);
+        drop(list);
+        assert_eq!(shared.len(), 50_000);
+    }
+
+    #[test]
+    fn arc_list_across_threads() {
+        let base = (0..100).rev().fold(ArcList::new(), |list, v| list.cons(v));
+        let handles: Vec<_> = (0..4)
+            .map(|t| {
+                let base = base.clone();
+                std::thread::spawn(move || {
+                    let mine = base.drop(10 * t).cons(-1);
+                    (mine.len(), mine.iter().sum::<i32>())
+                })
+            })
+            .collect();
+        for (t, handle) in handles.into_iter().enumerate() {
+            let (len, sum) = handle.join().unwrap();
+            let skipped = 10 * t as i32;
+            assert_eq!(len, 100 - 10 * t + 1);
+            assert_eq!(sum, (skipped..100).sum::<i32>() - 1);
+        }
+        assert_eq!(values(&base).len(), 100);
+    }
 }
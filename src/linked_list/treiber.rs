@@ -0,0 +1,206 @@
+// A lock-free stack (R. K. Treiber, 1986): the head is swapped in with
+// compare_exchange, so threads never wait for each other, they just
+// retry when another thread got there first. Popped nodes are freed
+// through hazard pointers, which also rules out the ABA problem: a
+// node can't be freed and reused while a thread still has its address.
+//
+// Check the unsafe code with Miri, over a few seeds, since the races
+// only show up in some interleavings (seed 2 used to catch a push
+// that held an unprotected head):
+// MIRIFLAGS="-Zmiri-many-seeds=0..8" cargo +nightly miri test --lib linked_list::treiber
+use std::fmt;
+use std::mem::ManuallyDrop;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+use super::hazard::Domain;
+
+pub struct TreiberStack<T> {
+    head: AtomicPtr<Node<T>>,
+    domain: Domain,
+}
+
+struct Node<T> {
+    // Moved out when the node is popped, before it's retired:
+    elem: ManuallyDrop<T>,
+    next: *mut Node<T>,
+}
+
+// Elements are sent to whichever thread pops them.
+unsafe impl<T: Send> Send for TreiberStack<T> {}
+unsafe impl<T: Send> Sync for TreiberStack<T> {}
+
+impl<T> TreiberStack<T> {
+    pub fn new() -> Self {
+        TreiberStack {
+            head: AtomicPtr::new(ptr::null_mut()),
+            domain: Domain::new(),
+        }
+    }
+
+    pub fn push(&self, elem: T) {
+        let node = Box::into_raw(Box::new(Node {
+            elem: ManuallyDrop::new(elem),
+            next: ptr::null_mut(),
+        }));
+        let guard = self.domain.guard();
+        loop {
+            // Protected, so that the head can't be freed and its
+            // address reused before the exchange, which would leave
+            // `next` pointing at a freed node:
+            let head = guard.protect(&self.head);
+            // The node isn't shared until the exchange succeeds:
+            unsafe { (*node).next = head };
+            if self
+                .head
+                .compare_exchange_weak(head, node, Ordering::Release, Ordering::Relaxed)
+                .is_ok()
+            {
+                return;
+            }
+        }
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        let guard = self.domain.guard();
+        loop {
+            let head = guard.protect(&self.head);
+            if head.is_null() {
+                return None;
+            }
+            // The guard keeps the node alive, and `next` never changes
+            // once the node is pushed:
+            let next = unsafe { (*head).next };
+            if self
+                .head
+                .compare_exchange(head, next, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+            {
+                guard.clear();
+                unsafe {
+                    // Only the thread that unlinked the node gets here:
+                    let elem = ManuallyDrop::into_inner(ptr::read(&(*head).elem));
+                    self.domain.retire(head);
+                    return Some(elem);
+                }
+            }
+        }
+    }
+
+    // A snapshot: other threads can change the stack straight after.
+    pub fn is_empty(&self) -> bool {
+        self.head.load(Ordering::Acquire).is_null()
+    }
+}
+
+impl<T> Default for TreiberStack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for TreiberStack<T> {
+    fn drop(&mut self) {
+        let mut node = *self.head.get_mut();
+        while !node.is_null() {
+            // Nothing else can hold the nodes any more:
+            let mut boxed = unsafe { Box::from_raw(node) };
+            unsafe { ManuallyDrop::drop(&mut boxed.elem) };
+            node = boxed.next;
+        }
+    }
+}
+
+impl<T> fmt::Debug for TreiberStack<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TreiberStack")
+            .field("is_empty", &self.is_empty())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TreiberStack;
+    use std::collections::HashSet;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    // Fewer rounds under Miri, which is much slower:
+    const ROUNDS: usize = if cfg!(miri) { 50 } else { 20_000 };
+    const THREADS: usize = 4;
+
+    #[test]
+    fn basics() {
+        let stack = TreiberStack::new();
+        assert_eq!(stack.pop(), None);
+        assert!(stack.is_empty());
+        stack.push(1);
+        stack.push(2);
+        stack.push(3);
+        assert!(!stack.is_empty());
+        assert_eq!(stack.pop(), Some(3));
+        assert_eq!(stack.pop(), Some(2));
+        stack.push(4);
+        assert_eq!(stack.pop(), Some(4));
+        assert_eq!(stack.pop(), Some(1));
+        assert_eq!(stack.pop(), None);
+    }
+
+    // Counts how many are alive, to check nothing is leaked or dropped
+    // twice.
+    struct Counted(Arc<AtomicUsize>);
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.0.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn drops_every_element_once() {
+        let alive = Arc::new(AtomicUsize::new(0));
+        let stack = TreiberStack::new();
+        for _ in 0..100 {
+            alive.fetch_add(1, Ordering::SeqCst);
+            stack.push(Counted(alive.clone()));
+        }
+        for _ in 0..40 {
+            stack.pop();
+        }
+        assert_eq!(alive.load(Ordering::SeqCst), 60);
+        drop(stack);
+        assert_eq!(alive.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn stress() {
+        let stack = Arc::new(TreiberStack::new());
+        let handles: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let stack = stack.clone();
+                thread::spawn(move || {
+                    let mut popped = Vec::new();
+                    for i in 0..ROUNDS {
+                        stack.push(t * ROUNDS + i);
+                        if i % 2 == 1 {
+                            popped.extend(stack.pop());
+                        }
+                    }
+                    popped
+                })
+            })
+            .collect();
+        let mut seen = HashSet::new();
+        for handle in handles {
+            for value in handle.join().unwrap() {
+                assert!(seen.insert(value), "popped {} twice", value);
+            }
+        }
+        while let Some(value) = stack.pop() {
+            assert!(seen.insert(value), "popped {} twice", value);
+        }
+        assert_eq!(seen.len(), THREADS * ROUNDS);
+    }
+}
@@ -0,0 +1,813 @@
+// A doubly linked list whose nodes all live in one Vec, linked by
+// slot number instead of by Rc<RefCell<..>>, like `ArenaTree` does
+// for the binary trees. There are no reference counts or borrow flags
+// per node, no borrows that can panic at runtime, and the nodes sit
+// next to each other in memory.
+//
+// Removed slots go on a free list and are reused. Every slot has a
+// generation that's bumped when its element is removed, and an
+// `Index` remembers the generation it was handed out with, so an
+// index to a removed element stays stale even once its slot holds a
+// new one. A slot whose generation runs out is never reused.
+//
+// Check the unsafe code in `IterMut` with Miri:
+// cargo +nightly miri test --lib linked_list::vec_list
+use std::fmt;
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::ops;
+
+use super::list;
+
+// Where an element is, for as long as it's in the list. Only
+// meaningful for the list that handed it out: another list can have
+// an element at the same slot and generation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Index {
+    slot: u32,
+    generation: u32,
+}
+
+#[derive(Clone)]
+pub struct VecList<T> {
+    slots: Vec<Slot<T>>,
+    head: Option<u32>,
+    tail: Option<u32>,
+    // The most recently freed slot, which links to the one before:
+    free: Option<u32>,
+    len: usize,
+}
+
+#[derive(Clone)]
+struct Slot<T> {
+    generation: u32,
+    entry: Entry<T>,
+}
+
+#[derive(Clone)]
+enum Entry<T> {
+    Occupied(Node<T>),
+    Free { next_free: Option<u32> },
+}
+
+#[derive(Clone)]
+struct Node<T> {
+    elem: T,
+    prev: Option<u32>,
+    next: Option<u32>,
+}
+
+impl<T> Default for VecList<T> {
+    fn default() -> Self {
+        VecList {
+            slots: Vec::new(),
+            head: None,
+            tail: None,
+            free: None,
+            len: 0,
+        }
+    }
+}
+
+impl<T> VecList<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        VecList {
+            slots: Vec::with_capacity(capacity),
+            ..Self::default()
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // Removes every element. Each slot's generation is bumped, so
+    // indices into the list all go stale.
+    // time: O(n)
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+
+    pub fn push_front(&mut self, elem: T) -> Index {
+        self.link_between(None, self.head, elem)
+    }
+
+    pub fn push_back(&mut self, elem: T) -> Index {
+        self.link_between(self.tail, None, elem)
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.head.map(|slot| self.unlink(slot))
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.tail.map(|slot| self.unlink(slot))
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.head.map(|slot| &self.node(slot).elem)
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.tail.map(|slot| &self.node(slot).elem)
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        let slot = self.head?;
+        Some(&mut self.node_mut(slot).elem)
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        let slot = self.tail?;
+        Some(&mut self.node_mut(slot).elem)
+    }
+
+    pub fn contains(&self, index: Index) -> bool {
+        self.slot_of(index).is_some()
+    }
+
+    // None if the element was removed.
+    pub fn get(&self, index: Index) -> Option<&T> {
+        let slot = self.slot_of(index)?;
+        Some(&self.node(slot).elem)
+    }
+
+    pub fn get_mut(&mut self, index: Index) -> Option<&mut T> {
+        let slot = self.slot_of(index)?;
+        Some(&mut self.node_mut(slot).elem)
+    }
+
+    // None if the element was removed already.
+    // time: O(1)
+    pub fn remove(&mut self, index: Index) -> Option<T> {
+        let slot = self.slot_of(index)?;
+        Some(self.unlink(slot))
+    }
+
+    pub fn front_index(&self) -> Option<Index> {
+        self.head.map(|slot| self.index_of(slot))
+    }
+
+    pub fn back_index(&self) -> Option<Index> {
+        self.tail.map(|slot| self.index_of(slot))
+    }
+
+    // The element after the given one, or None at the back or if the
+    // element was removed.
+    pub fn next_index(&self, index: Index) -> Option<Index> {
+        let next = self.node(self.slot_of(index)?).next?;
+        Some(self.index_of(next))
+    }
+
+    pub fn prev_index(&self, index: Index) -> Option<Index> {
+        let prev = self.node(self.slot_of(index)?).prev?;
+        Some(self.index_of(prev))
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            list: self,
+            front: self.head,
+            back: self.tail,
+            len: self.len,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            slots: self.slots.as_mut_ptr(),
+            front: self.head,
+            back: self.tail,
+            len: self.len,
+            _boo: PhantomData,
+        }
+    }
+
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        let current = self.head;
+        CursorMut {
+            list: self,
+            current,
+        }
+    }
+
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        let current = self.tail;
+        CursorMut {
+            list: self,
+            current,
+        }
+    }
+
+    // A cursor at the given element, or None if it was removed. Unlike
+    // the refcell list's `cursor_at`, this is O(1), since the index
+    // says where the node is.
+    pub fn cursor_at(&mut self, index: Index) -> Option<CursorMut<'_, T>> {
+        let current = Some(self.slot_of(index)?);
+        Some(CursorMut {
+            list: self,
+            current,
+        })
+    }
+
+    // The slot, if the index is still current.
+    fn slot_of(&self, index: Index) -> Option<u32> {
+        match self.slots.get(index.slot as usize) {
+            Some(Slot {
+                generation,
+                entry: Entry::Occupied(_),
+            }) if *generation == index.generation => Some(index.slot),
+            _ => None,
+        }
+    }
+
+    fn index_of(&self, slot: u32) -> Index {
+        Index {
+            slot,
+            generation: self.slots[slot as usize].generation,
+        }
+    }
+
+    // Every slot reachable from the head, the tail or another node's
+    // links is occupied.
+    fn node(&self, slot: u32) -> &Node<T> {
+        match &self.slots[slot as usize].entry {
+            Entry::Occupied(node) => node,
+            Entry::Free { .. } => unreachable!("linked slot {} is free", slot),
+        }
+    }
+
+    fn node_mut(&mut self, slot: u32) -> &mut Node<T> {
+        match &mut self.slots[slot as usize].entry {
+            Entry::Occupied(node) => node,
+            Entry::Free { .. } => unreachable!("linked slot {} is free", slot),
+        }
+    }
+
+    // Stores the node in a free slot if there is one, or a new slot at
+    // the end.
+    fn alloc(&mut self, node: Node<T>) -> u32 {
+        match self.free {
+            Some(slot) => {
+                let entry = &mut self.slots[slot as usize].entry;
+                self.free = match *entry {
+                    Entry::Free { next_free } => next_free,
+                    Entry::Occupied(_) => unreachable!("free slot {} is occupied", slot),
+                };
+                *entry = Entry::Occupied(node);
+                slot
+            }
+            None => {
+                assert!(
+                    self.slots.len() < u32::MAX as usize,
+                    "a VecList can't have more than u32::MAX slots"
+                );
+                self.slots.push(Slot {
+                    generation: 0,
+                    entry: Entry::Occupied(node),
+                });
+                (self.slots.len() - 1) as u32
+            }
+        }
+    }
+
+    // Links a new node in between two neighbours, where None is past
+    // either end of the list.
+    fn link_between(&mut self, prev: Option<u32>, next: Option<u32>, elem: T) -> Index {
+        let slot = self.alloc(Node { elem, prev, next });
+        match prev {
+            Some(prev) => self.node_mut(prev).next = Some(slot),
+            None => self.head = Some(slot),
+        }
+        match next {
+            Some(next) => self.node_mut(next).prev = Some(slot),
+            None => self.tail = Some(slot),
+        }
+        self.len += 1;
+        self.index_of(slot)
+    }
+
+    // Unlinks the node, frees its slot, and returns its element.
+    // time: O(1)
+    fn unlink(&mut self, slot: u32) -> T {
+        let Slot { generation, entry } = &mut self.slots[slot as usize];
+        let entry = std::mem::replace(
+            entry,
+            Entry::Free {
+                next_free: self.free,
+            },
+        );
+        let node = match entry {
+            Entry::Occupied(node) => node,
+            Entry::Free { .. } => unreachable!("linked slot {} is free", slot),
+        };
+        // A slot that's used up all its generations is left out of the
+        // free list, so that no old index can match it again:
+        if *generation < u32::MAX {
+            *generation += 1;
+            self.free = Some(slot);
+        }
+        match node.prev {
+            Some(prev) => self.node_mut(prev).next = node.next,
+            None => self.head = node.next,
+        }
+        match node.next {
+            Some(next) => self.node_mut(next).prev = node.prev,
+            None => self.tail = node.prev,
+        }
+        self.len -= 1;
+        node.elem
+    }
+}
+
+// A cursor over a list that can edit it, like `refcell::CursorMut`.
+// It's either at an element, or at the "ghost" position between the
+// tail and the head, where moving next wraps around to the head and
+// moving back to the tail.
+pub struct CursorMut<'a, T> {
+    list: &'a mut VecList<T>,
+    current: Option<u32>,
+}
+
+impl<'a, T> CursorMut<'a, T> {
+    // None at the ghost position.
+    pub fn current(&mut self) -> Option<&mut T> {
+        let slot = self.current?;
+        Some(&mut self.list.node_mut(slot).elem)
+    }
+
+    pub fn index(&self) -> Option<Index> {
+        self.current.map(|slot| self.list.index_of(slot))
+    }
+
+    pub fn move_next(&mut self) {
+        self.current = match self.current {
+            Some(slot) => self.list.node(slot).next,
+            None => self.list.head,
+        };
+    }
+
+    pub fn move_prev(&mut self) {
+        self.current = match self.current {
+            Some(slot) => self.list.node(slot).prev,
+            None => self.list.tail,
+        };
+    }
+
+    // At the ghost position, inserts at the back of the list.
+    pub fn insert_before(&mut self, elem: T) -> Index {
+        let (prev, next) = match self.current {
+            Some(slot) => (self.list.node(slot).prev, Some(slot)),
+            None => (self.list.tail, None),
+        };
+        self.list.link_between(prev, next, elem)
+    }
+
+    // At the ghost position, inserts at the front of the list.
+    pub fn insert_after(&mut self, elem: T) -> Index {
+        let (prev, next) = match self.current {
+            Some(slot) => (Some(slot), self.list.node(slot).next),
+            None => (None, self.list.head),
+        };
+        self.list.link_between(prev, next, elem)
+    }
+
+    // Removes the current element and moves to the next one.
+    pub fn remove_current(&mut self) -> Option<T> {
+        let slot = self.current?;
+        self.current = self.list.node(slot).next;
+        Some(self.list.unlink(slot))
+    }
+
+    // Moves everything after the current element into a new list. At
+    // the ghost position, that's the whole list. The moved elements get
+    // slots in the new list, so their old indices go stale.
+    // time: O(k) for the k elements moved
+    pub fn split_after(&mut self) -> VecList<T> {
+        let mut rest = VecList::new();
+        loop {
+            let next = match self.current {
+                Some(slot) => self.list.node(slot).next,
+                None => self.list.head,
+            };
+            match next {
+                Some(next) => {
+                    rest.push_back(self.list.unlink(next));
+                }
+                None => return rest,
+            }
+        }
+    }
+
+    // Moves every element of `other` in before the current one. At the
+    // ghost position, they go at the back of the list. The nodes can't
+    // be relinked across arenas, so each one is moved into a slot of
+    // this list, and `other`'s indices don't carry over.
+    // time: O(m) for the m elements of `other`
+    pub fn splice_before(&mut self, mut other: VecList<T>) {
+        while let Some(elem) = other.pop_front() {
+            self.insert_before(elem);
+        }
+    }
+
+    // At the ghost position, the elements of `other` go at the front of
+    // the list.
+    // time: O(m) for the m elements of `other`
+    pub fn splice_after(&mut self, mut other: VecList<T>) {
+        while let Some(elem) = other.pop_back() {
+            self.insert_after(elem);
+        }
+    }
+}
+
+pub struct Iter<'a, T> {
+    list: &'a VecList<T>,
+    front: Option<u32>,
+    back: Option<u32>,
+    // Stops the ends from passing each other:
+    len: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let node = self.list.node(self.front?);
+        self.front = node.next;
+        self.len -= 1;
+        Some(&node.elem)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let node = self.list.node(self.back?);
+        self.back = node.prev;
+        self.len -= 1;
+        Some(&node.elem)
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+// Hands out `&mut` to elements of different slots, which a borrow of
+// the Vec can't do, so it walks the slots through a raw pointer. The
+// links never visit a slot twice, so the references never alias.
+pub struct IterMut<'a, T> {
+    slots: *mut Slot<T>,
+    front: Option<u32>,
+    back: Option<u32>,
+    len: usize,
+    // The list is mutably borrowed for as long as the iterator lives:
+    _boo: PhantomData<&'a mut VecList<T>>,
+}
+
+impl<'a, T> IterMut<'a, T> {
+    fn node(&mut self, slot: u32) -> &'a mut Node<T> {
+        // Every linked slot is in bounds and occupied:
+        match unsafe { &mut (*self.slots.add(slot as usize)).entry } {
+            Entry::Occupied(node) => node,
+            Entry::Free { .. } => unreachable!("linked slot {} is free", slot),
+        }
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let node = self.node(self.front?);
+        self.front = node.next;
+        self.len -= 1;
+        Some(&mut node.elem)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let node = self.node(self.back?);
+        self.back = node.prev;
+        self.len -= 1;
+        Some(&mut node.elem)
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+impl<'a, T> IntoIterator for &'a VecList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut VecList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T> FromIterator<T> for VecList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = VecList::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> Extend<T> for VecList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push_back(elem);
+        }
+    }
+}
+
+// Panics if the element was removed.
+impl<T> ops::Index<Index> for VecList<T> {
+    type Output = T;
+
+    fn index(&self, index: Index) -> &T {
+        self.get(index).expect("stale VecList index")
+    }
+}
+
+impl<T> ops::IndexMut<Index> for VecList<T> {
+    fn index_mut(&mut self, index: Index) -> &mut T {
+        self.get_mut(index).expect("stale VecList index")
+    }
+}
+
+impl<T: PartialEq> PartialEq for VecList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for VecList<T> {}
+
+impl<T: fmt::Debug> fmt::Debug for VecList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> list::List for VecList<T> {
+    type Elem = T;
+
+    fn push_front(&mut self, elem: T) {
+        VecList::push_front(self, elem);
+    }
+
+    fn push_back(&mut self, elem: T) {
+        VecList::push_back(self, elem);
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        VecList::pop_front(self)
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        VecList::pop_back(self)
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn visit<F>(&self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        for elem in self.iter() {
+            if !f(elem) {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::VecList;
+
+    #[test]
+    fn basics() {
+        let mut list = VecList::new();
+        assert_eq!(list.pop_front(), None);
+        let b = list.push_back(2);
+        let a = list.push_front(1);
+        let c = list.push_back(3);
+        assert_eq!(list.len(), 3);
+        assert_eq!(format!("{:?}", list), "[1, 2, 3]");
+        assert_eq!(list.front(), Some(&1));
+        assert_eq!(list.back(), Some(&3));
+        assert_eq!(list[b], 2);
+        list[b] = 20;
+        *list.get_mut(c).unwrap() += 10;
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 20, 13]);
+
+        assert_eq!(list.front_index(), Some(a));
+        assert_eq!(list.next_index(a), Some(b));
+        assert_eq!(list.prev_index(a), None);
+        assert_eq!(list.back_index(), Some(c));
+
+        assert_eq!(list.pop_back(), Some(13));
+        assert_eq!(list.pop_front(), Some(1));
+        assert_eq!(list.pop_front(), Some(20));
+        assert!(list.is_empty());
+        assert_eq!(list.front_mut(), None);
+    }
+
+    #[test]
+    fn remove_by_index() {
+        let mut list: VecList<_> = (0..5).collect();
+        let indices: Vec<_> = {
+            let mut index = list.front_index();
+            let mut indices = Vec::new();
+            while let Some(i) = index {
+                indices.push(i);
+                index = list.next_index(i);
+            }
+            indices
+        };
+        assert_eq!(list.remove(indices[2]), Some(2));
+        assert_eq!(list.remove(indices[0]), Some(0));
+        assert_eq!(list.remove(indices[4]), Some(4));
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!(list.front_index(), Some(indices[1]));
+        assert_eq!(list.back_index(), Some(indices[3]));
+
+        // Removing again, or through a stale index, does nothing:
+        assert_eq!(list.remove(indices[2]), None);
+        assert!(!list.contains(indices[0]));
+        assert_eq!(list.get(indices[4]), None);
+        assert_eq!(list.next_index(indices[2]), None);
+    }
+
+    #[test]
+    fn reuses_slots_without_reviving_indices() {
+        let mut list = VecList::new();
+        let old = list.push_back('a');
+        list.push_back('b');
+        list.remove(old);
+        let new = list.push_back('c');
+        // The freed slot is reused, rather than the Vec growing:
+        assert_eq!(list.slots.len(), 2);
+        assert_eq!(list.get(old), None);
+        assert_eq!(list.get(new), Some(&'c'));
+
+        list.clear();
+        assert!(!list.contains(new));
+        assert_eq!(list.slots.len(), 2);
+    }
+
+    #[test]
+    fn retires_slots_out_of_generations() {
+        let mut list = VecList::new();
+        let index = list.push_back(1);
+        list.slots[0].generation = u32::MAX;
+        let index = super::Index {
+            generation: u32::MAX,
+            ..index
+        };
+        assert_eq!(list.remove(index), Some(1));
+        list.push_back(2);
+        // The used-up slot is skipped, so the stale index can't match:
+        assert_eq!(list.slots.len(), 2);
+        assert_eq!(list.get(index), None);
+    }
+
+    #[test]
+    fn iterators() {
+        let mut list: VecList<_> = (1..=5).collect();
+        let mut iter = list.iter();
+        assert_eq!(iter.len(), 5);
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next_back(), Some(&5));
+        assert_eq!(iter.next_back(), Some(&4));
+        assert_eq!(iter.next(), Some(&2));
+        assert_eq!(iter.next(), Some(&3));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        for (i, elem) in list.iter_mut().rev().enumerate() {
+            *elem *= 10 * (i + 1);
+        }
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            vec![50, 80, 90, 80, 50]
+        );
+        // Both references can be held at once:
+        let mut iter = list.iter_mut();
+        let first = iter.next().unwrap();
+        let second = iter.next().unwrap();
+        std::mem::swap(first, second);
+        assert_eq!(list, vec![80, 50, 90, 80, 50].into_iter().collect());
+    }
+
+    #[test]
+    fn cursor() {
+        let mut list: VecList<_> = (1..=3).collect();
+        let two = list.next_index(list.front_index().unwrap()).unwrap();
+
+        let mut cursor = list.cursor_at(two).unwrap();
+        assert_eq!(cursor.current(), Some(&mut 2));
+        cursor.insert_before(10);
+        let after = cursor.insert_after(20);
+        cursor.move_next();
+        assert_eq!(cursor.index(), Some(after));
+        assert_eq!(cursor.remove_current(), Some(20));
+        assert_eq!(cursor.current(), Some(&mut 3));
+        cursor.move_next();
+        // At the ghost, inserting before appends and after prepends:
+        assert_eq!(cursor.current(), None);
+        cursor.insert_before(4);
+        cursor.insert_after(0);
+        cursor.move_prev();
+        assert_eq!(cursor.current(), Some(&mut 4));
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            vec![0, 1, 10, 2, 3, 4]
+        );
+        assert!(!list.contains(after));
+    }
+
+    #[test]
+    fn split_and_splice() {
+        let mut list: VecList<_> = (1..=6).collect();
+        let three = list.iter().position(|&x| x == 3).unwrap();
+        let mut cursor = list.cursor_front_mut();
+        for _ in 0..three {
+            cursor.move_next();
+        }
+        let mut rest = cursor.split_after();
+        assert_eq!(rest.iter().copied().collect::<Vec<_>>(), vec![4, 5, 6]);
+        assert_eq!(cursor.current(), Some(&mut 3));
+        cursor.move_next();
+        assert_eq!(cursor.current(), None);
+
+        cursor.move_prev();
+        cursor.move_prev();
+        cursor.splice_before(vec![7, 8].into_iter().collect());
+        cursor.splice_after(vec![9].into_iter().collect());
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            vec![1, 7, 8, 2, 9, 3]
+        );
+
+        // At the ghost, splicing before appends and after prepends:
+        let mut cursor = rest.cursor_back_mut();
+        cursor.move_next();
+        cursor.splice_before(vec![7].into_iter().collect());
+        cursor.splice_after(vec![1, 2].into_iter().collect());
+        cursor.splice_after(VecList::new());
+        assert_eq!(
+            rest.iter().copied().collect::<Vec<_>>(),
+            vec![1, 2, 4, 5, 6, 7]
+        );
+        assert_eq!(rest.back(), Some(&7));
+
+        // Splitting at the ghost takes everything:
+        let all = rest.cursor_back_mut().split_after();
+        assert_eq!(all.len(), 0);
+        let mut cursor = rest.cursor_back_mut();
+        cursor.move_next();
+        let all = cursor.split_after();
+        assert_eq!(all.len(), 6);
+        assert!(rest.is_empty());
+    }
+}
@@ -0,0 +1,225 @@
+use std::collections::VecDeque;
+
+// A tree where every node has any number of children, in order. The
+// traversals are iterators over the nodes, so that they can be
+// stopped early, and nested trees can be walked without recursion.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node<T> {
+    pub data: T,
+    pub children: Vec<Node<T>>,
+}
+
+impl<T> Node<T> {
+    pub fn new(data: T, children: Vec<Node<T>>) -> Self {
+        Node { data, children }
+    }
+
+    pub fn leaf(data: T) -> Self {
+        Node {
+            data,
+            children: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NaryTree<T> {
+    pub root: Option<Node<T>>,
+}
+
+impl<T> Default for NaryTree<T> {
+    fn default() -> Self {
+        NaryTree { root: None }
+    }
+}
+
+impl<T> NaryTree<T> {
+    pub fn new(root: Option<Node<T>>) -> Self {
+        NaryTree { root }
+    }
+
+    // The number of nodes.
+    pub fn len(&self) -> usize {
+        self.preorder().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    // The number of nodes on the longest path from the root to a leaf.
+    pub fn get_height(&self) -> usize {
+        self.levels().count()
+    }
+
+    // Each node before its children.
+    pub fn preorder(&self) -> Preorder<'_, T> {
+        Preorder {
+            stack: self.root.iter().collect(),
+        }
+    }
+
+    // Each node after its children.
+    pub fn postorder(&self) -> Postorder<'_, T> {
+        Postorder {
+            stack: self.root.iter().map(|n| (n, 0)).collect(),
+        }
+    }
+
+    // Breadth-first, from left to right.
+    pub fn level_order(&self) -> LevelOrder<'_, T> {
+        LevelOrder {
+            queue: self.root.iter().collect(),
+        }
+    }
+
+    // Breadth-first, one Vec of nodes per depth.
+    pub fn levels(&self) -> Levels<'_, T> {
+        Levels {
+            level: self.root.iter().collect(),
+        }
+    }
+}
+
+pub struct Preorder<'a, T> {
+    stack: Vec<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for Preorder<'a, T> {
+    type Item = &'a Node<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        // Pushed in reverse, so that the first child is visited first:
+        self.stack.extend(node.children.iter().rev());
+        Some(node)
+    }
+}
+
+pub struct Postorder<'a, T> {
+    // Each node, with the index of the next child to visit:
+    stack: Vec<(&'a Node<T>, usize)>,
+}
+
+impl<'a, T> Iterator for Postorder<'a, T> {
+    type Item = &'a Node<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (node, next_child) = self.stack.last_mut()?;
+            let node: &'a Node<T> = node;
+            match node.children.get(*next_child) {
+                Some(child) => {
+                    *next_child += 1;
+                    self.stack.push((child, 0));
+                }
+                None => {
+                    self.stack.pop();
+                    return Some(node);
+                }
+            }
+        }
+    }
+}
+
+pub struct LevelOrder<'a, T> {
+    queue: VecDeque<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for LevelOrder<'a, T> {
+    type Item = &'a Node<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.queue.pop_front()?;
+        self.queue.extend(node.children.iter());
+        Some(node)
+    }
+}
+
+pub struct Levels<'a, T> {
+    level: Vec<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for Levels<'a, T> {
+    type Item = Vec<&'a Node<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.level.is_empty() {
+            return None;
+        }
+        let next_level = self
+            .level
+            .iter()
+            .flat_map(|node| node.children.iter())
+            .collect();
+        Some(std::mem::replace(&mut self.level, next_level))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //        1
+    //     /  |  \
+    //    2   3   4
+    //   / \      |
+    //  5   6     7
+    //            |
+    //            8
+    fn fixture() -> NaryTree<u32> {
+        NaryTree::new(Some(Node::new(
+            1,
+            vec![
+                Node::new(2, vec![Node::leaf(5), Node::leaf(6)]),
+                Node::leaf(3),
+                Node::new(4, vec![Node::new(7, vec![Node::leaf(8)])]),
+            ],
+        )))
+    }
+
+    fn data<'a>(nodes: impl Iterator<Item = &'a Node<u32>>) -> Vec<u32> {
+        nodes.map(|n| n.data).collect()
+    }
+
+    #[test]
+    fn traversals() {
+        let tree = fixture();
+        assert_eq!(data(tree.preorder()), vec![1, 2, 5, 6, 3, 4, 7, 8]);
+        assert_eq!(data(tree.postorder()), vec![5, 6, 2, 3, 8, 7, 4, 1]);
+        assert_eq!(data(tree.level_order()), vec![1, 2, 3, 4, 5, 6, 7, 8]);
+        let levels: Vec<Vec<u32>> = tree.levels().map(|level| data(level.into_iter())).collect();
+        assert_eq!(levels, vec![vec![1], vec![2, 3, 4], vec![5, 6, 7], vec![8]]);
+    }
+
+    #[test]
+    fn size_and_height() {
+        let tree = fixture();
+        assert_eq!(tree.len(), 8);
+        assert_eq!(tree.get_height(), 4);
+        let empty = NaryTree::<u32>::default();
+        assert!(empty.is_empty());
+        assert_eq!(empty.len(), 0);
+        assert_eq!(empty.get_height(), 0);
+        assert_eq!(empty.preorder().count(), 0);
+        assert_eq!(empty.postorder().count(), 0);
+    }
+
+    #[test]
+    fn deep_tree_without_recursion() {
+        let mut node = Node::leaf(0);
+        for i in 1..10_000 {
+            node = Node::new(i, vec![node]);
+        }
+        let tree = NaryTree::new(Some(node));
+        assert_eq!(tree.postorder().next().unwrap().data, 0);
+        assert_eq!(tree.preorder().last().unwrap().data, 0);
+        assert_eq!(tree.get_height(), 10_000);
+        // Unwind the nesting before dropping, since dropping nested
+        // Vecs is recursive:
+        let mut next = tree.root;
+        while let Some(mut n) = next {
+            next = n.children.pop();
+        }
+    }
+}
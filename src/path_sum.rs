@@ -0,0 +1,389 @@
+use std::cmp;
+use std::collections::BTreeMap;
+use std::ops::Add;
+
+use crate::binary_node::BinaryNode;
+
+// Path analytics for trees of numbers. Sums only need addition, an
+// order and a zero, so any such type can be summed with the `_by`
+// functions, which take a closure that turns node data into a sum.
+pub trait Accumulator: Clone + Ord + Default + Add<Output = Self> {}
+
+impl<T> Accumulator for T where T: Clone + Ord + Default + Add<Output = T> {}
+
+// The accumulator the functions without `_by` use. Integers are summed
+// in a wider type than the node data (`i8` sums in an `i64`, `i64`
+// sums in an `i128`, ...), so that realistic trees can't overflow the
+// way an `i8` running sum does. Types that can't overflow, like
+// `BigUint`, are summed as they are.
+pub trait Widen: Clone {
+    type Wide: Accumulator;
+
+    fn widen(self) -> Self::Wide;
+}
+
+macro_rules! impl_widen {
+    ($($t:ty => $wide:ty),*) => {
+        $(
+            impl Widen for $t {
+                type Wide = $wide;
+
+                fn widen(self) -> $wide {
+                    self as $wide
+                }
+            }
+        )*
+    };
+}
+
+impl_widen!(
+    i8 => i64, i16 => i64, i32 => i64, i64 => i128, isize => i128,
+    u8 => i64, u16 => i64, u32 => i64, u64 => i128, usize => i128
+);
+
+// Counts the downward paths (from a node to one of its descendants, or
+// to itself) whose data sums to `target`.
+// time: O(n log h), space: O(h) for the running sums
+pub fn count_paths_with_sum<N>(root: Option<N>, target: <N::Data as Widen>::Wide) -> usize
+where
+    N: BinaryNode,
+    N::Data: Widen,
+{
+    count_paths_with_sum_by(root, target, |data| data.clone().widen())
+}
+
+pub fn count_paths_with_sum_by<N, W, F>(root: Option<N>, target: W, mut sum_of: F) -> usize
+where
+    N: BinaryNode,
+    W: Accumulator,
+    F: FnMut(&N::Data) -> W,
+{
+    // Keyed by what the running sum has to be for a path to end at a
+    // node, which is an ancestor's prefix sum plus `target`. That needs
+    // no subtraction, so unsigned sums work too. The empty prefix is
+    // there so that paths starting at the root are counted:
+    let mut ends = BTreeMap::new();
+    ends.insert(W::default() + target.clone(), 1);
+    count_paths_rec(root, &target, W::default(), &mut ends, &mut sum_of)
+}
+
+fn count_paths_rec<N, W, F>(
+    node: Option<N>,
+    target: &W,
+    running_sum: W,
+    ends: &mut BTreeMap<W, usize>,
+    sum_of: &mut F,
+) -> usize
+where
+    N: BinaryNode,
+    W: Accumulator,
+    F: FnMut(&N::Data) -> W,
+{
+    let n = match node {
+        Some(n) => n,
+        None => return 0,
+    };
+    let running_sum = running_sum + n.with_data(|data| sum_of(data));
+    // Every earlier prefix that's `target` less than this one starts a
+    // path ending here:
+    let mut counts = *ends.get(&running_sum).unwrap_or(&0);
+
+    let end = running_sum.clone() + target.clone();
+    *ends.entry(end.clone()).or_insert(0) += 1;
+    counts += count_paths_rec(n.left(), target, running_sum.clone(), ends, sum_of);
+    counts += count_paths_rec(n.right(), target, running_sum, ends, sum_of);
+    // Backtrack, since this prefix isn't an ancestor of other subtrees.
+    // Sums no path can end at anymore are removed, so `ends` only holds
+    // the prefixes on the current path:
+    let left = ends.get_mut(&end).unwrap();
+    *left -= 1;
+    if *left == 0 {
+        ends.remove(&end);
+    }
+    counts
+}
+
+// Like `count_paths_with_sum`, but returns the nodes of every path,
+// from the top of the path down.
+// time: O(n log h + total length of the paths)
+pub fn paths_with_sum<N>(root: Option<N>, target: <N::Data as Widen>::Wide) -> Vec<Vec<N>>
+where
+    N: BinaryNode,
+    N::Data: Widen,
+{
+    paths_with_sum_by(root, target, |data| data.clone().widen())
+}
+
+pub fn paths_with_sum_by<N, W, F>(root: Option<N>, target: W, mut sum_of: F) -> Vec<Vec<N>>
+where
+    N: BinaryNode,
+    W: Accumulator,
+    F: FnMut(&N::Data) -> W,
+{
+    let mut paths = Vec::new();
+    let mut stack = Vec::new();
+    // Depths (in `stack`) at which a path can start, keyed by the sum
+    // it has to end at, like in `count_paths_with_sum_by`. Depth 0 is
+    // the empty prefix above the root:
+    let mut ends: BTreeMap<W, Vec<usize>> = BTreeMap::new();
+    ends.insert(W::default() + target.clone(), vec![0]);
+    paths_rec(
+        root,
+        &target,
+        W::default(),
+        &mut stack,
+        &mut ends,
+        &mut paths,
+        &mut sum_of,
+    );
+    paths
+}
+
+fn paths_rec<N, W, F>(
+    node: Option<N>,
+    target: &W,
+    running_sum: W,
+    stack: &mut Vec<N>,
+    ends: &mut BTreeMap<W, Vec<usize>>,
+    paths: &mut Vec<Vec<N>>,
+    sum_of: &mut F,
+) where
+    N: BinaryNode,
+    W: Accumulator,
+    F: FnMut(&N::Data) -> W,
+{
+    let n = match node {
+        Some(n) => n,
+        None => return,
+    };
+    let running_sum = running_sum + n.with_data(|data| sum_of(data));
+    stack.push(n.clone());
+    if let Some(starts) = ends.get(&running_sum) {
+        for start in starts {
+            paths.push(stack[*start..].to_vec());
+        }
+    }
+
+    let end = running_sum.clone() + target.clone();
+    ends.entry(end.clone()).or_default().push(stack.len());
+    paths_rec(
+        n.left(),
+        target,
+        running_sum.clone(),
+        stack,
+        ends,
+        paths,
+        sum_of,
+    );
+    paths_rec(n.right(), target, running_sum, stack, ends, paths, sum_of);
+    let starts = ends.get_mut(&end).unwrap();
+    starts.pop();
+    if starts.is_empty() {
+        ends.remove(&end);
+    }
+    stack.pop();
+}
+
+// The largest sum of any path between two nodes, which doesn't need to
+// go through the root, or go downwards. Returns None for an empty tree.
+pub fn max_path_sum<N>(root: Option<N>) -> Option<<N::Data as Widen>::Wide>
+where
+    N: BinaryNode,
+    N::Data: Widen,
+{
+    max_path_sum_by(root, |data| data.clone().widen())
+}
+
+pub fn max_path_sum_by<N, W, F>(root: Option<N>, mut sum_of: F) -> Option<W>
+where
+    N: BinaryNode,
+    W: Accumulator,
+    F: FnMut(&N::Data) -> W,
+{
+    let mut best = None;
+    max_path_sum_rec(root, &mut best, &mut sum_of);
+    best
+}
+
+// Returns the largest sum of a downward path starting at `node`, and
+// updates `best` with the largest path that bends at `node`.
+fn max_path_sum_rec<N, W, F>(node: Option<N>, best: &mut Option<W>, sum_of: &mut F) -> Option<W>
+where
+    N: BinaryNode,
+    W: Accumulator,
+    F: FnMut(&N::Data) -> W,
+{
+    let n = node?;
+    let data = n.with_data(|data| sum_of(data));
+    let zero = W::default();
+    // Negative branches are better left off the path:
+    let left = cmp::max(
+        max_path_sum_rec(n.left(), best, sum_of).unwrap_or_else(W::default),
+        zero.clone(),
+    );
+    let right = cmp::max(
+        max_path_sum_rec(n.right(), best, sum_of).unwrap_or_else(W::default),
+        zero,
+    );
+
+    let through_node = data.clone() + left.clone() + right.clone();
+    *best = Some(match best.take() {
+        Some(b) => cmp::max(b, through_node),
+        None => through_node,
+    });
+    Some(data + cmp::max(left, right))
+}
+
+// The number of edges on the longest path between any two nodes.
+// Returns None for an empty tree.
+pub fn diameter<N>(root: Option<N>) -> Option<usize>
+where
+    N: BinaryNode,
+{
+    let root = root?;
+    let mut best = 0;
+    height_rec(Some(root), &mut best);
+    Some(best)
+}
+
+// Returns the height of the subtree (in nodes), and updates `best`
+// with the longest path that bends at `node`.
+fn height_rec<N: BinaryNode>(node: Option<N>, best: &mut usize) -> usize {
+    match node {
+        Some(n) => {
+            let left = height_rec(n.left(), best);
+            let right = height_rec(n.right(), best);
+            *best = cmp::max(*best, left + right);
+            1 + cmp::max(left, right)
+        }
+        None => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binary_node::TreeRoot;
+    use crate::binary_tree::{BinaryTree, Node};
+    use crate::binary_tree_refcell::BinaryTree as RCBinaryTree;
+    use crate::tree_serialize::{Format, SerializeTree};
+
+    fn fixture() -> BinaryTree<i8> {
+        // Same fixture as ch4-p12_paths-with-sum:
+        BinaryTree::new(Node::new(
+            10,
+            Node::new(
+                5,
+                Node::new(3, Node::new(3, None, None), Node::new(-2, None, None)),
+                Node::new(1, None, Node::new(2, None, None)),
+            ),
+            Node::new(-3, None, Node::new(11, None, None)),
+        ))
+    }
+
+    #[test]
+    fn count_paths() {
+        let bt = fixture();
+        assert_eq!(count_paths_with_sum(bt.root_node(), 8), 3);
+        assert_eq!(count_paths_with_sum(bt.root_node(), 18), 3);
+        assert_eq!(count_paths_with_sum(bt.root_node(), 100), 0);
+        let empty = BinaryTree::<i8>::new(None);
+        assert_eq!(count_paths_with_sum(empty.root_node(), 0), 0);
+    }
+
+    #[test]
+    fn count_paths_no_overflow() {
+        // Every path sum here is outside of the i8 range:
+        let bt =
+            BinaryTree::<i8>::deserialize("[120,120,-100,120,null,null,120]", Format::LevelOrder)
+                .unwrap();
+        assert_eq!(count_paths_with_sum(bt.root_node(), 240), 2);
+        assert_eq!(count_paths_with_sum(bt.root_node(), 360), 1);
+        assert_eq!(count_paths_with_sum(bt.root_node(), 140), 1);
+
+        let big = u64::MAX;
+        let bt = BinaryTree::new(Node::new(big, Node::new(big, None, None), None));
+        assert_eq!(count_paths_with_sum(bt.root_node(), 2 * i128::from(big)), 1);
+        assert_eq!(max_path_sum(bt.root_node()), Some(2 * i128::from(big)));
+    }
+
+    #[test]
+    fn sums_of_any_type() {
+        use std::time::Duration;
+
+        // Any type with addition, an order and a zero can be summed:
+        let secs = |s| Duration::from_secs(s);
+        let bt = BinaryTree::new(Node::new(
+            secs(1),
+            Node::new(secs(2), Node::new(secs(1), None, None), None),
+            Node::new(secs(3), None, None),
+        ));
+        assert_eq!(count_paths_with_sum_by(bt.root_node(), secs(3), |d| *d), 3);
+        assert_eq!(paths_with_sum_by(bt.root_node(), secs(4), |d| *d).len(), 2);
+        assert_eq!(max_path_sum_by(bt.root_node(), |d| *d), Some(secs(7)));
+
+        // The closure can sum something other than the data itself:
+        let bt = BinaryTree::<&str>::new(Node::new(
+            "ab",
+            Node::new("c", None, None),
+            Node::new("def", None, None),
+        ));
+        assert_eq!(count_paths_with_sum_by(bt.root_node(), 3, |s| s.len()), 2);
+    }
+
+    #[test]
+    fn enumerate_paths() {
+        let bt = fixture();
+        let mut paths: Vec<Vec<i8>> = paths_with_sum(bt.root_node(), 8)
+            .iter()
+            .map(|path| path.iter().map(|n| n.data).collect())
+            .collect();
+        paths.sort();
+        assert_eq!(paths, vec![vec![-3, 11], vec![5, 1, 2], vec![5, 3]]);
+
+        // Zero-sum paths can overlap:
+        let bt = BinaryTree::<i32>::deserialize("[0,0,null,0]", Format::LevelOrder).unwrap();
+        assert_eq!(paths_with_sum(bt.root_node(), 0).len(), 6);
+        assert_eq!(count_paths_with_sum(bt.root_node(), 0), 6);
+    }
+
+    #[test]
+    fn enumerate_paths_refcell() {
+        let bt = RCBinaryTree::<i32>::deserialize("[1,-1,2,1]", Format::LevelOrder).unwrap();
+        let paths = paths_with_sum(bt.root_node(), 1);
+        let data: Vec<Vec<i32>> = paths
+            .iter()
+            .map(|path| path.iter().map(|n| n.borrow().data).collect())
+            .collect();
+        assert_eq!(data, vec![vec![1], vec![1, -1, 1], vec![1]]);
+    }
+
+    #[test]
+    fn max_sum() {
+        let bt = BinaryTree::<i32>::deserialize("[-10,9,20,null,null,15,7]", Format::LevelOrder)
+            .unwrap();
+        assert_eq!(max_path_sum(bt.root_node()), Some(42));
+        let bt = BinaryTree::<i32>::deserialize("[-3,-2,-5]", Format::LevelOrder).unwrap();
+        assert_eq!(max_path_sum(bt.root_node()), Some(-2));
+        assert_eq!(max_path_sum(fixture().root_node()), Some(29));
+        let empty = BinaryTree::<i32>::new(None);
+        assert_eq!(max_path_sum(empty.root_node()), None);
+    }
+
+    #[test]
+    fn tree_diameter() {
+        let bt = BinaryTree::<i32>::deserialize("[1,2,3,4,5]", Format::LevelOrder).unwrap();
+        assert_eq!(diameter(bt.root_node()), Some(3));
+        // The longest path doesn't need to go through the root:
+        let bt = BinaryTree::<i32>::deserialize(
+            "[1,2,null,3,4,5,null,null,6,7,null,null,8]",
+            Format::LevelOrder,
+        )
+        .unwrap();
+        assert_eq!(diameter(bt.root_node()), Some(6));
+        let bt = BinaryTree::<i32>::deserialize("[1]", Format::LevelOrder).unwrap();
+        assert_eq!(diameter(bt.root_node()), Some(0));
+        let empty = BinaryTree::<i32>::new(None);
+        assert_eq!(diameter(empty.root_node()), None);
+    }
+}
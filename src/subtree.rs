@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::binary_node::BinaryNode;
+
+// Subtree matching in O(n + m) time, instead of comparing the pattern
+// against every node of the tree.
+//
+// Every distinct subtree gets an id, Merkle-style: a node's id is
+// determined by its data and the ids of its two children. Two
+// subtrees are identical exactly when their ids are equal. Unlike
+// hashing the subtrees down to a u64, interning the (data, left,
+// right) triples can't produce false positives from collisions.
+
+type SubtreeId = usize;
+
+struct Interner<T> {
+    ids: HashMap<(T, Option<SubtreeId>, Option<SubtreeId>), SubtreeId>,
+}
+
+impl<T> Interner<T>
+where
+    T: Hash + Eq + Clone,
+{
+    fn new() -> Self {
+        Interner {
+            ids: HashMap::new(),
+        }
+    }
+
+    // Assigns ids to every subtree below `node`, and records each node
+    // with its id in post-order. Returns the id of `node`, where the
+    // empty tree has no id.
+    fn intern<N>(&mut self, node: Option<N>, nodes: &mut Vec<(N, SubtreeId)>) -> Option<SubtreeId>
+    where
+        N: BinaryNode<Data = T>,
+    {
+        let n = node?;
+        let left = self.intern(n.left(), nodes);
+        let right = self.intern(n.right(), nodes);
+        let key = (n.with_data(T::clone), left, right);
+        let next_id = self.ids.len();
+        let id = *self.ids.entry(key).or_insert(next_id);
+        nodes.push((n, id));
+        Some(id)
+    }
+
+    // Like `intern`, but without adding new ids. Returns Err if some
+    // part of the subtree has never been seen, which means that the
+    // subtree can't appear anywhere in the interned trees.
+    fn lookup<N>(&self, node: Option<N>) -> Result<Option<SubtreeId>, ()>
+    where
+        N: BinaryNode<Data = T>,
+    {
+        let n = match node {
+            Some(n) => n,
+            None => return Ok(None),
+        };
+        let left = self.lookup(n.left())?;
+        let right = self.lookup(n.right())?;
+        let key = n.with_data(|data| (data.clone(), left, right));
+        self.ids.get(&key).map(|id| Some(*id)).ok_or(())
+    }
+}
+
+// Returns true if `pattern` is identical to the subtree of some node
+// in `tree`. The empty tree is a subtree of every tree.
+pub fn is_subtree<N, M>(tree: Option<N>, pattern: Option<M>) -> bool
+where
+    N: BinaryNode,
+    M: BinaryNode<Data = N::Data>,
+    N::Data: Hash + Eq + Clone,
+{
+    if pattern.is_none() {
+        return true;
+    }
+    !find_subtrees(tree, pattern).is_empty()
+}
+
+// Returns every node of `tree` whose subtree is identical to
+// `pattern`, from left to right. Matches can't overlap, since a tree
+// can't contain a copy of itself.
+pub fn find_subtrees<N, M>(tree: Option<N>, pattern: Option<M>) -> Vec<N>
+where
+    N: BinaryNode,
+    M: BinaryNode<Data = N::Data>,
+    N::Data: Hash + Eq + Clone,
+{
+    let mut interner = Interner::new();
+    let mut nodes = Vec::new();
+    interner.intern(tree, &mut nodes);
+    match interner.lookup(pattern) {
+        Ok(Some(pattern_id)) => nodes
+            .into_iter()
+            .filter(|(_, id)| *id == pattern_id)
+            .map(|(node, _)| node)
+            .collect(),
+        // The empty pattern has no root node to return, and an unseen
+        // pattern doesn't match anything:
+        Ok(None) | Err(()) => Vec::new(),
+    }
+}
+
+// Groups the nodes of `tree` whose subtrees are identical to each
+// other. Only groups with at least two nodes are returned, ordered by
+// where their first node appears in a post-order walk.
+pub fn find_duplicate_subtrees<N>(tree: Option<N>) -> Vec<Vec<N>>
+where
+    N: BinaryNode,
+    N::Data: Hash + Eq + Clone,
+{
+    let mut interner = Interner::new();
+    let mut nodes = Vec::new();
+    interner.intern(tree, &mut nodes);
+
+    // Ids are handed out in post-order, so grouping by id keeps the
+    // groups in order of their first appearance:
+    let mut groups: Vec<Vec<N>> = (0..interner.ids.len()).map(|_| Vec::new()).collect();
+    for (node, id) in nodes {
+        groups[id].push(node);
+    }
+    groups.into_iter().filter(|group| group.len() > 1).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binary_node::TreeRoot;
+    use crate::binary_search_tree::BinarySearchTree;
+    use crate::binary_tree::{BinaryTree, Node};
+    use crate::binary_tree_refcell::BinaryTree as RCBinaryTree;
+    use crate::tree_serialize::{Format, SerializeTree};
+    use std::rc::Rc;
+
+    fn level_order(s: &str) -> BinaryTree<i32> {
+        BinaryTree::deserialize(s, Format::LevelOrder).unwrap()
+    }
+
+    #[test]
+    fn subtree_simple() {
+        let bt = level_order("[1,2,1,null,null,1]");
+        let sub = level_order("[1,1]");
+        let not_sub = level_order("[1,1,1]");
+        assert!(is_subtree(bt.root_node(), sub.root_node()));
+        assert!(!is_subtree(bt.root_node(), not_sub.root_node()));
+        assert!(is_subtree(sub.root_node(), sub.root_node()));
+    }
+
+    #[test]
+    fn subtree_must_match_to_the_leaves() {
+        // [4,1,2] appears at the top of the right subtree, but the
+        // tree continues below it:
+        let bt = level_order("[3,4,5,1,2,null,null,null,null,0]");
+        let sub = level_order("[4,1,2]");
+        assert!(!is_subtree(bt.root_node(), sub.root_node()));
+        assert!(is_subtree(bt.root_node(), level_order("[2,0]").root_node()));
+    }
+
+    #[test]
+    fn subtree_empty() {
+        let bt = level_order("[1,2]");
+        let empty = level_order("[]");
+        assert!(is_subtree(bt.root_node(), empty.root_node()));
+        assert!(is_subtree(empty.root_node(), empty.root_node()));
+        assert!(!is_subtree(empty.root_node(), bt.root_node()));
+        assert!(find_subtrees(bt.root_node(), empty.root_node()).is_empty());
+    }
+
+    #[test]
+    fn subtree_across_tree_types() {
+        let mut bst = BinarySearchTree::new();
+        for i in [5, 3, 8, 1, 4].iter() {
+            bst.add(*i);
+        }
+        let rcbt = RCBinaryTree::<i32>::deserialize("[3,1,4]", Format::LevelOrder).unwrap();
+        assert!(is_subtree(bst.root_node(), rcbt.root_node()));
+    }
+
+    #[test]
+    fn find_all_matches() {
+        let bt = level_order("[1,2,3,4,null,2,4,null,null,4]");
+        let sub = level_order("[2,4]");
+        let matches = find_subtrees(bt.root_node(), sub.root_node());
+        assert_eq!(matches.len(), 2);
+        let root = bt.root.as_deref().unwrap();
+        assert!(std::ptr::eq(matches[0], root.left.as_deref().unwrap()));
+        let right_left = root.right.as_ref().unwrap().left.as_deref().unwrap();
+        assert!(std::ptr::eq(matches[1], right_left));
+
+        let leaves = find_subtrees(bt.root_node(), level_order("[4]").root_node());
+        assert_eq!(leaves.len(), 3);
+        assert!(leaves.iter().all(|n| n.data == 4 && n.left.is_none()));
+    }
+
+    #[test]
+    fn find_matches_refcell() {
+        let rcbt =
+            RCBinaryTree::<i32>::deserialize("[1,2,2,3,null,3]", Format::LevelOrder).unwrap();
+        let matches = find_subtrees(rcbt.root_node(), level_order("[2,3]").root_node());
+        let root = rcbt.root.clone().unwrap();
+        assert_eq!(matches.len(), 2);
+        assert!(Rc::ptr_eq(
+            &matches[0],
+            root.borrow().left.as_ref().unwrap()
+        ));
+        assert!(Rc::ptr_eq(
+            &matches[1],
+            root.borrow().right.as_ref().unwrap()
+        ));
+    }
+
+    #[test]
+    fn duplicates() {
+        let bt = level_order("[1,2,3,4,null,2,4,null,null,4]");
+        let groups = find_duplicate_subtrees(bt.root_node());
+        let summary: Vec<(i32, usize)> = groups.iter().map(|g| (g[0].data, g.len())).collect();
+        assert_eq!(summary, vec![(4, 3), (2, 2)]);
+        for group in groups.iter() {
+            let first = BinaryTree::new(Some(Box::new((*group[0]).clone())));
+            for node in group.iter() {
+                let other = BinaryTree::new(Some(Box::new((*node).clone())));
+                assert_eq!(
+                    first.serialize(Format::Preorder),
+                    other.serialize(Format::Preorder)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn duplicates_are_exact() {
+        // Same data, different shapes:
+        let bt = BinaryTree::new(Node::new(
+            0,
+            Node::new(1, Node::new(2, None, None), None),
+            Node::new(1, None, Node::new(2, None, None)),
+        ));
+        let groups = find_duplicate_subtrees(bt.root_node());
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].len(), 2);
+        assert_eq!(groups[0][0].data, 2);
+        assert!(find_duplicate_subtrees(level_order("[]").root_node()).is_empty());
+    }
+}
@@ -0,0 +1,406 @@
+use std::fmt;
+use std::fmt::Display;
+
+use crate::binary_node::{BinaryNode, TreeRoot};
+
+// Renders any of the binary trees as a box-drawing diagram for the
+// terminal, or as DOT for Graphviz. This is much easier to read than
+// the nested `Debug` output of `Node`:
+//
+// 5 [h=3, size=5]
+// ├── 3 [h=2, size=3]
+// │   ├── ∅
+// │   └── 4 [h=1, size=1]
+// └── 9 [h=1, size=1]
+//
+// When a node has only one child, the missing child is drawn as `∅`,
+// so that left and right children can be told apart.
+pub struct TreeDisplay<N> {
+    root: Option<N>,
+    show_heights: bool,
+    show_sizes: bool,
+    show_parents: bool,
+}
+
+const EMPTY: &str = "∅";
+
+// A snapshot of one node, numbered in preorder:
+struct Row {
+    label: String,
+    left: Option<usize>,
+    right: Option<usize>,
+    height: usize,
+    size: usize,
+    // The data of the node that the parent link points to, if this
+    // tree type has parent links:
+    parent: Option<String>,
+    // Whether the parent link points to the node that actually has
+    // this node as a child:
+    parent_ok: bool,
+}
+
+impl<N> TreeDisplay<N>
+where
+    N: BinaryNode,
+    N::Data: Display,
+{
+    pub fn new<'a, B>(tree: &'a B) -> Self
+    where
+        B: TreeRoot<'a, Node = N>,
+    {
+        Self::from_node(tree.root_node())
+    }
+
+    pub fn from_node(root: Option<N>) -> Self {
+        TreeDisplay {
+            root,
+            show_heights: false,
+            show_sizes: false,
+            show_parents: false,
+        }
+    }
+
+    // Using the builder pattern to allow for optional arguments.
+    pub fn show_heights(mut self, show_heights: bool) -> Self {
+        self.show_heights = show_heights;
+        self
+    }
+
+    // Using the builder pattern to allow for optional arguments.
+    pub fn show_sizes(mut self, show_sizes: bool) -> Self {
+        self.show_sizes = show_sizes;
+        self
+    }
+
+    // Parent links are only shown for trees that have them. A link
+    // that doesn't point back to the node's actual parent is flagged
+    // with `(!)` in the diagram, and drawn in red in DOT.
+    pub fn show_parents(mut self, show_parents: bool) -> Self {
+        self.show_parents = show_parents;
+        self
+    }
+
+    pub fn to_ascii(&self) -> String {
+        let rows = self.collect_rows();
+        if rows.is_empty() {
+            return format!("{}\n", EMPTY);
+        }
+        let mut out = String::new();
+        out.push_str(&self.annotated_label(&rows[0]));
+        out.push('\n');
+        self.write_ascii_children(&rows, 0, "", &mut out);
+        out
+    }
+
+    pub fn to_dot(&self) -> String {
+        let rows = self.collect_rows();
+        let mut out = String::from("digraph tree {\n");
+        for (i, row) in rows.iter().enumerate() {
+            let mut label = escape_dot(&row.label);
+            if let Some(notes) = self.notes(row) {
+                label = format!("{}\\n[{}]", label, escape_dot(&notes));
+            }
+            out.push_str(&format!("    n{} [label=\"{}\"];\n", i, label));
+        }
+        for (i, row) in rows.iter().enumerate() {
+            if row.left.is_none() && row.right.is_none() {
+                continue;
+            }
+            for (side, child) in [("L", row.left), ("R", row.right)].iter() {
+                match child {
+                    Some(c) => {
+                        out.push_str(&format!("    n{} -> n{} [label=\"{}\"];\n", i, c, side))
+                    }
+                    None => {
+                        // Keep the single child on its own side:
+                        out.push_str(&format!("    null{}{} [shape=point];\n", i, side));
+                        out.push_str(&format!("    n{} -> null{}{};\n", i, i, side));
+                    }
+                }
+            }
+        }
+        if self.show_parents && N::HAS_PARENT_LINKS {
+            for (i, row) in rows.iter().enumerate() {
+                let actual_parent = rows
+                    .iter()
+                    .position(|p| p.left == Some(i) || p.right == Some(i));
+                if row.parent_ok {
+                    if let Some(p) = actual_parent {
+                        out.push_str(&format!(
+                            "    n{} -> n{} [style=dashed, color=gray, constraint=false];\n",
+                            i, p
+                        ));
+                    }
+                } else {
+                    // The link doesn't point to the actual parent, so
+                    // draw it as a separate, red node:
+                    let label = escape_dot(row.parent.as_deref().unwrap_or(EMPTY));
+                    out.push_str(&format!(
+                        "    parent{} [label=\"{}\", color=red];\n",
+                        i, label
+                    ));
+                    out.push_str(&format!(
+                        "    n{} -> parent{} [style=dashed, color=red, constraint=false];\n",
+                        i, i
+                    ));
+                }
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    fn collect_rows(&self) -> Vec<Row> {
+        let mut rows = Vec::new();
+        if let Some(root) = &self.root {
+            collect(root, None, &mut rows);
+        }
+        rows
+    }
+
+    fn annotated_label(&self, row: &Row) -> String {
+        match self.notes(row) {
+            Some(notes) => format!("{} [{}]", row.label, notes),
+            None => row.label.clone(),
+        }
+    }
+
+    fn notes(&self, row: &Row) -> Option<String> {
+        let mut notes = Vec::new();
+        if self.show_heights {
+            notes.push(format!("h={}", row.height));
+        }
+        if self.show_sizes {
+            notes.push(format!("size={}", row.size));
+        }
+        if self.show_parents && N::HAS_PARENT_LINKS {
+            let parent = row.parent.as_deref().unwrap_or(EMPTY);
+            if row.parent_ok {
+                notes.push(format!("parent={}", parent));
+            } else {
+                notes.push(format!("parent={} (!)", parent));
+            }
+        }
+        if notes.is_empty() {
+            None
+        } else {
+            Some(notes.join(", "))
+        }
+    }
+
+    fn write_ascii_children(&self, rows: &[Row], index: usize, prefix: &str, out: &mut String) {
+        let row = &rows[index];
+        if row.left.is_none() && row.right.is_none() {
+            return;
+        }
+        for (child, is_last) in [(row.left, false), (row.right, true)].iter() {
+            let (connector, indent) = if *is_last {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+            out.push_str(prefix);
+            out.push_str(connector);
+            match child {
+                Some(c) => {
+                    out.push_str(&self.annotated_label(&rows[*c]));
+                    out.push('\n');
+                    self.write_ascii_children(rows, *c, &format!("{}{}", prefix, indent), out);
+                }
+                None => {
+                    out.push_str(EMPTY);
+                    out.push('\n');
+                }
+            }
+        }
+    }
+}
+
+// Walks the subtree in preorder, and returns the index of its root row.
+fn collect<N>(node: &N, actual_parent: Option<&N>, rows: &mut Vec<Row>) -> usize
+where
+    N: BinaryNode,
+    N::Data: Display,
+{
+    let index = rows.len();
+    let (parent, parent_ok) = if N::HAS_PARENT_LINKS {
+        let parent = node.parent();
+        let parent_ok = match (&parent, actual_parent) {
+            (Some(p), Some(actual)) => p.same_node(actual),
+            (None, None) => true,
+            _ => false,
+        };
+        (
+            parent.map(|p| p.with_data(|data| data.to_string())),
+            parent_ok,
+        )
+    } else {
+        (None, true)
+    };
+    rows.push(Row {
+        label: node.with_data(|data| data.to_string()),
+        left: None,
+        right: None,
+        height: 1,
+        size: 1,
+        parent,
+        parent_ok,
+    });
+
+    let left = node.left().map(|l| collect(&l, Some(node), rows));
+    let right = node.right().map(|r| collect(&r, Some(node), rows));
+    let mut height = 0;
+    let mut size = 1;
+    for child in left.iter().chain(right.iter()) {
+        height = std::cmp::max(height, rows[*child].height);
+        size += rows[*child].size;
+    }
+    let row = &mut rows[index];
+    row.left = left;
+    row.right = right;
+    row.height = height + 1;
+    row.size = size;
+    index
+}
+
+fn escape_dot(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl<N> Display for TreeDisplay<N>
+where
+    N: BinaryNode,
+    N::Data: Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_ascii())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binary_search_tree::BinarySearchTree;
+    use crate::binary_tree::{BinaryTree, Node};
+    use crate::binary_tree_arena::ArenaTree;
+    use crate::binary_tree_refcell::{BinaryTree as RCBinaryTree, Node as RCNode};
+    use std::rc::Rc;
+
+    fn bst_fixture() -> BinarySearchTree<u32> {
+        let mut bst = BinarySearchTree::new();
+        bst.add(5);
+        bst.add(3);
+        bst.add(9);
+        bst.add(4);
+        bst.add(1);
+        bst
+    }
+
+    #[test]
+    fn ascii_plain() {
+        let bst = bst_fixture();
+        assert_eq!(
+            TreeDisplay::new(&bst).to_ascii(),
+            "5\n\
+             ├── 3\n\
+             │   ├── 1\n\
+             │   └── 4\n\
+             └── 9\n"
+        );
+    }
+
+    #[test]
+    fn ascii_missing_child() {
+        let bt = BinaryTree::new(Node::new(
+            1,
+            None,
+            Node::new(2, Node::new(3, None, None), None),
+        ));
+        assert_eq!(
+            format!("{}", TreeDisplay::new(&bt)),
+            "1\n\
+             ├── ∅\n\
+             └── 2\n\
+             \x20   ├── 3\n\
+             \x20   └── ∅\n"
+        );
+    }
+
+    #[test]
+    fn ascii_empty() {
+        let bt = BinaryTree::<u32>::new(None);
+        assert_eq!(TreeDisplay::new(&bt).to_ascii(), "∅\n");
+    }
+
+    #[test]
+    fn ascii_heights_and_sizes() {
+        let bst = bst_fixture();
+        let display = TreeDisplay::new(&bst).show_heights(true).show_sizes(true);
+        assert_eq!(
+            display.to_ascii(),
+            "5 [h=3, size=5]\n\
+             ├── 3 [h=2, size=3]\n\
+             │   ├── 1 [h=1, size=1]\n\
+             │   └── 4 [h=1, size=1]\n\
+             └── 9 [h=1, size=1]\n"
+        );
+    }
+
+    #[test]
+    fn ascii_parents() {
+        let leaf = RCNode::new(3, None, None);
+        let bt = RCBinaryTree::new(RCNode::new(1, RCNode::new(2, leaf.clone(), None), None));
+        let display = TreeDisplay::new(&bt).show_parents(true);
+        assert_eq!(
+            display.to_ascii(),
+            "1 [parent=∅]\n\
+             ├── 2 [parent=1]\n\
+             │   ├── 3 [parent=2]\n\
+             │   └── ∅\n\
+             └── ∅\n"
+        );
+
+        // Point the leaf's parent link at the root, which is wrong:
+        let root = bt.root.clone().unwrap();
+        leaf.as_ref().unwrap().borrow_mut().parent = Some(Rc::downgrade(&root));
+        assert!(display.to_ascii().contains("3 [parent=1 (!)]"));
+        assert!(display.to_dot().contains("color=red"));
+
+        // Boxed trees don't have parent links to show:
+        let bst = bst_fixture();
+        assert!(!TreeDisplay::new(&bst)
+            .show_parents(true)
+            .to_ascii()
+            .contains("parent"));
+    }
+
+    #[test]
+    fn ascii_arena_tree() {
+        let mut t = ArenaTree::new();
+        t.add(2);
+        t.add(1);
+        t.add(3);
+        let display = TreeDisplay::new(&t).show_parents(true);
+        assert_eq!(
+            display.to_ascii(),
+            "2 [parent=∅]\n\
+             ├── 1 [parent=2]\n\
+             └── 3 [parent=2]\n"
+        );
+    }
+
+    #[test]
+    fn dot() {
+        let bt = BinaryTree::new(Node::new("a", Node::new("\"b\"", None, None), None));
+        assert_eq!(
+            TreeDisplay::new(&bt).show_sizes(true).to_dot(),
+            "digraph tree {\n    \
+             n0 [label=\"a\\n[size=2]\"];\n    \
+             n1 [label=\"\\\"b\\\"\\n[size=1]\"];\n    \
+             n0 -> n1 [label=\"L\"];\n    \
+             null0R [shape=point];\n    \
+             n0 -> null0R;\n\
+             }\n"
+        );
+    }
+}
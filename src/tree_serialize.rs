@@ -0,0 +1,427 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::binary_node::{BinaryNode, BuildBinaryNode};
+use crate::binary_search_tree::{BinarySearchTree, Tree as BSTTree};
+use crate::binary_search_tree_refcell::{
+    BinarySearchTree as RCBinarySearchTree, Tree as RCBSTTree,
+};
+use crate::binary_tree::{BinaryTree, Tree as BTTree};
+use crate::binary_tree_refcell::{BinaryTree as RCBinaryTree, Tree as RCBTTree};
+
+// Round-trippable string formats for the binary trees, mostly so that
+// test fixtures can be written as strings instead of nested
+// `Node::new` calls.
+//
+// LevelOrder is the LeetCode format: a breadth-first listing, where
+// only the children of non-null nodes are listed, and trailing nulls
+// are dropped. The tree 1 -> (2 -> (3, _), _) is `[1,2,null,3]`.
+//
+// Preorder lists every node before its children, and marks every
+// missing child with `#`. The same tree is `1,2,3,#,#,#,#`.
+//
+// Values are written with `Display` and read back with `FromStr`, so
+// values whose string form contains a comma won't round-trip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    LevelOrder,
+    Preorder,
+}
+
+const NULL: &str = "null";
+const SENTINEL: &str = "#";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseTreeError {
+    // LevelOrder input must be wrapped in `[` and `]`:
+    MissingBrackets,
+    // A token that isn't a null marker, and couldn't be parsed as a value:
+    InvalidValue(String),
+    // Preorder input ended before every node had both of its children:
+    UnexpectedEnd,
+    // Tokens left over once the tree is complete:
+    TrailingInput(String),
+}
+
+impl Display for ParseTreeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseTreeError::MissingBrackets => write!(f, "level-order input must be wrapped in []"),
+            ParseTreeError::InvalidValue(token) => write!(f, "invalid value: {:?}", token),
+            ParseTreeError::UnexpectedEnd => write!(f, "input ended before the tree was complete"),
+            ParseTreeError::TrailingInput(token) => {
+                write!(
+                    f,
+                    "unexpected input after the tree was complete: {:?}",
+                    token
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseTreeError {}
+
+pub trait SerializeTree: Sized {
+    fn serialize(&self, format: Format) -> String;
+    fn deserialize(s: &str, format: Format) -> Result<Self, ParseTreeError>;
+}
+
+pub fn serialize_nodes<N>(root: Option<N>, format: Format) -> String
+where
+    N: BinaryNode,
+    N::Data: Display,
+{
+    match format {
+        Format::LevelOrder => to_level_order(root),
+        Format::Preorder => {
+            let mut tokens = Vec::new();
+            to_preorder(root, &mut tokens);
+            tokens.join(",")
+        }
+    }
+}
+
+// Returns the root of the parsed tree, along with its number of nodes.
+pub fn deserialize_nodes<B>(s: &str, format: Format) -> Result<(B, u64), ParseTreeError>
+where
+    B: BuildBinaryNode,
+    B::Data: FromStr,
+{
+    match format {
+        Format::LevelOrder => from_level_order(s),
+        Format::Preorder => {
+            let mut length = 0;
+            let mut tokens = s.split(',').map(str::trim).filter(|t| !t.is_empty());
+            let root = from_preorder(&mut tokens, &mut length)?;
+            if let Some(token) = tokens.next() {
+                return Err(ParseTreeError::TrailingInput(token.to_string()));
+            }
+            Ok((root, length))
+        }
+    }
+}
+
+fn to_level_order<N>(root: Option<N>) -> String
+where
+    N: BinaryNode,
+    N::Data: Display,
+{
+    let mut tokens = Vec::new();
+    let mut queue = VecDeque::new();
+    queue.push_back(root);
+    while let Some(node) = queue.pop_front() {
+        if let Some(n) = node {
+            tokens.push(n.with_data(|data| data.to_string()));
+            queue.push_back(n.left());
+            queue.push_back(n.right());
+        } else {
+            tokens.push(NULL.to_string());
+        }
+    }
+    while tokens.last().map(String::as_str) == Some(NULL) {
+        tokens.pop();
+    }
+    format!("[{}]", tokens.join(","))
+}
+
+fn to_preorder<N>(node: Option<N>, tokens: &mut Vec<String>)
+where
+    N: BinaryNode,
+    N::Data: Display,
+{
+    if let Some(n) = node {
+        tokens.push(n.with_data(|data| data.to_string()));
+        to_preorder(n.left(), tokens);
+        to_preorder(n.right(), tokens);
+    } else {
+        tokens.push(SENTINEL.to_string());
+    }
+}
+
+fn parse_value<T: FromStr>(token: &str) -> Result<T, ParseTreeError> {
+    token
+        .parse()
+        .map_err(|_| ParseTreeError::InvalidValue(token.to_string()))
+}
+
+fn from_level_order<B>(s: &str) -> Result<(B, u64), ParseTreeError>
+where
+    B: BuildBinaryNode,
+    B::Data: FromStr,
+{
+    let s = s.trim();
+    if !s.starts_with('[') || !s.ends_with(']') || s.len() < 2 {
+        return Err(ParseTreeError::MissingBrackets);
+    }
+    let inner = s[1..s.len() - 1].trim();
+    if inner.is_empty() {
+        return Ok((B::empty(), 0));
+    }
+
+    let mut values: Vec<Option<B::Data>> = Vec::new();
+    for token in inner.split(',').map(str::trim) {
+        if token == NULL {
+            values.push(None);
+        } else {
+            values.push(Some(parse_value(token)?));
+        }
+    }
+
+    // Assign each listed value to a child slot of an earlier non-null
+    // node, in breadth-first order:
+    let mut children: Vec<(Option<usize>, Option<usize>)> = vec![(None, None); values.len()];
+    let mut parents: VecDeque<usize> = VecDeque::new();
+    if values[0].is_some() {
+        parents.push_back(0);
+    }
+    let mut next = 1;
+    while next < values.len() {
+        let parent = match parents.pop_front() {
+            Some(parent) => parent,
+            None => {
+                let token = inner.split(',').nth(next).unwrap_or("").trim();
+                return Err(ParseTreeError::TrailingInput(token.to_string()));
+            }
+        };
+        for is_left in &[true, false] {
+            if next >= values.len() {
+                break;
+            }
+            if values[next].is_some() {
+                if *is_left {
+                    children[parent].0 = Some(next);
+                } else {
+                    children[parent].1 = Some(next);
+                }
+                parents.push_back(next);
+            }
+            next += 1;
+        }
+    }
+
+    let length = values.iter().filter(|v| v.is_some()).count() as u64;
+    if values[0].is_none() {
+        return Ok((B::empty(), 0));
+    }
+    Ok((build_level_order(0, &mut values, &children), length))
+}
+
+fn build_level_order<B: BuildBinaryNode>(
+    index: usize,
+    values: &mut Vec<Option<B::Data>>,
+    children: &[(Option<usize>, Option<usize>)],
+) -> B {
+    let (left, right) = children[index];
+    let left = left.map_or_else(B::empty, |i| build_level_order(i, values, children));
+    let right = right.map_or_else(B::empty, |i| build_level_order(i, values, children));
+    let data = values[index]
+        .take()
+        .expect("build_level_order: invariant violated, node was built twice");
+    B::build(data, left, right)
+}
+
+fn from_preorder<'a, B, I>(tokens: &mut I, length: &mut u64) -> Result<B, ParseTreeError>
+where
+    B: BuildBinaryNode,
+    B::Data: FromStr,
+    I: Iterator<Item = &'a str>,
+{
+    match tokens.next() {
+        None => Err(ParseTreeError::UnexpectedEnd),
+        Some(SENTINEL) => Ok(B::empty()),
+        Some(token) => {
+            let data = parse_value(token)?;
+            *length += 1;
+            let left = from_preorder(tokens, length)?;
+            let right = from_preorder(tokens, length)?;
+            Ok(B::build(data, left, right))
+        }
+    }
+}
+
+impl<T> SerializeTree for BinaryTree<T>
+where
+    T: Display + FromStr,
+{
+    fn serialize(&self, format: Format) -> String {
+        serialize_nodes(self.root.as_deref(), format)
+    }
+
+    fn deserialize(s: &str, format: Format) -> Result<Self, ParseTreeError> {
+        let (root, length) = deserialize_nodes::<BTTree<T>>(s, format)?;
+        Ok(BinaryTree { root, length })
+    }
+}
+
+impl<T> SerializeTree for BinarySearchTree<T>
+where
+    T: Display + FromStr,
+{
+    fn serialize(&self, format: Format) -> String {
+        serialize_nodes(self.root.as_deref(), format)
+    }
+
+    fn deserialize(s: &str, format: Format) -> Result<Self, ParseTreeError> {
+        let (root, length) = deserialize_nodes::<BSTTree<T>>(s, format)?;
+        Ok(BinarySearchTree { root, length })
+    }
+}
+
+impl<T> SerializeTree for RCBinaryTree<T>
+where
+    T: Display + FromStr,
+{
+    fn serialize(&self, format: Format) -> String {
+        serialize_nodes(self.root.clone(), format)
+    }
+
+    fn deserialize(s: &str, format: Format) -> Result<Self, ParseTreeError> {
+        let (root, length) = deserialize_nodes::<RCBTTree<T>>(s, format)?;
+        Ok(RCBinaryTree { root, length })
+    }
+}
+
+impl<T> SerializeTree for RCBinarySearchTree<T>
+where
+    T: Display + FromStr,
+{
+    fn serialize(&self, format: Format) -> String {
+        serialize_nodes(self.root.clone(), format)
+    }
+
+    fn deserialize(s: &str, format: Format) -> Result<Self, ParseTreeError> {
+        let (root, length) = deserialize_nodes::<RCBSTTree<T>>(s, format)?;
+        Ok(RCBinarySearchTree { root, length })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binary_tree::Node;
+    use std::rc::Rc;
+
+    #[test]
+    fn serialize_binary_tree() {
+        let root = Node::new(1, Node::new(2, Node::new(3, None, None), None), None);
+        let bt = BinaryTree::<u32>::new(root);
+        assert_eq!(bt.serialize(Format::LevelOrder), "[1,2,null,3]");
+        assert_eq!(bt.serialize(Format::Preorder), "1,2,3,#,#,#,#");
+    }
+
+    #[test]
+    fn serialize_empty() {
+        let bt = BinaryTree::<u32>::new(None);
+        assert_eq!(bt.serialize(Format::LevelOrder), "[]");
+        assert_eq!(bt.serialize(Format::Preorder), "#");
+
+        let bt = BinaryTree::<u32>::deserialize("[]", Format::LevelOrder).unwrap();
+        assert!(bt.root.is_none());
+        let bt = BinaryTree::<u32>::deserialize("#", Format::Preorder).unwrap();
+        assert!(bt.root.is_none());
+    }
+
+    #[test]
+    fn deserialize_level_order() {
+        let bt = BinaryTree::<i32>::deserialize(
+            "[5, 4, 8, 11, null, 13, 4, 7, 2, null, null, 5, 1]",
+            Format::LevelOrder,
+        )
+        .unwrap();
+        assert_eq!(bt.length, 10);
+        let root = bt.root.as_ref().unwrap();
+        assert_eq!(root.data, 5);
+        let eleven = root.left.as_ref().unwrap().left.as_ref().unwrap();
+        assert_eq!(eleven.data, 11);
+        assert_eq!(eleven.right.as_ref().unwrap().data, 2);
+        let four = root.right.as_ref().unwrap().right.as_ref().unwrap();
+        assert_eq!(four.left.as_ref().unwrap().data, 5);
+        assert_eq!(four.right.as_ref().unwrap().data, 1);
+    }
+
+    #[test]
+    fn round_trip_all_trees() {
+        let fixtures = [
+            "[1]",
+            "[1,2,3]",
+            "[1,null,2,null,3]",
+            "[3,1,4,0,2,null,5]",
+            "[1,2,null,3,4,null,null,5]",
+        ];
+        for fixture in fixtures.iter() {
+            let bt = BinaryTree::<u32>::deserialize(fixture, Format::LevelOrder).unwrap();
+            assert_eq!(&bt.serialize(Format::LevelOrder), fixture);
+            let preorder = bt.serialize(Format::Preorder);
+
+            let bst = BinarySearchTree::<u32>::deserialize(&preorder, Format::Preorder).unwrap();
+            assert_eq!(&bst.serialize(Format::LevelOrder), fixture);
+            assert_eq!(bst.serialize(Format::Preorder), preorder);
+
+            let rcbt = RCBinaryTree::<u32>::deserialize(fixture, Format::LevelOrder).unwrap();
+            assert_eq!(rcbt.serialize(Format::Preorder), preorder);
+
+            let rcbst =
+                RCBinarySearchTree::<u32>::deserialize(&preorder, Format::Preorder).unwrap();
+            assert_eq!(&rcbst.serialize(Format::LevelOrder), fixture);
+            assert_eq!(rcbst.length, bt.length);
+        }
+    }
+
+    #[test]
+    fn deserialize_bst_is_searchable() {
+        let bst = BinarySearchTree::<u32>::deserialize("[5,3,9,1,4,null,10]", Format::LevelOrder)
+            .unwrap();
+        assert_eq!(bst.find(4), Some(4));
+        assert_eq!(bst.find(10), Some(10));
+        assert_eq!(bst.find(6), None);
+        assert_eq!(bst.get_height(), 3);
+    }
+
+    #[test]
+    fn deserialize_refcell_sets_parents() {
+        let rcbt = RCBinaryTree::<u32>::deserialize("[1,2,3,null,4]", Format::LevelOrder).unwrap();
+        let root = rcbt.root.clone().unwrap();
+        let two = root.borrow().left.clone().unwrap();
+        let four = two.borrow().right.clone().unwrap();
+        assert_eq!(four.borrow().data, 4);
+        assert!(Rc::ptr_eq(&four.borrow().parent().unwrap(), &two));
+        assert!(Rc::ptr_eq(&two.borrow().parent().unwrap(), &root));
+
+        let rcbst =
+            RCBinarySearchTree::<u32>::deserialize("2,1,#,#,3,#,#", Format::Preorder).unwrap();
+        let root = rcbst.root.clone().unwrap();
+        let three = root.borrow().right.clone().unwrap();
+        assert!(Rc::ptr_eq(&three.borrow().parent().unwrap(), &root));
+    }
+
+    #[test]
+    fn deserialize_errors() {
+        assert_eq!(
+            BinaryTree::<u32>::deserialize("1,2,3", Format::LevelOrder).err(),
+            Some(ParseTreeError::MissingBrackets)
+        );
+        assert_eq!(
+            BinaryTree::<u32>::deserialize("[1,two,3]", Format::LevelOrder).err(),
+            Some(ParseTreeError::InvalidValue("two".to_string()))
+        );
+        assert_eq!(
+            BinaryTree::<u32>::deserialize("[null,1]", Format::LevelOrder).err(),
+            Some(ParseTreeError::TrailingInput("1".to_string()))
+        );
+        assert_eq!(
+            BinaryTree::<u32>::deserialize("1,2,#", Format::Preorder).err(),
+            Some(ParseTreeError::UnexpectedEnd)
+        );
+        assert_eq!(
+            BinaryTree::<u32>::deserialize("1,#,#,4", Format::Preorder).err(),
+            Some(ParseTreeError::TrailingInput("4".to_string()))
+        );
+        assert_eq!(
+            BinaryTree::<u8>::deserialize("300,#,#", Format::Preorder).err(),
+            Some(ParseTreeError::InvalidValue("300".to_string()))
+        );
+    }
+}
@@ -0,0 +1,370 @@
+use std::cmp::Reverse;
+use std::collections::btree_map;
+use std::collections::BTreeMap;
+
+// A prefix tree over sequences of `K`, such as the chars of a string:
+// `trie.insert("cat".chars())`. Children are kept in a BTreeMap, so
+// keys always come out in sorted order.
+//
+// Every key also has a weight, which is how many times it was
+// inserted unless it's given explicitly. Autocomplete suggestions are
+// ranked by weight.
+#[derive(Debug, Clone)]
+pub struct Trie<K> {
+    root: TrieNode<K>,
+    len: usize,
+}
+
+#[derive(Debug, Clone)]
+struct TrieNode<K> {
+    children: BTreeMap<K, TrieNode<K>>,
+    // Zero if no key ends at this node:
+    weight: u64,
+}
+
+impl<K> TrieNode<K> {
+    fn new() -> Self {
+        TrieNode {
+            children: BTreeMap::new(),
+            weight: 0,
+        }
+    }
+
+    fn is_key(&self) -> bool {
+        self.weight > 0
+    }
+}
+
+impl<K> Default for Trie<K> {
+    fn default() -> Self {
+        Trie {
+            root: TrieNode::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<K> Trie<K>
+where
+    K: Ord + Clone,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // Returns true if the key wasn't already in the trie.
+    pub fn insert<I>(&mut self, key: I) -> bool
+    where
+        I: IntoIterator<Item = K>,
+    {
+        self.insert_with_weight(key, 1)
+    }
+
+    // Adds `weight` to the key's weight. A weight of zero doesn't add
+    // the key.
+    pub fn insert_with_weight<I>(&mut self, key: I, weight: u64) -> bool
+    where
+        I: IntoIterator<Item = K>,
+    {
+        if weight == 0 {
+            return false;
+        }
+        let mut node = &mut self.root;
+        for k in key {
+            node = node.children.entry(k).or_insert_with(TrieNode::new);
+        }
+        let is_new = !node.is_key();
+        node.weight = node.weight.saturating_add(weight);
+        if is_new {
+            self.len += 1;
+        }
+        is_new
+    }
+
+    fn find<I>(&self, key: I) -> Option<&TrieNode<K>>
+    where
+        I: IntoIterator<Item = K>,
+    {
+        let mut node = &self.root;
+        for k in key {
+            node = node.children.get(&k)?;
+        }
+        Some(node)
+    }
+
+    pub fn contains<I>(&self, key: I) -> bool
+    where
+        I: IntoIterator<Item = K>,
+    {
+        self.weight(key).is_some()
+    }
+
+    pub fn weight<I>(&self, key: I) -> Option<u64>
+    where
+        I: IntoIterator<Item = K>,
+    {
+        self.find(key).filter(|n| n.is_key()).map(|n| n.weight)
+    }
+
+    // Returns true if any key starts with the prefix.
+    pub fn starts_with<I>(&self, prefix: I) -> bool
+    where
+        I: IntoIterator<Item = K>,
+    {
+        match self.find(prefix) {
+            Some(node) => node.is_key() || !node.children.is_empty(),
+            None => false,
+        }
+    }
+
+    // Removes the key, and any branches that no longer lead to a key.
+    // Returns the key's weight, or None if it wasn't in the trie.
+    pub fn remove<I>(&mut self, key: I) -> Option<u64>
+    where
+        I: IntoIterator<Item = K>,
+    {
+        let key: Vec<K> = key.into_iter().collect();
+        let weight = remove_rec(&mut self.root, &key)?;
+        self.len -= 1;
+        Some(weight)
+    }
+
+    // Every key, in sorted order.
+    pub fn iter(&self) -> PrefixIter<'_, K> {
+        self.iter_prefix(Vec::new())
+    }
+
+    // Every key that starts with the prefix (including the prefix
+    // itself), in sorted order. Keys are produced lazily.
+    pub fn iter_prefix<I>(&self, prefix: I) -> PrefixIter<'_, K>
+    where
+        I: IntoIterator<Item = K>,
+    {
+        let key: Vec<K> = prefix.into_iter().collect();
+        let start = self.find(key.iter().cloned());
+        PrefixIter {
+            key,
+            start,
+            stack: Vec::new(),
+        }
+    }
+
+    // The longest prefix shared by every key. Empty if the trie is.
+    pub fn longest_common_prefix(&self) -> Vec<K> {
+        let mut prefix = Vec::new();
+        let mut node = &self.root;
+        // Stop where keys branch off, or where a key ends:
+        while node.children.len() == 1 && !node.is_key() {
+            let (k, child) = node.children.iter().next().unwrap();
+            prefix.push(k.clone());
+            node = child;
+        }
+        prefix
+    }
+
+    // Up to `limit` keys that start with the prefix, heaviest first.
+    // Keys with the same weight are in sorted order.
+    // time: O(m log(m)) for the m keys under the prefix
+    pub fn autocomplete<I>(&self, prefix: I, limit: usize) -> Vec<(Vec<K>, u64)>
+    where
+        I: IntoIterator<Item = K>,
+    {
+        let mut suggestions: Vec<(Vec<K>, u64)> = self.iter_prefix(prefix).collect();
+        // The sort is stable, so equal weights stay in key order:
+        suggestions.sort_by_key(|s| Reverse(s.1));
+        suggestions.truncate(limit);
+        suggestions
+    }
+}
+
+// Returns the removed key's weight, and prunes the path on the way
+// back up.
+fn remove_rec<K: Ord>(node: &mut TrieNode<K>, key: &[K]) -> Option<u64> {
+    match key.split_first() {
+        None => {
+            if !node.is_key() {
+                return None;
+            }
+            let weight = node.weight;
+            node.weight = 0;
+            Some(weight)
+        }
+        Some((k, rest)) => {
+            let child = node.children.get_mut(k)?;
+            let weight = remove_rec(child, rest)?;
+            if !child.is_key() && child.children.is_empty() {
+                node.children.remove(k);
+            }
+            Some(weight)
+        }
+    }
+}
+
+// A depth-first walk below the prefix, with one BTreeMap iterator per
+// level, so only the current path is held in memory.
+pub struct PrefixIter<'a, K> {
+    key: Vec<K>,
+    // The node at the prefix, until the walk starts:
+    start: Option<&'a TrieNode<K>>,
+    stack: Vec<btree_map::Iter<'a, K, TrieNode<K>>>,
+}
+
+impl<'a, K> Iterator for PrefixIter<'a, K>
+where
+    K: Clone,
+{
+    type Item = (Vec<K>, u64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(start) = self.start.take() {
+            self.stack.push(start.children.iter());
+            if start.is_key() {
+                return Some((self.key.clone(), start.weight));
+            }
+        }
+        loop {
+            let level = self.stack.last_mut()?;
+            match level.next() {
+                Some((k, child)) => {
+                    self.key.push(k.clone());
+                    self.stack.push(child.children.iter());
+                    if child.is_key() {
+                        return Some((self.key.clone(), child.weight));
+                    }
+                }
+                None => {
+                    self.stack.pop();
+                    // The prefix itself stays on the key:
+                    if !self.stack.is_empty() {
+                        self.key.pop();
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(trie: &Trie<char>, prefix: &str) -> Vec<String> {
+        trie.iter_prefix(prefix.chars())
+            .map(|(key, _)| key.into_iter().collect())
+            .collect()
+    }
+
+    fn fixture() -> Trie<char> {
+        let mut trie = Trie::new();
+        for word in ["car", "cart", "care", "cat", "dog", "do"].iter() {
+            trie.insert(word.chars());
+        }
+        trie
+    }
+
+    #[test]
+    fn insert_and_contains() {
+        let mut trie = fixture();
+        assert_eq!(trie.len(), 6);
+        assert!(trie.contains("cart".chars()));
+        assert!(!trie.contains("ca".chars()));
+        assert!(trie.starts_with("ca".chars()));
+        assert!(!trie.starts_with("cow".chars()));
+        assert!(!trie.insert("car".chars()));
+        assert_eq!(trie.len(), 6);
+        assert_eq!(trie.weight("car".chars()), Some(2));
+        assert_eq!(trie.weight("ca".chars()), None);
+
+        // The empty key:
+        assert!(!trie.contains("".chars()));
+        assert!(trie.insert("".chars()));
+        assert!(trie.contains("".chars()));
+    }
+
+    #[test]
+    fn prefix_iteration_is_sorted() {
+        let trie = fixture();
+        assert_eq!(words(&trie, "ca"), vec!["car", "care", "cart", "cat"]);
+        assert_eq!(words(&trie, "car"), vec!["car", "care", "cart"]);
+        assert_eq!(words(&trie, "do"), vec!["do", "dog"]);
+        assert!(words(&trie, "x").is_empty());
+        assert_eq!(trie.iter().count(), 6);
+    }
+
+    #[test]
+    fn remove_prunes_branches() {
+        let mut trie = fixture();
+        assert_eq!(trie.remove("cart".chars()), Some(1));
+        assert_eq!(trie.remove("cart".chars()), None);
+        assert_eq!(trie.remove("ca".chars()), None);
+        assert_eq!(words(&trie, "car"), vec!["car", "care"]);
+        assert_eq!(trie.len(), 5);
+
+        // "car" is a prefix of "care", so the path stays:
+        trie.remove("car".chars());
+        assert!(trie.starts_with("car".chars()));
+        trie.remove("care".chars());
+        assert!(!trie.starts_with("car".chars()));
+        assert!(trie.starts_with("ca".chars()));
+        trie.remove("cat".chars());
+        assert!(!trie.starts_with("c".chars()));
+        assert_eq!(trie.len(), 2);
+    }
+
+    #[test]
+    fn longest_common_prefix() {
+        let mut trie = Trie::new();
+        assert!(trie.longest_common_prefix().is_empty());
+        trie.insert("flower".chars());
+        assert_eq!(trie.longest_common_prefix().len(), 6);
+        trie.insert("flow".chars());
+        trie.insert("flight".chars());
+        let lcp: String = trie.longest_common_prefix().into_iter().collect();
+        assert_eq!(lcp, "fl");
+        // A key that's a prefix of the others ends the common prefix:
+        trie.insert("f".chars());
+        assert_eq!(trie.longest_common_prefix(), vec!['f']);
+        trie.insert("z".chars());
+        assert!(trie.longest_common_prefix().is_empty());
+    }
+
+    #[test]
+    fn autocomplete_by_weight() {
+        let mut trie = fixture();
+        trie.insert_with_weight("cat".chars(), 10);
+        trie.insert_with_weight("care".chars(), 3);
+        let suggestions: Vec<(String, u64)> = trie
+            .autocomplete("ca".chars(), 3)
+            .into_iter()
+            .map(|(key, weight)| (key.into_iter().collect(), weight))
+            .collect();
+        assert_eq!(
+            suggestions,
+            vec![
+                ("cat".to_string(), 11),
+                ("care".to_string(), 4),
+                ("car".to_string(), 1)
+            ]
+        );
+        assert!(trie.autocomplete("z".chars(), 3).is_empty());
+    }
+
+    #[test]
+    fn non_char_keys() {
+        let mut trie = Trie::new();
+        trie.insert(vec![1, 2, 3]);
+        trie.insert(vec![1, 2, 4]);
+        trie.insert(vec![1, 5]);
+        assert_eq!(trie.longest_common_prefix(), vec![1]);
+        let keys: Vec<Vec<u8>> = trie.iter_prefix(vec![1, 2]).map(|(k, _)| k).collect();
+        assert_eq!(keys, vec![vec![1, 2, 3], vec![1, 2, 4]]);
+    }
+}